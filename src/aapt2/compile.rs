@@ -1,6 +1,9 @@
 use super::aapt2_tool;
-use crate::error::*;
-use std::path::{Path, PathBuf};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 /// # Compile
 /// `AAPT2` supports compilation of all Android resource types, such as drawables and XML
@@ -187,8 +190,49 @@ impl Aapt2Compile {
         self
     }
 
+    /// Returns copy of the options that compiles given resource file into the output
+    /// directory
+    pub(crate) fn with_input(&self, res_path: Option<&Path>, compiled_res: &Path) -> Self {
+        Self {
+            res_path: res_path.map(ToOwned::to_owned),
+            compiled_res: compiled_res.to_owned(),
+            res_dir: None,
+            res_zip: None,
+            ..self.clone()
+        }
+    }
+
     /// Executes aapt2 compile with arguments
     pub fn run(&self) -> Result<PathBuf> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        self.command()?.output_with(options)?;
+        Ok(self.compiled_res.clone())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<PathBuf> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(self.compiled_res.clone())
+    }
+
+    /// Builds aapt2 compile command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
         let mut aapt2 = aapt2_tool()?;
         aapt2.arg("compile");
         if let Some(res_path) = &self.res_path {
@@ -237,7 +281,11 @@ impl Aapt2Compile {
         if self.help {
             aapt2.arg("-h");
         }
-        aapt2.output_err(true)?;
-        Ok(self.compiled_res.clone())
+        Ok(aapt2)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
@@ -1,6 +1,11 @@
 use super::aapt2_tool;
-use crate::error::{CommandExt, Result};
+use crate::{
+    command_line::CommandLine,
+    error::{CommandExt, Result},
+    executor::ExecOptions,
+};
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Converts an apk between binary and proto formats.
 #[derive(Default)]
@@ -56,6 +61,35 @@ impl Aapt2Convert {
 
     /// Executes aapt2 convert with arguments
     pub fn run(&self) -> Result<()> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
         let mut aapt2 = aapt2_tool()?;
         aapt2.arg("convert");
         aapt2.arg("-o").arg(&self.output_path);
@@ -74,8 +108,12 @@ impl Aapt2Convert {
         if self.help {
             aapt2.arg("-h");
         }
-        aapt2.output_err(true)?;
-        Ok(())
+        Ok(aapt2)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
 
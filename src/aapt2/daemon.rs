@@ -1,38 +1,219 @@
-use super::aapt2_tool;
+use super::{aapt2_tool, Aapt2Compile, Aapt2Link};
 use crate::error::*;
-use std::path::{Path, PathBuf};
+use std::{
+    ffi::OsStr,
+    io::{BufRead, BufReader, Lines, Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStderr, ChildStdin, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// Time the daemon is given to exit after it's asked to quit before it's killed
+const QUIT_GRACE_PERIOD: Duration = Duration::from_secs(2);
 
 /// Runs aapt in daemon mode. Each subsequent line is a single parameter to the
 /// command. The end of an invocation is signaled by providing an empty line.
 pub struct Aapt2Daemon {
-    trace_folder: PathBuf,
-    help: bool,
+    trace_folder: Option<PathBuf>,
 }
 
 impl Aapt2Daemon {
     /// Initialize aapt2 daemon and then specifies path to trace folder
     pub fn new(trace_folder: &Path) -> Self {
         Self {
-            trace_folder: trace_folder.to_owned(),
-            help: false,
+            trace_folder: Some(trace_folder.to_owned()),
         }
     }
 
-    /// Displays this help menu
-    pub fn help(&mut self, help: bool) -> &mut Self {
-        self.help = help;
-        self
+    /// Starts aapt2 daemon and waits until it's ready to accept invocations
+    pub fn start(&self) -> Result<Aapt2DaemonSession> {
+        let mut aapt2 = aapt2_tool()?;
+        aapt2.arg("daemon");
+        if let Some(trace_folder) = &self.trace_folder {
+            aapt2.arg("--trace-folder").arg(trace_folder);
+        }
+        Aapt2DaemonSession::spawn(aapt2)
     }
+}
+
+/// Running `aapt2 daemon` process that executes compile and link invocations without
+/// spawning a new process for each of them.
+///
+/// Every argument of the invocation is sent on its own line and the invocation ends with
+/// an empty line. The daemon prints diagnostics of the invocation to stderr followed by
+/// `Error` if it failed and `Done` when it's finished. The daemon is asked to quit when
+/// the session is dropped, and killed if it doesn't exit shortly.
+///
+/// ```no_run
+/// use android_tools::aapt2::{Aapt2, Aapt2DaemonSession};
+/// use std::path::Path;
+///
+/// let mut daemon = Aapt2DaemonSession::start().unwrap();
+/// for res in ["res/values/strings.xml", "res/layout/main.xml"] {
+///     let compile = Aapt2.compile_incremental(Path::new(res), Path::new("compiled"));
+///     let result = daemon.compile(&compile).unwrap();
+///     assert!(result.success, "{:?}", result.diagnostics);
+/// }
+/// ```
+pub struct Aapt2DaemonSession {
+    command: Command,
+    started: Instant,
+    child: Child,
+    stdin: ChildStdin,
+    stderr: Lines<BufReader<ChildStderr>>,
+}
 
-    /// Executes aapt2 daemon with arguments
-    pub fn run(&self) -> Result<()> {
+/// Result of a single invocation executed by aapt2 daemon
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaemonResult {
+    /// Whether the invocation was successful
+    pub success: bool,
+    /// Diagnostics printed by aapt2 during the invocation
+    pub diagnostics: Vec<String>,
+}
+
+impl Aapt2DaemonSession {
+    /// Starts aapt2 daemon found in the SDK and waits until it's ready to accept
+    /// invocations
+    pub fn start() -> Result<Self> {
         let mut aapt2 = aapt2_tool()?;
         aapt2.arg("daemon");
-        aapt2.arg(&self.trace_folder);
-        if self.help {
-            aapt2.arg("-h");
+        Self::spawn(aapt2)
+    }
+
+    fn spawn(mut aapt2: Command) -> Result<Self> {
+        let started = Instant::now();
+        // Spawned directly instead of by the executor, as invocations are written to
+        // stdin of the daemon
+        let mut child = aapt2
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("aapt2 daemon stdin is piped");
+        let stderr = child.stderr.take().expect("aapt2 daemon stderr is piped");
+        let mut stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        let mut line = String::new();
+        while line.trim_end() != "Ready" {
+            line.clear();
+            if stdout.read_line(&mut line)? == 0 {
+                let mut stderr_output = String::new();
+                BufReader::new(stderr).read_to_string(&mut stderr_output)?;
+                let status = child.wait()?;
+                let duration = started.elapsed();
+                return Err(
+                    CommandFailure::new(&aapt2, status, line, stderr_output, duration).into(),
+                );
+            }
+        }
+        // Output printed to stdout isn't used, but the pipe must be drained to not block
+        // the daemon
+        std::thread::spawn(move || std::io::copy(&mut stdout, &mut std::io::sink()));
+        Ok(Self {
+            command: aapt2,
+            started,
+            child,
+            stdin,
+            stderr: BufReader::new(stderr).lines(),
+        })
+    }
+
+    /// Compiles resources with given aapt2 compile arguments
+    pub fn compile(&mut self, compile: &Aapt2Compile) -> Result<DaemonResult> {
+        let command = compile.command()?;
+        self.invoke(command.get_args())
+    }
+
+    /// Links resources with given aapt2 link arguments
+    pub fn link(&mut self, link: &Aapt2Link) -> Result<DaemonResult> {
+        let command = link.command()?;
+        self.invoke(command.get_args())
+    }
+
+    /// Executes aapt2 invocation with given arguments, starting with the subcommand,
+    /// e.g. `["compile", "res/values/strings.xml", "-o", "compiled"]`
+    pub fn invoke<I, S>(&mut self, args: I) -> Result<DaemonResult>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut invocation = String::new();
+        for arg in args {
+            let arg = arg.as_ref().to_str().ok_or_else(|| {
+                Error::InvalidArguments(format!("{:?} is not valid unicode", arg.as_ref()))
+            })?;
+            if arg.contains('\n') || arg.is_empty() {
+                return Err(Error::InvalidArguments(format!(
+                    "aapt2 daemon can't pass empty or multi-line argument {:?}",
+                    arg
+                )));
+            }
+            invocation.push_str(arg);
+            invocation.push('\n');
+        }
+        if invocation.is_empty() {
+            return Err(Error::InvalidArguments(
+                "aapt2 daemon invocation is empty".to_owned(),
+            ));
         }
-        aapt2.output_err(true)?;
+        invocation.push('\n');
+        self.stdin.write_all(invocation.as_bytes())?;
+        self.stdin.flush()?;
+        let mut result = DaemonResult {
+            success: true,
+            diagnostics: Vec::new(),
+        };
+        loop {
+            match self.stderr.next().transpose()? {
+                Some(line) if line == "Done" => return Ok(result),
+                Some(line) if line == "Error" => result.success = false,
+                Some(line) => result.diagnostics.push(line),
+                None => {
+                    // The daemon exited in the middle of the invocation
+                    let status = self.child.wait()?;
+                    return Err(CommandFailure::new(
+                        &self.command,
+                        status,
+                        String::new(),
+                        result.diagnostics.join("\n"),
+                        self.started.elapsed(),
+                    )
+                    .into());
+                }
+            }
+        }
+    }
+
+    /// Asks the daemon to quit and waits for it to exit. The daemon is killed if it
+    /// doesn't exit shortly
+    pub fn shutdown(mut self) -> Result<()> {
+        self.quit()
+    }
+
+    fn quit(&mut self) -> Result<()> {
+        if self.child.try_wait()?.is_some() {
+            return Ok(());
+        }
+        if self.stdin.write_all(b"quit\n\n").is_ok() {
+            let asked = Instant::now();
+            while asked.elapsed() < QUIT_GRACE_PERIOD {
+                if self.child.try_wait()?.is_some() {
+                    return Ok(());
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+        self.child.kill()?;
+        self.child.wait()?;
         Ok(())
     }
 }
+
+impl Drop for Aapt2DaemonSession {
+    fn drop(&mut self) {
+        if self.quit().is_err() {
+            self.child.kill().ok();
+            self.child.wait().ok();
+        }
+    }
+}
@@ -1,6 +1,14 @@
-use super::aapt2_tool;
-use crate::error::*;
-use std::path::PathBuf;
+use super::{aapt2_tool, Aapt2Dump, Badging, SubCommand};
+use crate::{
+    command_line::CommandLine,
+    error::*,
+    executor::{current_executor, ExecOptions},
+};
+use std::{
+    path::PathBuf,
+    process::{Command, Output},
+    time::Duration,
+};
 
 /// Prints the differences in resources of two apks.
 pub struct Aapt2Diff {
@@ -23,8 +31,90 @@ impl Aapt2Diff {
         self
     }
 
-    /// Executes aapt2 diff with arguments
-    pub fn run(&self) -> Result<()> {
+    /// Executes aapt2 diff with arguments and returns differences between the first and
+    /// the second APK. Differences in the manifest are found by comparing
+    /// `aapt2 dump badging` of both APKs
+    pub fn run(&self) -> Result<ResourceDiff> {
+        self.run_with(&ExecOptions::new())
+    }
+
+    /// Runs the commands with given execution options, e.g. a timeout for each of them
+    pub fn run_with(&self, options: &ExecOptions) -> Result<ResourceDiff> {
+        let (old_apk, new_apk) = self.apks()?;
+        let mut aapt2 = self.command()?;
+        let started = std::time::Instant::now();
+        let output = current_executor().execute(&mut aapt2, options)?;
+        let mut diff = self.parse_output(&aapt2, output, started.elapsed())?;
+        let badging = |apk| Aapt2Dump::new(SubCommand::Badging, apk).run_with(options);
+        let old_badging = Badging::parse(&badging(old_apk)?)?;
+        let new_badging = Badging::parse(&badging(new_apk)?)?;
+        diff.manifest = ManifestChange::compare(&old_badging, &new_badging);
+        Ok(diff)
+    }
+
+    /// Executes aapt2 diff asynchronously. The child processes are killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<ResourceDiff> {
+        self.run_async_with(&ExecOptions::new()).await
+    }
+
+    /// Executes aapt2 diff asynchronously with given execution options, e.g. a timeout
+    /// for each of the commands. The child processes are killed when the future is
+    /// dropped or the timeout elapses for any of them
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<ResourceDiff> {
+        let (old_apk, new_apk) = self.apks()?;
+        let started = std::time::Instant::now();
+        let (aapt2, output) = crate::async_command::output_async(self.command()?, options).await?;
+        let mut diff = self.parse_output(&aapt2, output, started.elapsed())?;
+        let old_badging = Aapt2Dump::new(SubCommand::Badging, old_apk)
+            .run_async_with(options)
+            .await?;
+        let new_badging = Aapt2Dump::new(SubCommand::Badging, new_apk)
+            .run_async_with(options)
+            .await?;
+        diff.manifest = ManifestChange::compare(
+            &Badging::parse(&old_badging)?,
+            &Badging::parse(&new_badging)?,
+        );
+        Ok(diff)
+    }
+
+    fn apks(&self) -> Result<(&PathBuf, &PathBuf)> {
+        match self.input_apks.as_slice() {
+            [old_apk, new_apk] => Ok((old_apk, new_apk)),
+            _ => Err(Error::InvalidArguments(format!(
+                "aapt2 diff requires two APKs, {} given",
+                self.input_apks.len()
+            ))),
+        }
+    }
+
+    /// Parses differences. aapt2 diff exits with code 1 when the APKs are different, so
+    /// it's treated as a failure only if no differences were printed
+    fn parse_output(
+        &self,
+        aapt2: &Command,
+        output: Output,
+        duration: Duration,
+    ) -> Result<ResourceDiff> {
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let diff = ResourceDiff::parse(&stderr, &self.input_apks);
+        if !output.status.success() && diff.is_identical() {
+            return Err(CommandFailure::new(aapt2, output.status, stdout, stderr, duration).into());
+        }
+        Ok(diff)
+    }
+
+    /// Returns program and arguments of `aapt2 diff`, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+
+    /// Builds the command with arguments
+    fn command(&self) -> Result<Command> {
         let mut aapt2 = aapt2_tool()?;
         aapt2.arg("diff");
         self.input_apks.iter().for_each(|input_apks| {
@@ -33,7 +123,216 @@ impl Aapt2Diff {
         if self.help {
             aapt2.arg("-h");
         }
-        aapt2.output_err(true)?;
-        Ok(())
+        Ok(aapt2)
+    }
+}
+
+/// Differences between two APKs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceDiff {
+    /// Packages, types, resources and configurations present only in the new APK
+    pub added: Vec<ResourceChange>,
+    /// Packages, types, resources and configurations present only in the old APK
+    pub removed: Vec<ResourceChange>,
+    /// Resource values that are different in the new APK
+    pub changed: Vec<ValueChange>,
+    /// Differences of the manifests
+    pub manifest: Vec<ManifestChange>,
+    /// Other differences reported by aapt2, like changed visibility or resource id
+    pub other: Vec<String>,
+}
+
+/// Package, type, resource or resource configuration that was added or removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceChange {
+    /// Name in the `package:type/name` form, e.g. `com.example:string/app_name`
+    pub name: String,
+    /// Configuration of the resource value, if only this configuration was changed
+    pub config: Option<String>,
+}
+
+/// Resource value that was changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueChange {
+    /// Name in the `package:type/name` form, e.g. `com.example:string/app_name`
+    pub name: String,
+    pub config: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+/// Manifest attribute that was changed. Values of list attributes, like
+/// `uses-permission`, are reported one by one as added or removed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestChange {
+    pub attribute: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl ResourceDiff {
+    /// Returns `true` if no differences were found
+    pub fn is_identical(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.manifest.is_empty()
+            && self.other.is_empty()
+    }
+
+    /// Parses differences printed by `aapt2 diff` for given input APKs. Every message
+    /// starts with the path of the APK it's reported for, e.g.
+    ///
+    /// ```sh
+    /// new.apk: new entry com.example:string/title
+    /// new.apk: value com.example:string/app_name config= does not match:
+    /// "Example"
+    ///  vs
+    /// "Example app"
+    /// ```
+    pub fn parse(output: &str, input_apks: &[PathBuf]) -> ResourceDiff {
+        let prefixes = input_apks
+            .iter()
+            .map(|apk| format!("{}: ", apk.display()))
+            .collect::<Vec<_>>();
+        let mut messages: Vec<String> = Vec::new();
+        for line in output.lines() {
+            match prefixes
+                .iter()
+                .find(|prefix| line.starts_with(prefix.as_str()))
+            {
+                Some(prefix) => messages.push(line[prefix.len()..].to_owned()),
+                None => match messages.last_mut() {
+                    Some(message) => {
+                        message.push('\n');
+                        message.push_str(line);
+                    }
+                    None => continue,
+                },
+            }
+        }
+        let mut diff = ResourceDiff::default();
+        for message in messages {
+            if let Some(value) = message.strip_prefix("value ") {
+                diff.changed.push(parse_value_change(value));
+            } else if let Some(name) = message.strip_prefix("missing ") {
+                diff.removed.push(parse_resource_change(name));
+            } else if let Some(name) = ["new entry ", "new config ", "new type ", "new package "]
+                .iter()
+                .find_map(|prefix| message.strip_prefix(prefix))
+            {
+                diff.added.push(parse_resource_change(name));
+            } else {
+                diff.other.push(message);
+            }
+        }
+        diff
+    }
+}
+
+impl ManifestChange {
+    /// Compares manifest information of the old and the new APK
+    pub fn compare(old: &Badging, new: &Badging) -> Vec<ManifestChange> {
+        let mut changes = Vec::new();
+        let mut compare = |attribute: &str, old: Option<String>, new: Option<String>| {
+            if old != new {
+                changes.push(ManifestChange {
+                    attribute: attribute.to_owned(),
+                    old_value: old,
+                    new_value: new,
+                });
+            }
+        };
+        let to_string = |value: Option<u32>| value.map(|value| value.to_string());
+        compare(
+            "package",
+            Some(old.package.clone()),
+            Some(new.package.clone()),
+        );
+        compare(
+            "versionCode",
+            to_string(old.version_code),
+            to_string(new.version_code),
+        );
+        compare(
+            "versionName",
+            old.version_name.clone(),
+            new.version_name.clone(),
+        );
+        compare(
+            "sdkVersion",
+            to_string(old.sdk_version),
+            to_string(new.sdk_version),
+        );
+        compare(
+            "targetSdkVersion",
+            to_string(old.target_sdk_version),
+            to_string(new.target_sdk_version),
+        );
+        compare(
+            "launchable-activity",
+            old.launchable_activity.clone(),
+            new.launchable_activity.clone(),
+        );
+        let densities = |badging: &Badging| {
+            badging
+                .densities
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        let lists = [
+            (
+                "uses-permission",
+                &old.uses_permissions,
+                &new.uses_permissions,
+            ),
+            ("native-code", &old.native_code, &new.native_code),
+            ("locales", &old.locales, &new.locales),
+            ("densities", &densities(old), &densities(new)),
+        ];
+        for (attribute, old_values, new_values) in lists {
+            for value in old_values
+                .iter()
+                .filter(|value| !new_values.contains(value))
+            {
+                compare(attribute, Some(value.clone()), None);
+            }
+            for value in new_values
+                .iter()
+                .filter(|value| !old_values.contains(value))
+            {
+                compare(attribute, None, Some(value.clone()));
+            }
+        }
+        changes
+    }
+}
+
+/// Parses `com.example:string/app_name config=fr`
+fn parse_resource_change(name: &str) -> ResourceChange {
+    match name.split_once(" config=") {
+        Some((name, config)) => ResourceChange {
+            name: name.to_owned(),
+            config: Some(config.to_owned()),
+        },
+        None => ResourceChange {
+            name: name.to_owned(),
+            config: None,
+        },
+    }
+}
+
+/// Parses `com.example:string/app_name config=fr does not match:\n<old>\n vs \n<new>`
+fn parse_value_change(value: &str) -> ValueChange {
+    let (header, values) = value.split_once('\n').unwrap_or((value, ""));
+    let header = header.trim_end_matches(" does not match:");
+    let change = parse_resource_change(header);
+    let (old_value, new_value) = values.split_once("\n vs \n").unwrap_or((values, ""));
+    ValueChange {
+        name: change.name,
+        config: change.config.unwrap_or_default(),
+        old_value: old_value.trim().to_owned(),
+        new_value: new_value.trim().to_owned(),
     }
 }
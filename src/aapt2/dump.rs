@@ -1,6 +1,9 @@
-use super::aapt2_tool;
-use crate::error::*;
-use std::path::{Path, PathBuf};
+use super::{aapt2_tool, parse_configurations, parse_permissions, Badging, ResourceTable};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 /// # Dump
 /// Dump is used for printing information about the APK you generated using the link
@@ -61,11 +64,78 @@ impl Aapt2Dump {
         self
     }
 
-    /// Executes aapt2 dump with arguments
-    pub fn run(&self) -> Result<()> {
+    /// Executes aapt2 dump with arguments and returns the printed output
+    pub fn run(&self) -> Result<String> {
+        self.output(self.subcommand)
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<String> {
+        let output = self.command(self.subcommand)?.output_with(options)?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Executes aapt2 dump asynchronously and returns the printed output. The child
+    /// process is killed when the future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<String> {
+        self.run_async_with(&ExecOptions::new()).await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<String> {
+        let command = self.command(self.subcommand)?;
+        let output = crate::async_command::output_err_async(command, options).await?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Executes `aapt2 dump badging` and parses information extracted from the APK's
+    /// manifest
+    pub fn badging(&self) -> Result<Badging> {
+        Badging::parse(&self.output(SubCommand::Badging)?)
+    }
+
+    /// Executes `aapt2 dump permissions` and returns permissions used by the application
+    pub fn permissions(&self) -> Result<Vec<String>> {
+        Ok(parse_permissions(&self.output(SubCommand::Permissions)?))
+    }
+
+    /// Executes `aapt2 dump packagename` and returns the APK's package name
+    pub fn packagename(&self) -> Result<String> {
+        Ok(self.output(SubCommand::Packagename)?.trim().to_owned())
+    }
+
+    /// Executes `aapt2 dump resources` and parses the APK's resource table
+    pub fn resources(&self) -> Result<ResourceTable> {
+        ResourceTable::parse(&self.output(SubCommand::Resources)?)
+    }
+
+    /// Executes `aapt2 dump configurations` and returns every configuration used by the
+    /// APK's resources
+    pub fn configurations(&self) -> Result<Vec<String>> {
+        Ok(parse_configurations(
+            &self.output(SubCommand::Configurations)?,
+        ))
+    }
+
+    /// Returns program and arguments `run` executes, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command(self.subcommand)?))
+    }
+
+    fn output(&self, subcommand: SubCommand) -> Result<String> {
+        let output = self.command(subcommand)?.output_err(false)?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Builds the command with arguments for given subcommand
+    fn command(&self, subcommand: SubCommand) -> Result<Command> {
         let mut aapt2 = aapt2_tool()?;
         aapt2.arg("dump");
-        aapt2.arg(self.subcommand.to_string());
+        aapt2.arg(subcommand.to_string());
         aapt2.arg(&self.filename_apk);
         if self.no_values {
             aapt2.arg("--no-values");
@@ -79,13 +149,14 @@ impl Aapt2Dump {
         if self.help {
             aapt2.arg("-h");
         }
-        aapt2.output_err(true)?;
-        Ok(())
+        Ok(aapt2)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SubCommand {
     /// Print the contents of the AAPT2 Container (APC) generated during compilation.
+    #[default]
     Apc,
     /// Print information extracted from the APK's manifest.
     Badging,
@@ -123,9 +194,3 @@ impl std::fmt::Display for SubCommand {
         }
     }
 }
-
-impl Default for SubCommand {
-    fn default() -> Self {
-        Self::Apc
-    }
-}
@@ -0,0 +1,129 @@
+use crate::error::*;
+
+/// Information extracted from the APK's manifest by `aapt2 dump badging`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Badging {
+    /// Package name of the application
+    pub package: String,
+    pub version_code: Option<u32>,
+    pub version_name: Option<String>,
+    /// Minimum SDK version the application runs on
+    pub sdk_version: Option<u32>,
+    pub target_sdk_version: Option<u32>,
+    /// Name of the activity started by the launcher
+    pub launchable_activity: Option<String>,
+    /// ABIs of the native libraries packaged in the APK
+    pub native_code: Vec<String>,
+    pub locales: Vec<String>,
+    pub densities: Vec<u32>,
+    pub uses_permissions: Vec<String>,
+}
+
+impl Badging {
+    /// Parses output of `aapt2 dump badging`, e.g.
+    ///
+    /// ```sh
+    /// package: name='com.example' versionCode='1' versionName='1.0'
+    /// sdkVersion:'21'
+    /// targetSdkVersion:'30'
+    /// uses-permission: name='android.permission.INTERNET'
+    /// launchable-activity: name='android.app.NativeActivity'  label='' icon=''
+    /// locales: '--_--'
+    /// densities: '160' '240'
+    /// native-code: 'arm64-v8a'
+    /// ```
+    pub fn parse(output: &str) -> Result<Badging> {
+        let mut badging = Badging::default();
+        let mut package = None;
+        for line in output.lines() {
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+            match key {
+                "package" => {
+                    let attributes = attributes(value);
+                    package = find(&attributes, "name").map(ToOwned::to_owned);
+                    badging.version_code = find(&attributes, "versionCode")
+                        .and_then(|version_code| version_code.parse().ok());
+                    badging.version_name = find(&attributes, "versionName").map(ToOwned::to_owned);
+                }
+                "sdkVersion" | "minSdkVersion" => {
+                    badging.sdk_version = quoted(value).first().and_then(|v| v.parse().ok())
+                }
+                "targetSdkVersion" => {
+                    badging.target_sdk_version = quoted(value).first().and_then(|v| v.parse().ok())
+                }
+                "uses-permission" | "uses-permission-sdk-23" => {
+                    if let Some(name) = find(&attributes(value), "name") {
+                        badging.uses_permissions.push(name.to_owned());
+                    }
+                }
+                "launchable-activity" => {
+                    badging.launchable_activity =
+                        find(&attributes(value), "name").map(ToOwned::to_owned)
+                }
+                "native-code" => badging.native_code = to_owned(quoted(value)),
+                "locales" => badging.locales = to_owned(quoted(value)),
+                "densities" => {
+                    badging.densities = quoted(value)
+                        .into_iter()
+                        .filter_map(|density| density.parse().ok())
+                        .collect()
+                }
+                _ => (),
+            }
+        }
+        badging.package = package.ok_or_else(|| {
+            Error::OutputParseFailed("package name is missing in badging".to_owned())
+        })?;
+        Ok(badging)
+    }
+}
+
+/// Parses output of `aapt2 dump permissions` into the list of permissions used by the
+/// application, e.g.
+///
+/// ```sh
+/// package: com.example
+/// uses-permission: name='android.permission.INTERNET'
+/// ```
+pub fn parse_permissions(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(key, _)| matches!(key.trim(), "uses-permission" | "uses-permission-sdk-23"))
+        .filter_map(|(_, value)| find(&attributes(value), "name").map(ToOwned::to_owned))
+        .collect()
+}
+
+/// Splits `name='value' other='value'` into key-value pairs. Values may contain spaces
+fn attributes(line: &str) -> Vec<(&str, &str)> {
+    let mut attributes = Vec::new();
+    let mut rest = line;
+    while let Some((key, value)) = rest.split_once("='") {
+        let (value, tail) = match value.find('\'') {
+            Some(end) => (&value[..end], &value[end + 1..]),
+            None => (value, ""),
+        };
+        attributes.push((key.trim(), value));
+        rest = tail;
+    }
+    attributes
+}
+
+fn find<'a>(attributes: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| *value)
+}
+
+/// Splits `'first' 'second'` into the list of values
+fn quoted(line: &str) -> Vec<&str> {
+    line.split('\'').skip(1).step_by(2).collect()
+}
+
+fn to_owned(values: Vec<&str>) -> Vec<String> {
+    values.into_iter().map(ToOwned::to_owned).collect()
+}
@@ -0,0 +1,246 @@
+use super::Aapt2Compile;
+use crate::{
+    error::*,
+    executor::{current_executor, with_executor, ExecOptions},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Name of the file in the output directory that keeps hashes of compiled resources
+const CACHE_FILE: &str = ".aapt2_incremental";
+
+/// # Incremental compile
+/// Walks the `res` directory and compiles only resource files changed since the
+/// previous run, several files at once. Every resource file is compiled by its own
+/// `aapt2 compile` invocation, so the output is the same as compiling the files one by
+/// one with [`Aapt2Compile::new`].
+///
+/// Hashes of compiled files are kept in the output directory between runs. Compiled
+/// files whose sources were removed are deleted, and all resources are recompiled when
+/// compile options change.
+///
+/// ```no_run
+/// use android_tools::aapt2::Aapt2;
+/// use std::path::Path;
+///
+/// let report = Aapt2
+///     .compile_changed(Path::new("res"), Path::new("compiled_res"))
+///     .run()
+///     .unwrap();
+/// println!("{} compiled, {} up to date", report.compiled.len(), report.unchanged.len());
+/// ```
+#[derive(Clone, Default)]
+pub struct Aapt2IncrementalCompile {
+    res_dir: PathBuf,
+    compiled_res: PathBuf,
+    options: Aapt2Compile,
+    jobs: Option<usize>,
+}
+
+/// Result of the incremental compilation
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IncrementalCompileReport {
+    /// Resource files that were compiled
+    pub compiled: Vec<PathBuf>,
+    /// Resource files that were up to date
+    pub unchanged: Vec<PathBuf>,
+    /// Compiled files deleted because their sources were removed
+    pub removed: Vec<PathBuf>,
+    /// All compiled files of the `res` directory, ready to be linked
+    pub flat_files: Vec<PathBuf>,
+}
+
+impl Aapt2IncrementalCompile {
+    /// Initialize incremental compile then specifies the `res` directory to scan for
+    /// resources and the output directory for the compiled resources
+    pub fn new(res_dir: &Path, compiled_res: &Path) -> Self {
+        Self {
+            res_dir: res_dir.to_owned(),
+            compiled_res: compiled_res.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Specifies compile options, like `--no-crunch` or `--legacy`, used for every
+    /// resource file. Inputs and output of the given compile are ignored
+    pub fn options(&mut self, options: &Aapt2Compile) -> &mut Self {
+        self.options = options.clone();
+        self
+    }
+
+    /// Maximum number of resource files compiled at once. Defaults to the number of
+    /// available CPUs
+    pub fn jobs(&mut self, jobs: usize) -> &mut Self {
+        self.jobs = Some(jobs.max(1));
+        self
+    }
+
+    /// Compiles changed resources and removes stale compiled files. Fails with
+    /// [`Error::CompileFailed`] listing every resource that failed to compile. Their
+    /// hashes are not saved, so they are compiled again on the next run
+    pub fn run(&self) -> Result<IncrementalCompileReport> {
+        std::fs::create_dir_all(&self.compiled_res)?;
+        let options = options_hash(&self.options)?;
+        let cache_file = self.compiled_res.join(CACHE_FILE);
+        let cache = read_cache(&cache_file, &options)?;
+        let sources = self.sources()?;
+
+        let mut report = IncrementalCompileReport::default();
+        for name in cache.keys().filter(|name| !sources.contains_key(*name)) {
+            let flat_file = self.compiled_res.join(flat_file_name(name));
+            if flat_file.exists() {
+                std::fs::remove_file(&flat_file)?;
+                report.removed.push(flat_file);
+            }
+        }
+        let (unchanged, changed): (Vec<_>, Vec<_>) = sources.iter().partition(|(name, hash)| {
+            cache.get(*name) == Some(&Some(hash.to_string()))
+                && self.compiled_res.join(flat_file_name(name)).exists()
+        });
+
+        let names = changed
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        let results = self.compile(&names);
+        let mut new_cache = BTreeMap::new();
+        let mut failures = Vec::new();
+        for (name, hash) in &unchanged {
+            new_cache.insert(name.as_str(), hash.as_str());
+            report.unchanged.push(self.res_dir.join(name));
+        }
+        for ((name, hash), result) in changed.iter().zip(results) {
+            match result {
+                Ok(()) => {
+                    new_cache.insert(name.as_str(), hash.as_str());
+                    report.compiled.push(self.res_dir.join(name));
+                }
+                Err(err) => failures.push((self.res_dir.join(name), err)),
+            }
+        }
+        write_cache(&cache_file, &options, &new_cache)?;
+        if !failures.is_empty() {
+            return Err(Error::CompileFailed(CompileFailures(failures)));
+        }
+        report.flat_files = sources
+            .keys()
+            .map(|name| self.compiled_res.join(flat_file_name(name)))
+            .collect();
+        Ok(report)
+    }
+
+    /// Compiles given resources on several threads and returns results in the same order.
+    /// Output is captured instead of printed, so output of parallel compiles isn't mixed
+    fn compile(&self, names: &[&str]) -> Vec<Result<()>> {
+        let queue = Mutex::new(names.iter().enumerate());
+        let results = Mutex::new(Vec::new());
+        let jobs = self.jobs.unwrap_or_else(|| {
+            std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+        });
+        // Workers use the executor of the calling thread
+        let executor = current_executor();
+        std::thread::scope(|scope| {
+            for _ in 0..jobs {
+                scope.spawn(|| {
+                    with_executor(executor.clone(), || loop {
+                        let next = queue.lock().unwrap().next();
+                        let Some((index, name)) = next else {
+                            break;
+                        };
+                        let result = self
+                            .options
+                            .with_input(Some(&self.res_dir.join(name)), &self.compiled_res)
+                            .command()
+                            .and_then(|command| command.output_with(&ExecOptions::new()))
+                            .map(|_| ());
+                        results.lock().unwrap().push((index, result));
+                    })
+                });
+            }
+        });
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Returns hashes of resource files by their `type[-config]/file` names
+    fn sources(&self) -> Result<BTreeMap<String, String>> {
+        let mut sources = BTreeMap::new();
+        for entry in walkdir::WalkDir::new(&self.res_dir)
+            .min_depth(2)
+            .max_depth(2)
+            .into_iter()
+            .filter_entry(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        {
+            let entry = entry.map_err(std::io::Error::from)?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let dir = path.parent().and_then(Path::file_name).unwrap_or_default();
+            let name = format!(
+                "{}/{}",
+                dir.to_string_lossy(),
+                entry.file_name().to_string_lossy()
+            );
+            let hash = Sha256::digest(std::fs::read(path)?);
+            sources.insert(name, format!("{:x}", hash));
+        }
+        Ok(sources)
+    }
+}
+
+/// Returns name of the file aapt2 compiles given `type[-config]/file` resource into, e.g.
+/// `values-en_strings.arsc.flat` or `drawable_icon.png.flat`
+pub fn flat_file_name(resource: &str) -> String {
+    let (dir, file) = resource.split_once('/').unwrap_or(("", resource));
+    let resource_type = dir.split('-').next().unwrap_or_default();
+    match file.strip_suffix(".xml") {
+        Some(stem) if resource_type == "values" => format!("{}_{}.arsc.flat", dir, stem),
+        _ => format!("{}_{}.flat", dir, file),
+    }
+}
+
+/// Hash of compile arguments other than inputs, which affect the compiled files
+fn options_hash(options: &Aapt2Compile) -> Result<String> {
+    let command = options.with_input(None, Path::new("")).command()?;
+    let mut hasher = Sha256::new();
+    for arg in command.get_args() {
+        hasher.update(arg.to_string_lossy().as_bytes());
+        hasher.update([0]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Reads the cache written by [`write_cache`]. Hashes are dropped if it was written with
+/// different options, but names are kept to remove compiled files of deleted sources
+fn read_cache(cache_file: &Path, options: &str) -> Result<BTreeMap<String, Option<String>>> {
+    let mut cache = BTreeMap::new();
+    let content = match std::fs::read_to_string(cache_file) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(cache),
+        Err(err) => return Err(err.into()),
+    };
+    let mut lines = content.lines();
+    let same_options = lines.next().and_then(|line| line.strip_prefix("options ")) == Some(options);
+    for line in lines {
+        if let Some((hash, name)) = line.split_once("  ") {
+            cache.insert(name.to_owned(), same_options.then(|| hash.to_owned()));
+        }
+    }
+    Ok(cache)
+}
+
+/// Writes hashes of compiled resources in `sha256sum` format after the options hash
+fn write_cache(cache_file: &Path, options: &str, cache: &BTreeMap<&str, &str>) -> Result<()> {
+    let mut content = format!("options {}\n", options);
+    for (name, hash) in cache {
+        content.push_str(&format!("{}  {}\n", hash, name));
+    }
+    std::fs::write(cache_file, content)?;
+    Ok(())
+}
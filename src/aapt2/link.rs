@@ -1,6 +1,13 @@
 use super::aapt2_tool;
-use crate::error::{CommandExt, Error, Result};
-use std::path::{Path, PathBuf};
+use crate::{
+    command_line::CommandLine,
+    error::{CommandExt, Error, Result},
+    executor::ExecOptions,
+};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 /// ## Link
 /// In the link phase, `AAPT2` merges all the intermediate files generated from the
@@ -125,6 +132,24 @@ impl Aapt2Link {
         }
     }
 
+    /// Links inputs with the generated manifest. The manifest is written to
+    /// `manifest_path` right away, replacing the file if it exists, so pass a path in the
+    /// build directory rather than the handwritten manifest. Missing directories of the
+    /// path are created
+    #[cfg(feature = "manifest")]
+    pub fn new_with_manifest(
+        inputs: &[PathBuf],
+        output_apk: &Path,
+        manifest: &crate::manifest::AndroidManifest,
+        manifest_path: &Path,
+    ) -> Result<Self> {
+        if let Some(dir) = manifest_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        manifest.to_file(manifest_path)?;
+        Ok(Self::new(inputs, output_apk, manifest_path))
+    }
+
     /// Specifies the output path for the linked resource APK.
     ///
     /// This is a required flag because you must specify the path for the output APK that
@@ -601,6 +626,35 @@ impl Aapt2Link {
 
     /// Executes aapt2 link with arguments
     pub fn run(&self) -> Result<PathBuf> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        self.command()?.output_with(options)?;
+        Ok(self.output_apk.clone())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<PathBuf> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(self.output_apk.clone())
+    }
+
+    /// Builds aapt2 link command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
         let mut aapt2 = aapt2_tool()?;
         aapt2.arg("link");
         if !self.inputs.is_empty() {
@@ -613,7 +667,10 @@ impl Aapt2Link {
                 .flat_map(|e| e.map(|x| x.path()))
                 .collect::<Vec<_>>();
             paths.iter().for_each(|input| {
-                if !input.ends_with("AndroidManifest.xml") {
+                let hidden = input
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+                if !input.ends_with("AndroidManifest.xml") && !hidden {
                     aapt2.arg(input);
                 }
             });
@@ -825,7 +882,11 @@ impl Aapt2Link {
         if self.merge_only {
             aapt2.arg("--merge-only");
         }
-        aapt2.output_err(true)?;
-        Ok(self.output_apk.clone())
+        Ok(aapt2)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
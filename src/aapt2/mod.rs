@@ -12,8 +12,11 @@ mod convert;
 mod daemon;
 mod diff;
 mod dump;
+mod dump_output;
+mod incremental;
 mod link;
 mod optimize;
+mod resource_table;
 mod version;
 
 pub use compile::*;
@@ -21,12 +24,14 @@ pub use convert::*;
 pub use daemon::*;
 pub use diff::*;
 pub use dump::*;
+pub use dump_output::*;
+pub use incremental::*;
 pub use link::*;
 pub use optimize::*;
+pub use resource_table::*;
 pub use version::*;
 
-use self::{daemon::Aapt2Daemon, diff::Aapt2Diff, version::Aapt2Version};
-use crate::{error::*, find_max_version, sdk_path_from_env};
+use crate::{error::*, executor::current_executor, sdk::build_tools_executable};
 use std::{
     path::{Path, PathBuf},
     process::Command,
@@ -56,6 +61,11 @@ impl Aapt2 {
         Aapt2Compile::new_from_res_zip(res_zip, compiled_res)
     }
 
+    /// Compiles only resources of given resource dir changed since the previous run
+    pub fn compile_changed(self, res_dir: &Path, compiled_res: &Path) -> Aapt2IncrementalCompile {
+        Aapt2IncrementalCompile::new(res_dir, compiled_res)
+    }
+
     /// Links given list of resources into an APK
     pub fn link_inputs(self, inputs: &[PathBuf], output_apk: &Path, manifest: &Path) -> Aapt2Link {
         Aapt2Link::new(inputs, output_apk, manifest)
@@ -103,14 +113,11 @@ impl Aapt2 {
     }
 }
 
-/// Find aapt2 executable binary file in SDK and initialize it
+/// Find aapt2 executable binary file in SDK and initialize it. `aapt2` from `PATH`
+/// is used unless `ANDROID_BUILD_TOOLS_VERSION` selects build-tools version, e.g.
+/// `30.0.3` or `>=30.0.0`. Otherwise the latest installed build-tools are used
 pub fn aapt2_tool() -> Result<Command> {
-    if let Ok(aapt2) = which::which(bin!("aapt2")) {
-        return Ok(Command::new(aapt2));
-    }
-    let sdk_path = sdk_path_from_env()?;
-    let build_tools = sdk_path.join("build-tools");
-    let target_sdk_version = find_max_version(&build_tools)?;
-    let aapt2_exe = build_tools.join(target_sdk_version).join(bin!("aapt2"));
-    Ok(Command::new(aapt2_exe))
+    current_executor().locate("aapt2", &|| {
+        Ok(Command::new(build_tools_executable(bin!("aapt2"))?))
+    })
 }
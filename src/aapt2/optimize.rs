@@ -1,6 +1,7 @@
 use super::aapt2_tool;
-use crate::error::*;
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Preforms resource optimizations on an APK
 #[derive(Clone, Default)]
@@ -129,31 +130,60 @@ impl Aapt2Optimize {
 
     /// Executes aapt2 optimize with arguments
     pub fn run(self) -> Result<()> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
         let mut aapt2 = aapt2_tool()?;
         aapt2.arg("optimize");
         aapt2.arg("-o").arg(&self.output_apk);
         aapt2.arg("-d").arg(&self.output_dir);
-        if let Some(config_xml_file) = self.config_xml_file {
+        if let Some(config_xml_file) = &self.config_xml_file {
             aapt2.arg("-x").arg(config_xml_file);
         }
         if self.apk_artifacts {
             aapt2.arg("-p");
         }
-        if let Some(target_densities) = self.target_densities {
+        if let Some(target_densities) = &self.target_densities {
             aapt2.arg("--target_densities").arg(target_densities);
         }
-        if let Some(resources_config_path) = self.resources_config_path {
+        if let Some(resources_config_path) = &self.resources_config_path {
             aapt2
                 .arg("--resources_config_path")
                 .arg(resources_config_path);
         }
-        if let Some(configs_to_include) = self.configs_to_include {
+        if let Some(configs_to_include) = &self.configs_to_include {
             aapt2.arg("-c").arg(configs_to_include);
         }
-        if let Some(split) = self.split {
+        if let Some(split) = &self.split {
             aapt2.arg("--split").arg(split);
         }
-        if let Some(keep_artifacts) = self.keep_artifacts {
+        if let Some(keep_artifacts) = &self.keep_artifacts {
             aapt2.arg("--keep-artifacts").arg(keep_artifacts);
         }
         if self.enable_sparse_encoding {
@@ -165,7 +195,7 @@ impl Aapt2Optimize {
         if self.shorten_resource_paths {
             aapt2.arg("--shorten-resource-paths");
         }
-        if let Some(resource_path_shortening_map) = self.resource_path_shortening_map {
+        if let Some(resource_path_shortening_map) = &self.resource_path_shortening_map {
             aapt2
                 .arg("--resource-path-shortening-map")
                 .arg(resource_path_shortening_map);
@@ -176,7 +206,11 @@ impl Aapt2Optimize {
         if self.help {
             aapt2.arg("-h");
         }
-        aapt2.output_err(true)?;
-        Ok(())
+        Ok(aapt2)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
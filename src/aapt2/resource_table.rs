@@ -0,0 +1,227 @@
+use super::Visibility;
+use crate::error::*;
+
+/// Resource table of the APK printed by `aapt2 dump resources`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceTable {
+    pub packages: Vec<ResourcePackage>,
+}
+
+/// Package of the resource table, e.g. `com.example` with id `0x7f`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourcePackage {
+    pub name: String,
+    pub id: Option<u8>,
+    pub types: Vec<ResourceType>,
+}
+
+/// Resource type of the package, e.g. `string` or `drawable`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceType {
+    pub name: String,
+    pub id: Option<u8>,
+    pub entries: Vec<ResourceEntry>,
+}
+
+/// Single resource with its values for every configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceEntry {
+    pub id: ResourceId,
+    /// Name of the resource without type, e.g. `app_name`
+    pub name: String,
+    pub visibility: Visibility,
+    pub values: Vec<ResourceValue>,
+}
+
+/// Value of the resource in a configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceValue {
+    /// Configuration qualifiers of the value, e.g. `fr` or `hdpi-v4`. Empty for the
+    /// default configuration
+    pub config: String,
+    /// Value as printed by aapt2, e.g. `"Hello"` or `(file) res/drawable/icon.png type=PNG`
+    pub value: String,
+}
+
+/// Resource identifier in the `0xPPTTEEEE` form, where `PP` is the package id, `TT` is the
+/// type id and `EEEE` is the entry index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResourceId(pub u32);
+
+impl ResourceId {
+    pub fn package_id(self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    pub fn type_id(self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    pub fn entry_id(self) -> u16 {
+        self.0 as u16
+    }
+}
+
+impl std::fmt::Display for ResourceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:08x}", self.0)
+    }
+}
+
+impl std::str::FromStr for ResourceId {
+    type Err = Error;
+
+    fn from_str(id: &str) -> Result<Self> {
+        let hex = id.strip_prefix("0x").unwrap_or(id);
+        u32::from_str_radix(hex, 16)
+            .map(ResourceId)
+            .map_err(|_| Error::OutputParseFailed(format!("invalid resource id {:?}", id)))
+    }
+}
+
+impl ResourceTable {
+    /// Parses output of `aapt2 dump resources`, e.g.
+    ///
+    /// ```sh
+    /// Binary APK
+    /// Package name=com.example id=7f
+    ///   type string id=01 entryCount=1
+    ///     resource 0x7f010000 string/app_name PUBLIC
+    ///       () "Example"
+    ///       (fr) "Exemple"
+    /// ```
+    pub fn parse(output: &str) -> Result<ResourceTable> {
+        let mut table = ResourceTable::default();
+        for line in output.lines() {
+            let trimmed = line.trim();
+            if let Some(package) = trimmed.strip_prefix("Package ") {
+                table.packages.push(ResourcePackage {
+                    name: field(package, "name").unwrap_or_default().to_owned(),
+                    id: field(package, "id").and_then(|id| u8::from_str_radix(id, 16).ok()),
+                    types: Vec::new(),
+                });
+            } else if let Some(resource_type) = trimmed.strip_prefix("type ") {
+                let name = resource_type.split_whitespace().next().unwrap_or_default();
+                last_package(&mut table, line)?.types.push(ResourceType {
+                    name: name.to_owned(),
+                    id: field(resource_type, "id").and_then(|id| u8::from_str_radix(id, 16).ok()),
+                    entries: Vec::new(),
+                });
+            } else if let Some(resource) = trimmed.strip_prefix("resource ") {
+                let entry = parse_entry(resource)?;
+                last_type(&mut table, line)?.entries.push(entry);
+            } else if trimmed.starts_with('(') && line.starts_with("      (") {
+                let (config, value) = trimmed[1..].split_once(')').ok_or_else(|| {
+                    Error::OutputParseFailed(format!("invalid resource value {:?}", line))
+                })?;
+                last_entry(&mut table, line)?.values.push(ResourceValue {
+                    config: config.to_owned(),
+                    value: value.trim().to_owned(),
+                });
+            } else if !trimmed.is_empty() && line.starts_with("       ") {
+                // Nested lines of styles, arrays and plurals belong to the previous value
+                if let Some(value) = last_entry(&mut table, line)?.values.last_mut() {
+                    value.value.push('\n');
+                    value.value.push_str(trimmed);
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// Returns resource by its type and name, e.g. `string/app_name`
+    pub fn entry(&self, name: &str) -> Option<&ResourceEntry> {
+        let (type_name, entry_name) = name.trim_start_matches('@').split_once('/')?;
+        self.packages
+            .iter()
+            .flat_map(|package| &package.types)
+            .filter(|resource_type| resource_type.name == type_name)
+            .flat_map(|resource_type| &resource_type.entries)
+            .find(|entry| entry.name == entry_name)
+    }
+
+    /// Returns resource by its identifier
+    pub fn entry_by_id(&self, id: ResourceId) -> Option<&ResourceEntry> {
+        self.packages
+            .iter()
+            .flat_map(|package| &package.types)
+            .flat_map(|resource_type| &resource_type.entries)
+            .find(|entry| entry.id == id)
+    }
+
+    /// Returns sorted list of distinct configurations used by the resources. The default
+    /// configuration is listed as an empty string
+    pub fn configurations(&self) -> Vec<String> {
+        let mut configurations = self
+            .packages
+            .iter()
+            .flat_map(|package| &package.types)
+            .flat_map(|resource_type| &resource_type.entries)
+            .flat_map(|entry| &entry.values)
+            .map(|value| value.config.clone())
+            .collect::<Vec<_>>();
+        configurations.sort();
+        configurations.dedup();
+        configurations
+    }
+}
+
+/// Parses output of `aapt2 dump configurations` into the list of configurations
+pub fn parse_configurations(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Parses `0x7f010000 string/app_name PUBLIC`
+fn parse_entry(resource: &str) -> Result<ResourceEntry> {
+    let mut tokens = resource.split_whitespace();
+    let id = tokens.next().unwrap_or_default().parse()?;
+    let name = tokens.next().unwrap_or_default();
+    let name = name.split_once('/').map(|(_, name)| name).unwrap_or(name);
+    let visibility = tokens
+        .find_map(|token| match token {
+            "PUBLIC" => Some(Visibility::Public),
+            "PRIVATE" => Some(Visibility::Private),
+            _ => None,
+        })
+        .unwrap_or(Visibility::Default);
+    Ok(ResourceEntry {
+        id,
+        name: name.to_owned(),
+        visibility,
+        values: Vec::new(),
+    })
+}
+
+/// Returns value of `key=value` field
+fn field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    line.split_whitespace()
+        .filter_map(|token| token.split_once('='))
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| value)
+}
+
+fn last_package<'a>(table: &'a mut ResourceTable, line: &str) -> Result<&'a mut ResourcePackage> {
+    table
+        .packages
+        .last_mut()
+        .ok_or_else(|| Error::OutputParseFailed(format!("{:?} is outside of a package", line)))
+}
+
+fn last_type<'a>(table: &'a mut ResourceTable, line: &str) -> Result<&'a mut ResourceType> {
+    last_package(table, line)?
+        .types
+        .last_mut()
+        .ok_or_else(|| Error::OutputParseFailed(format!("{:?} is outside of a type", line)))
+}
+
+fn last_entry<'a>(table: &'a mut ResourceTable, line: &str) -> Result<&'a mut ResourceEntry> {
+    last_type(table, line)?
+        .entries
+        .last_mut()
+        .ok_or_else(|| Error::OutputParseFailed(format!("{:?} is outside of a resource", line)))
+}
@@ -1,5 +1,6 @@
 use super::aapt2_tool;
-use crate::error::*;
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
+use std::process::Command;
 
 /// Prints the version of aapt.
 pub struct Aapt2Version {
@@ -24,13 +25,46 @@ impl Aapt2Version {
 
     /// Executes aapt2 version with arguments
     pub fn run(&self) -> Result<()> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
         let mut aapt2 = aapt2_tool()?;
         aapt2.arg("version");
         aapt2.arg(&self.version);
         if self.help {
             aapt2.arg("-h");
         }
-        aapt2.output_err(true)?;
-        Ok(())
+        Ok(aapt2)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
@@ -0,0 +1,178 @@
+use super::{AdbTarget, Device};
+use crate::error::*;
+use std::{
+    io::{Read, Write},
+    net::{Ipv4Addr, SocketAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// Default port of the adb server
+pub const ADB_SERVER_PORT: u16 = 5037;
+
+/// Native client for the adb server host protocol.
+///
+/// Instead of spawning an `adb` process for every command, `AdbClient` connects to the
+/// adb server over TCP and speaks its
+/// [`host protocol`](https://android.googlesource.com/platform/packages/modules/adb/+/refs/heads/master/SERVICES.TXT)
+/// directly. Every request is sent as a 4-digit hexadecimal length followed by the
+/// payload, and the server answers with `OKAY` or `FAIL` followed by a
+/// length-prefixed message.
+///
+/// The adb server must already be running, for example after `adb start-server`.
+#[derive(Debug, Clone)]
+pub struct AdbClient {
+    address: SocketAddr,
+    timeout: Option<Duration>,
+}
+
+impl Default for AdbClient {
+    fn default() -> Self {
+        Self {
+            address: SocketAddr::from((Ipv4Addr::LOCALHOST, ADB_SERVER_PORT)),
+            timeout: None,
+        }
+    }
+}
+
+impl AdbClient {
+    /// Initialize client for the adb server listening on `127.0.0.1:5037`
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Address of the adb server [default=127.0.0.1:5037]
+    pub fn address(&mut self, address: SocketAddr) -> &mut Self {
+        self.address = address;
+        self
+    }
+
+    /// Read and write timeout for every connection to the adb server
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the internal version of the adb server (`host:version`)
+    pub fn version(&self) -> Result<u32> {
+        let mut connection = self.connect()?;
+        connection.request("host:version")?;
+        let version = connection.read_string()?;
+        u32::from_str_radix(version.trim(), 16)
+            .map_err(|_| Error::AdbProtocol(format!("invalid server version {:?}", version)))
+    }
+
+    /// Returns the list of attached devices in the long format of `adb devices -l`
+    /// (`host:devices-l`)
+    pub fn devices_l(&self) -> Result<String> {
+        let mut connection = self.connect()?;
+        connection.request("host:devices-l")?;
+        connection.read_string()
+    }
+
+    /// Returns the list of attached devices
+    pub fn devices(&self) -> Result<Vec<Device>> {
+        Device::parse_list(&self.devices_l()?)
+    }
+
+    /// Runs a command in the shell of the target device (`shell:`) and returns its output
+    pub fn shell(&self, target: &AdbTarget, command: &str) -> Result<String> {
+        let output = self.service(target, &format!("shell:{}", command))?;
+        Ok(String::from_utf8_lossy(&output).to_string())
+    }
+
+    /// Executes a command on the target device (`exec:`) and returns its raw output.
+    /// Unlike [`shell`](Self::shell) no pty is allocated, so the output is binary safe
+    pub fn exec(&self, target: &AdbTarget, command: &str) -> Result<Vec<u8>> {
+        self.service(target, &format!("exec:{}", command))
+    }
+
+    /// Switches a new connection to the target device (`host:transport:<serial>`),
+    /// starts the service on it and reads the output until the device closes the stream.
+    ///
+    /// Host and port of the target select the adb server instead of the client address
+    fn service(&self, target: &AdbTarget, service: &str) -> Result<Vec<u8>> {
+        let mut connection = self.connect_to(self.server_address(target)?)?;
+        connection.request(&target.transport_request())?;
+        connection.request(service)?;
+        let mut output = Vec::new();
+        connection.stream.read_to_end(&mut output)?;
+        Ok(output)
+    }
+
+    /// Returns address of the adb server selected by the target. Missing host or port
+    /// are taken from the client address
+    fn server_address(&self, target: &AdbTarget) -> Result<SocketAddr> {
+        let (host, port) = target.server();
+        let port = port.unwrap_or(self.address.port());
+        let Some(host) = host else {
+            return Ok(SocketAddr::new(self.address.ip(), port));
+        };
+        (host, port).to_socket_addrs()?.next().ok_or_else(|| {
+            Error::InvalidArguments(format!("adb server host {} is not resolved", host))
+        })
+    }
+
+    fn connect(&self) -> Result<AdbConnection> {
+        self.connect_to(self.address)
+    }
+
+    fn connect_to(&self, address: SocketAddr) -> Result<AdbConnection> {
+        let stream = match self.timeout {
+            Some(timeout) => TcpStream::connect_timeout(&address, timeout)?,
+            None => TcpStream::connect(address)?,
+        };
+        stream.set_read_timeout(self.timeout)?;
+        stream.set_write_timeout(self.timeout)?;
+        Ok(AdbConnection { stream })
+    }
+}
+
+/// Single connection to the adb server. The server closes the connection after every
+/// host request, so a new one is opened for each of them
+struct AdbConnection {
+    stream: TcpStream,
+}
+
+impl AdbConnection {
+    /// Sends the length-prefixed request and checks that the server answered with `OKAY`
+    fn request(&mut self, request: &str) -> Result<()> {
+        if request.len() > 0xffff {
+            return Err(Error::AdbProtocol(format!(
+                "request is too long: {} bytes",
+                request.len()
+            )));
+        }
+        self.stream
+            .write_all(format!("{:04x}{}", request.len(), request).as_bytes())?;
+        let mut status = [0; 4];
+        self.stream.read_exact(&mut status)?;
+        match &status {
+            b"OKAY" => Ok(()),
+            b"FAIL" => Err(Error::AdbServerFailed(self.read_string()?)),
+            _ => Err(Error::AdbProtocol(format!(
+                "unexpected response status {:?}",
+                String::from_utf8_lossy(&status)
+            ))),
+        }
+    }
+
+    /// Reads a message prefixed with its length in 4 hexadecimal digits
+    fn read_string(&mut self) -> Result<String> {
+        let mut length = [0; 4];
+        self.stream.read_exact(&mut length)?;
+        let length = std::str::from_utf8(&length)
+            .ok()
+            .and_then(|length| usize::from_str_radix(length, 16).ok())
+            .ok_or_else(|| {
+                Error::AdbProtocol(format!(
+                    "invalid message length {:?}",
+                    String::from_utf8_lossy(&length)
+                ))
+            })?;
+        let mut message = vec![0; length];
+        self.stream.read_exact(&mut message)?;
+        Ok(String::from_utf8_lossy(&message).to_string())
+    }
+}
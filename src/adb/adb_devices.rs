@@ -0,0 +1,78 @@
+use super::DeviceState;
+use crate::error::*;
+
+/// Device attached to the adb server, as listed by `adb devices -l`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    /// Serial number of the device. Use it to target the device with `-s`
+    pub serial: String,
+    /// Connection state of the device
+    pub state: DeviceState,
+    /// Product name of the device
+    pub product: Option<String>,
+    /// Model name of the device
+    pub model: Option<String>,
+    /// Device name
+    pub device: Option<String>,
+    /// Transport id of the device. Use it to target the device with `-t`
+    pub transport_id: Option<u32>,
+}
+
+impl Device {
+    /// Parses output of `adb devices -l` into the list of devices.
+    ///
+    /// The `List of devices attached` header and adb server messages starting with `*`
+    /// are skipped
+    pub fn parse_list(output: &str) -> Result<Vec<Device>> {
+        output
+            .lines()
+            .map(str::trim)
+            .filter(|line| {
+                !line.is_empty() && !line.starts_with('*') && !line.starts_with("List of devices")
+            })
+            .map(Device::parse)
+            .collect()
+    }
+
+    /// Parses a single line of `adb devices -l` output, e.g.
+    ///
+    /// ```sh
+    /// emulator-5554 device product:sdk_gphone64 model:sdk_gphone64 device:emu64x transport_id:1
+    /// ```
+    ///
+    /// The state may consist of several words, like `no permissions (...); see [...]`
+    pub fn parse(line: &str) -> Result<Device> {
+        let line = line.trim();
+        let (serial, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim_start();
+        let state_len = if rest.starts_with("no permissions") {
+            // The explanation ends with a link in brackets in recent adb versions
+            rest.rfind(']')
+                .or_else(|| rest.rfind(')'))
+                .map_or("no permissions".len(), |end| end + 1)
+        } else {
+            rest.find(char::is_whitespace).unwrap_or(rest.len())
+        };
+        let (state, qualifiers) = rest.split_at(state_len);
+        let state = state.parse()?;
+        let tokens = qualifiers.split_whitespace();
+        let mut device = Device {
+            serial: serial.to_owned(),
+            state,
+            product: None,
+            model: None,
+            device: None,
+            transport_id: None,
+        };
+        for (key, value) in tokens.filter_map(|token| token.split_once(':')) {
+            match key {
+                "product" => device.product = Some(value.to_owned()),
+                "model" => device.model = Some(value.to_owned()),
+                "device" => device.device = Some(value.to_owned()),
+                "transport_id" => device.transport_id = value.parse().ok(),
+                _ => (),
+            }
+        }
+        Ok(device)
+    }
+}
@@ -32,3 +32,160 @@ impl std::fmt::Display for ScreenCompatibilityMode {
         }
     }
 }
+
+/// Connection state of the device reported by `adb devices`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceState {
+    /// Device is connected to the adb server
+    Device,
+    /// Device is not connected to adb or is not responding
+    Offline,
+    /// Device hasn't accepted the RSA key of this computer yet
+    Unauthorized,
+    /// Device is waiting for the user to accept the RSA key of this computer
+    Authorizing,
+    /// Device is being connected to the adb server
+    Connecting,
+    /// Device is in the bootloader
+    Bootloader,
+    /// Device is booted into recovery mode
+    Recovery,
+    /// Device is booted into rescue mode
+    Rescue,
+    /// Device is in sideload mode of recovery
+    Sideload,
+    /// Device is attached but not available to adb, e.g. used by a virtual machine
+    Detached,
+    /// The adb server itself, reported when connected to itself
+    Host,
+    /// The user has no permissions to access the USB device, e.g. because of udev rules
+    NoPermissions,
+}
+
+impl std::fmt::Display for DeviceState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Device => write!(f, "device"),
+            Self::Offline => write!(f, "offline"),
+            Self::Unauthorized => write!(f, "unauthorized"),
+            Self::Authorizing => write!(f, "authorizing"),
+            Self::Connecting => write!(f, "connecting"),
+            Self::Bootloader => write!(f, "bootloader"),
+            Self::Recovery => write!(f, "recovery"),
+            Self::Rescue => write!(f, "rescue"),
+            Self::Sideload => write!(f, "sideload"),
+            Self::Detached => write!(f, "detached"),
+            Self::Host => write!(f, "host"),
+            Self::NoPermissions => write!(f, "no permissions"),
+        }
+    }
+}
+
+impl std::str::FromStr for DeviceState {
+    type Err = crate::error::Error;
+
+    /// Parses the state. `no permissions` may be followed by the explanation printed by
+    /// adb
+    fn from_str(state: &str) -> Result<Self, Self::Err> {
+        if state.starts_with("no permissions") {
+            return Ok(Self::NoPermissions);
+        }
+        match state {
+            "device" => Ok(Self::Device),
+            "offline" => Ok(Self::Offline),
+            "unauthorized" => Ok(Self::Unauthorized),
+            "authorizing" => Ok(Self::Authorizing),
+            "connecting" => Ok(Self::Connecting),
+            "bootloader" => Ok(Self::Bootloader),
+            "recovery" => Ok(Self::Recovery),
+            "rescue" => Ok(Self::Rescue),
+            "sideload" => Ok(Self::Sideload),
+            "detached" => Ok(Self::Detached),
+            "host" => Ok(Self::Host),
+            _ => Err(crate::error::Error::UnknownDeviceState(state.to_owned())),
+        }
+    }
+}
+
+/// Priority of the log message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogPriority {
+    Verbose,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+    /// Highest priority, on which nothing is ever printed. Used only in filter specs
+    Silent,
+}
+
+impl LogPriority {
+    /// Returns priority from its letter in the logcat output
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'V' => Some(Self::Verbose),
+            'D' => Some(Self::Debug),
+            'I' => Some(Self::Info),
+            'W' => Some(Self::Warn),
+            'E' => Some(Self::Error),
+            'F' => Some(Self::Fatal),
+            'S' => Some(Self::Silent),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for LogPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Verbose => write!(f, "V"),
+            Self::Debug => write!(f, "D"),
+            Self::Info => write!(f, "I"),
+            Self::Warn => write!(f, "W"),
+            Self::Error => write!(f, "E"),
+            Self::Fatal => write!(f, "F"),
+            Self::Silent => write!(f, "S"),
+        }
+    }
+}
+
+/// Ring buffer of the device log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogcatBuffer {
+    Main,
+    System,
+    Radio,
+    Events,
+    Crash,
+    /// Main, system and crash buffers
+    Default,
+    All,
+}
+
+impl std::fmt::Display for LogcatBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Main => write!(f, "main"),
+            Self::System => write!(f, "system"),
+            Self::Radio => write!(f, "radio"),
+            Self::Events => write!(f, "events"),
+            Self::Crash => write!(f, "crash"),
+            Self::Default => write!(f, "default"),
+            Self::All => write!(f, "all"),
+        }
+    }
+}
+
+/// Output format of the log messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogcatFormat {
+    /// Display the date, invocation time, priority, tag, and the PID and TID of the
+    /// thread issuing the message
+    #[default]
+    Threadtime,
+    /// Same as `Threadtime` with the time in seconds since Jan 1, 1970
+    Epoch,
+    /// Same as `Threadtime` with the UID of the logging process
+    Uid,
+}
@@ -0,0 +1,288 @@
+use super::{adb_tool, AdbTarget, LogPriority, LogcatBuffer, LogcatFormat};
+use crate::{
+    command_line::CommandLine,
+    error::*,
+    executor::{current_executor, ToolProcess},
+};
+use std::{
+    io::{BufRead, BufReader},
+    process::Command,
+    time::Instant,
+};
+
+/// # Logcat
+/// Logcat is a command-line tool that dumps a log of system messages, including stack
+/// traces when the device throws an error and messages that you have written from your
+/// app with the `Log` class.
+///
+/// Every line of the log is parsed into a [`LogEntry`]. By default logcat keeps
+/// printing new messages until the returned [`LogcatStream`] is dropped. Use
+/// [`d`](Self::d) to dump the log and stop:
+///
+/// ```no_run
+/// use android_tools::adb::{AdbLogcat, LogPriority};
+///
+/// let crashes = AdbLogcat::new()
+///     .d(true)
+///     .filter("AndroidRuntime", LogPriority::Error)
+///     .silence_others(true)
+///     .run()
+///     .unwrap()
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// ```
+///
+/// See [`Logcat command-line tool`](https://developer.android.com/studio/command-line/logcat)
+#[derive(Clone, Default)]
+pub struct AdbLogcat {
+    buffers: Vec<LogcatBuffer>,
+    filters: Vec<String>,
+    silence_others: bool,
+    pid: Option<u32>,
+    since: Option<String>,
+    d: bool,
+    format: LogcatFormat,
+    target: AdbTarget,
+}
+
+impl AdbLogcat {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Selects the adb server and the device to run the command on
+    pub fn target(&mut self, target: &AdbTarget) -> &mut Self {
+        self.target = target.clone();
+        self
+    }
+
+    /// Loads an alternate log buffer for viewing. Can be specified several times
+    pub fn buffer(&mut self, buffer: LogcatBuffer) -> &mut Self {
+        self.buffers.push(buffer);
+        self
+    }
+
+    /// Shows only messages of given tag with given or higher priority (`tag:priority`).
+    /// Use `*` as tag to set the priority of all tags
+    pub fn filter(&mut self, tag: &str, priority: LogPriority) -> &mut Self {
+        self.filters.push(format!("{}:{}", tag, priority));
+        self
+    }
+
+    /// Silences all tags not listed in [`filter`](Self::filter) (`*:S`)
+    pub fn silence_others(&mut self, silence_others: bool) -> &mut Self {
+        self.silence_others = silence_others;
+        self
+    }
+
+    /// Only prints logs from the given pid
+    pub fn pid(&mut self, pid: u32) -> &mut Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Prints the lines since specified time in the `'MM-DD hh:mm:ss.mmm'` or
+    /// `'sss.mmm'` format, or the given number of most recent lines
+    pub fn since(&mut self, since: String) -> &mut Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Dumps the log to the screen and exits
+    pub fn d(&mut self, d: bool) -> &mut Self {
+        self.d = d;
+        self
+    }
+
+    /// Sets the output format of the log messages [default=threadtime]
+    pub fn format(&mut self, format: LogcatFormat) -> &mut Self {
+        self.format = format;
+        self
+    }
+
+    /// Starts logcat and returns the stream of parsed log entries. Logcat is killed
+    /// when the stream is dropped
+    pub fn run(&self) -> Result<LogcatStream> {
+        let mut logcat = self.command()?;
+        let started = Instant::now();
+        let process = current_executor().spawn(&mut logcat)?;
+        Ok(LogcatStream {
+            command: logcat,
+            started,
+            process: BufReader::new(process),
+            format: self.format,
+            finished: false,
+        })
+    }
+
+    /// Returns program and arguments logcat is started with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+
+    /// Builds the command with arguments
+    fn command(&self) -> Result<Command> {
+        let mut logcat = adb_tool()?;
+        self.target.apply(&mut logcat);
+        logcat.arg("logcat");
+        self.buffers.iter().for_each(|buffer| {
+            logcat.arg("-b").arg(buffer.to_string());
+        });
+        logcat.arg("-v").arg("threadtime");
+        match self.format {
+            LogcatFormat::Threadtime => (),
+            LogcatFormat::Epoch => {
+                logcat.arg("-v").arg("epoch");
+            }
+            LogcatFormat::Uid => {
+                logcat.arg("-v").arg("uid");
+            }
+        }
+        if let Some(pid) = &self.pid {
+            logcat.arg("--pid").arg(pid.to_string());
+        }
+        if let Some(since) = &self.since {
+            logcat.arg("-T").arg(since);
+        }
+        if self.d {
+            logcat.arg("-d");
+        }
+        logcat.args(&self.filters);
+        if self.silence_others {
+            logcat.arg("*:S");
+        }
+        Ok(logcat)
+    }
+}
+
+/// Iterator over log entries printed by a running logcat process. Lines that aren't
+/// log messages, like `--------- beginning of main`, are skipped
+pub struct LogcatStream {
+    command: Command,
+    started: Instant,
+    /// Logcat started by the executor, read by lines
+    process: BufReader<Box<dyn ToolProcess>>,
+    format: LogcatFormat,
+    finished: bool,
+}
+
+impl LogcatStream {
+    /// Returns an error if logcat exited with a non-zero exit code
+    fn finish(&mut self) -> Option<Result<LogEntry>> {
+        self.finished = true;
+        let output = match self.process.get_mut().wait() {
+            Ok(output) => output,
+            Err(error) => return Some(Err(error)),
+        };
+        if output.status.success() {
+            return None;
+        }
+        let failure = CommandFailure::new(
+            &self.command,
+            output.status,
+            String::new(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+            self.started.elapsed(),
+        );
+        Some(Err(failure.into()))
+    }
+}
+
+impl Iterator for LogcatStream {
+    type Item = Result<LogEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match self.process.read_line(&mut line) {
+                Ok(0) => return self.finish(),
+                Ok(_) => {
+                    let line = line.trim_end_matches(['\n', '\r']);
+                    if let Some(entry) = LogEntry::parse(line, self.format) {
+                        return Some(Ok(entry));
+                    }
+                }
+                Err(error) => return Some(Err(error.into())),
+            }
+        }
+    }
+}
+
+impl Drop for LogcatStream {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.process.get_mut().kill().ok();
+        }
+    }
+}
+
+/// Single message of the device log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Time of the message as printed by logcat: `MM-DD hh:mm:ss.mmm`, or `sss.mmm`
+    /// with [`LogcatFormat::Epoch`]
+    pub timestamp: String,
+    /// UID of the logging process. Only present with [`LogcatFormat::Uid`]
+    pub uid: Option<String>,
+    pub pid: u32,
+    pub tid: u32,
+    pub level: LogPriority,
+    pub tag: String,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Parses a line of logcat output printed in given format, e.g.
+    ///
+    /// ```sh
+    /// 10-17 11:31:02.123  1234  5678 I ActivityManager: Start proc 5678:com.example
+    /// ```
+    pub fn parse(line: &str, format: LogcatFormat) -> Option<LogEntry> {
+        let mut rest = line;
+        let timestamp = match format {
+            LogcatFormat::Epoch => next_token(&mut rest)?.to_owned(),
+            LogcatFormat::Threadtime | LogcatFormat::Uid => {
+                format!("{} {}", next_token(&mut rest)?, next_token(&mut rest)?)
+            }
+        };
+        let uid = match format {
+            LogcatFormat::Uid => Some(next_token(&mut rest)?.to_owned()),
+            LogcatFormat::Threadtime | LogcatFormat::Epoch => None,
+        };
+        let pid = next_token(&mut rest)?.parse().ok()?;
+        let tid = next_token(&mut rest)?.parse().ok()?;
+        let mut level = next_token(&mut rest)?.chars();
+        let level = match (level.next(), level.next()) {
+            (Some(letter), None) => LogPriority::from_letter(letter)?,
+            _ => return None,
+        };
+        let (tag, message) = rest.trim_start().split_once(':')?;
+        Some(LogEntry {
+            timestamp,
+            uid,
+            pid,
+            tid,
+            level,
+            tag: tag.trim_end().to_owned(),
+            message: message.strip_prefix(' ').unwrap_or(message).to_owned(),
+        })
+    }
+}
+
+/// Splits the next whitespace separated token off the beginning of the line
+fn next_token<'a>(line: &mut &'a str) -> Option<&'a str> {
+    let trimmed = line.trim_start();
+    let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+    if end == 0 {
+        return None;
+    }
+    let (token, rest) = trimmed.split_at(end);
+    *line = rest;
+    Some(token)
+}
@@ -1,20 +1,46 @@
-use crate::error::*;
-use std::process::Command;
+use super::{adb_tool, AdbTarget};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
 
+/// Copies files and directories from the device to the computer
 #[derive(Clone, Default)]
 pub struct AdbPull {
+    remote: Vec<PathBuf>,
+    local: PathBuf,
     a: bool,
-    z: bool,
+    z: Option<String>,
     disable_compression: bool,
+    target: AdbTarget,
 }
 
 impl AdbPull {
-    pub fn new() -> Self {
+    /// Initialize adb pull then specifies path to the file or directory on the device
+    /// and local path to copy it to
+    pub fn new(remote: &Path, local: &Path) -> Self {
         Self {
+            remote: vec![remote.to_owned()],
+            local: local.to_owned(),
             ..Default::default()
         }
     }
 
+    /// Adds another file or directory on the device to copy. With several remote paths
+    /// the local path must be a directory
+    pub fn remote(&mut self, remote: &Path) -> &mut Self {
+        self.remote.push(remote.to_owned());
+        self
+    }
+
+    /// Selects the adb server and the device to run the command on
+    pub fn target(&mut self, target: &AdbTarget) -> &mut Self {
+        self.target = target.clone();
+        self
+    }
+
     /// Preserve file timestamp and mode
     pub fn a(&mut self, a: bool) -> &mut Self {
         self.a = a;
@@ -22,8 +48,8 @@ impl AdbPull {
     }
 
     /// Enable compression with a specified algorithm (any, none, brotli)
-    pub fn z(&mut self, z: bool) -> &mut Self {
-        self.z = z;
+    pub fn z(&mut self, z: String) -> &mut Self {
+        self.z = Some(z);
         self
     }
 
@@ -33,18 +59,120 @@ impl AdbPull {
         self
     }
 
-    pub fn run(&self) -> Result<()> {
-        let mut pull = Command::new("adb");
+    /// Executes adb pull and returns transfer stats reported for every remote path
+    pub fn run(&self) -> Result<Vec<PullStats>> {
+        self.run_with(ExecOptions::new().print_logs(false))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<Vec<PullStats>> {
+        let output = self.command()?.output_with(options)?;
+        Ok(PullStats::parse_output(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<Vec<PullStats>> {
+        self.run_async_with(&ExecOptions::new()).await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<Vec<PullStats>> {
+        let output = crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(PullStats::parse_output(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut pull = adb_tool()?;
+        self.target.apply(&mut pull);
         pull.arg("pull");
         if self.a {
             pull.arg("-a");
         }
-        if self.z {
-            pull.arg("-z");
+        if let Some(z) = &self.z {
+            pull.arg("-z").arg(z);
         }
         if self.disable_compression {
             pull.arg("-Z");
         }
-        Ok(())
+        pull.args(&self.remote);
+        pull.arg(&self.local);
+        Ok(pull)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+}
+
+/// Transfer stats from the summary line printed by `adb pull`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullStats {
+    /// Remote path the stats are reported for. Absent in the overall summary
+    pub remote: Option<PathBuf>,
+    /// Number of pulled files
+    pub files_pulled: u32,
+    /// Number of skipped files
+    pub files_skipped: u32,
+    /// Number of transferred bytes
+    pub bytes: u64,
+    /// Duration of the transfer
+    pub duration: Duration,
+    /// Transfer rate in MB/s
+    pub throughput: f64,
+}
+
+impl PullStats {
+    /// Parses every summary line of `adb pull` output, skipping progress and other lines
+    pub fn parse_output(output: &str) -> Vec<PullStats> {
+        output
+            .lines()
+            .flat_map(|line| line.split('\r'))
+            .filter_map(PullStats::parse)
+            .collect()
+    }
+
+    /// Parses a summary line of `adb pull`, e.g.
+    ///
+    /// ```sh
+    /// /sdcard/report.txt: 1 file pulled, 0 skipped. 4.2 MB/s (52430 bytes in 0.012s)
+    /// ```
+    pub fn parse(line: &str) -> Option<PullStats> {
+        let line = line.trim();
+        let (remote, summary) = match line.rsplit_once(": ") {
+            Some((remote, summary)) => (Some(PathBuf::from(remote)), summary),
+            None => (None, line),
+        };
+        let words = summary
+            .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == ',')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+        let [files_pulled, "file" | "files", "pulled", files_skipped, "skipped.", throughput, "MB/s", bytes, "bytes", "in", duration] =
+            words.as_slice()
+        else {
+            return None;
+        };
+        // Durations like `inf` or `-1` don't fit into `Duration`, the line is skipped then
+        let duration =
+            Duration::try_from_secs_f64(duration.strip_suffix('s')?.parse().ok()?).ok()?;
+        Some(PullStats {
+            remote,
+            files_pulled: files_pulled.parse().ok()?,
+            files_skipped: files_skipped.parse().ok()?,
+            bytes: bytes.parse().ok()?,
+            duration,
+            throughput: throughput.parse().ok()?,
+        })
     }
 }
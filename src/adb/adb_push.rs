@@ -1,4 +1,5 @@
-use crate::error::*;
+use super::{adb_tool, AdbTarget};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::{
     path::{Path, PathBuf},
     process::Command,
@@ -10,6 +11,7 @@ pub struct AdbPush {
     n: bool,
     z_enable_compression: bool,
     z_disable_compression: bool,
+    target: AdbTarget,
 }
 
 impl AdbPush {
@@ -19,6 +21,12 @@ impl AdbPush {
         }
     }
 
+    /// Selects the adb server and the device to run the command on
+    pub fn target(&mut self, target: &AdbTarget) -> &mut Self {
+        self.target = target.clone();
+        self
+    }
+
     /// Only push files that are newer on the host than the device
     pub fn sync(&mut self, sync: &Path) -> &mut Self {
         self.sync = Some(sync.to_owned());
@@ -44,7 +52,37 @@ impl AdbPush {
     }
 
     pub fn run(&self) -> Result<()> {
-        let mut adb_push = Command::new("adb");
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut adb_push = adb_tool()?;
+        self.target.apply(&mut adb_push);
         adb_push.arg("push");
         if let Some(sync) = &self.sync {
             adb_push.arg("--sync").arg(sync);
@@ -58,7 +96,11 @@ impl AdbPush {
         if self.z_disable_compression {
             adb_push.arg("-Z");
         }
-        adb_push.output_err(true)?;
-        Ok(())
+        Ok(adb_push)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
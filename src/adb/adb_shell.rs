@@ -1,4 +1,5 @@
-use crate::error::*;
+use super::{adb_tool, AdbTarget};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::{
     path::{Path, PathBuf},
     process::Command,
@@ -21,6 +22,7 @@ pub struct Shell {
     list_permissions_g_r: bool,
     dump: Option<String>,
     path: Option<PathBuf>,
+    target: AdbTarget,
 }
 
 impl Shell {
@@ -30,6 +32,12 @@ impl Shell {
         }
     }
 
+    /// Selects the adb server and the device to run the command on
+    pub fn target(&mut self, target: &AdbTarget) -> &mut Self {
+        self.target = target.clone();
+        self
+    }
+
     /// Print current working directory
     pub fn pwd(&mut self, pwd: bool) -> &mut Self {
         self.pwd = pwd;
@@ -115,7 +123,37 @@ impl Shell {
     }
 
     pub fn run(&self) -> Result<()> {
-        let mut shell = Command::new("adb");
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut shell = adb_tool()?;
+        self.target.apply(&mut shell);
         shell.arg("shell");
         if self.pwd {
             shell.arg("pwd");
@@ -162,7 +200,11 @@ impl Shell {
         if let Some(path) = &self.path {
             shell.arg("path").arg(path);
         }
-        shell.output_err(true)?;
-        Ok(())
+        Ok(shell)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
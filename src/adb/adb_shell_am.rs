@@ -1,10 +1,10 @@
-use crate::error::*;
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::{
     path::{Path, PathBuf},
     process::Command,
 };
 
-use super::ScreenCompatibilityMode;
+use super::{adb_tool, AdbTarget, ScreenCompatibilityMode};
 
 #[derive(Clone, Default)]
 pub struct AdbShellAm {
@@ -38,6 +38,7 @@ pub struct AdbShellAm {
     display_density: Option<String>,
     to_uri: bool,
     to_intent_uri: bool,
+    target: AdbTarget,
 }
 
 impl AdbShellAm {
@@ -47,6 +48,12 @@ impl AdbShellAm {
         }
     }
 
+    /// Selects the adb server and the device to run the command on
+    pub fn target(&mut self, target: &AdbTarget) -> &mut Self {
+        self.target = target.clone();
+        self
+    }
+
     pub fn d(&mut self, d: bool) -> &mut Self {
         self.d = d;
         self
@@ -198,7 +205,37 @@ impl AdbShellAm {
     }
 
     pub fn run(&self) -> Result<()> {
-        let mut am = Command::new("adb");
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut am = adb_tool()?;
+        self.target.apply(&mut am);
         am.arg("shell");
         am.arg("am");
         if self.d {
@@ -294,7 +331,11 @@ impl AdbShellAm {
         if self.to_intent_uri {
             am.arg("to-intent-uri");
         }
-        am.output_err(true)?;
-        Ok(())
+        Ok(am)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
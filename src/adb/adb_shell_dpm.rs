@@ -1,4 +1,5 @@
-use crate::error::*;
+use super::{adb_tool, AdbTarget};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::process::Command;
 
 #[derive(Clone, Default)]
@@ -12,6 +13,7 @@ pub struct AdbShellDpm {
     clear_freeze_period_record: bool,
     force_network_logs: bool,
     force_security_logs: bool,
+    target: AdbTarget,
 }
 
 impl AdbShellDpm {
@@ -21,6 +23,12 @@ impl AdbShellDpm {
         }
     }
 
+    /// Selects the adb server and the device to run the command on
+    pub fn target(&mut self, target: &AdbTarget) -> &mut Self {
+        self.target = target.clone();
+        self
+    }
+
     pub fn name(&mut self, name: String) -> &mut Self {
         self.name = Some(name);
         self
@@ -46,7 +54,7 @@ impl AdbShellDpm {
     ///
     /// Options are:
     /// * `--user user_id`: Specify the target user. You can also pass --user
-    /// current to select the current user.
+    ///   current to select the current user.
     /// * `--name name`: Specify the human-readable organization name.
     pub fn set_profile_owner(&mut self, set_profile_owner: bool) -> &mut Self {
         self.set_profile_owner = set_profile_owner;
@@ -57,7 +65,7 @@ impl AdbShellDpm {
     ///
     /// Options are:
     /// * `--user user_id`: Specify the target user. You can also pass --user
-    /// current to select the current user.
+    ///   current to select the current user.
     /// * `--name name`: Specify the human-readable organization name.
     pub fn set_device_owner(&mut self, set_device_owner: bool) -> &mut Self {
         self.set_device_owner = set_device_owner;
@@ -69,7 +77,7 @@ impl AdbShellDpm {
     ///
     /// Options are:
     /// * `--user user_id`: Specify the target user. You can also pass --user
-    /// current to select the current user.
+    ///   current to select the current user.
     pub fn remove_active_admin(&mut self, remove_active_admin: bool) -> &mut Self {
         self.remove_active_admin = remove_active_admin;
         self
@@ -108,7 +116,37 @@ impl AdbShellDpm {
     }
 
     pub fn run(&self) -> Result<()> {
-        let mut dpm = Command::new("adb");
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut dpm = adb_tool()?;
+        self.target.apply(&mut dpm);
         dpm.arg("shell");
         dpm.arg("dpm");
         if let Some(name) = &self.name {
@@ -138,7 +176,11 @@ impl AdbShellDpm {
         if self.force_security_logs {
             dpm.arg("force-security-logs");
         }
-        dpm.output_err(true)?;
-        Ok(())
+        Ok(dpm)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
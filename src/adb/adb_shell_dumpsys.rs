@@ -1,4 +1,5 @@
-use crate::error::*;
+use super::{adb_tool, AdbTarget};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::process::Command;
 
 #[derive(Clone, Default)]
@@ -9,6 +10,7 @@ pub struct AdbShellDumpsys {
     battery_set_status: Option<String>,
     battery_reset: bool,
     battery_set_usb: Option<String>,
+    target: AdbTarget,
 }
 
 impl AdbShellDumpsys {
@@ -18,6 +20,12 @@ impl AdbShellDumpsys {
         }
     }
 
+    /// Selects the adb server and the device to run the command on
+    pub fn target(&mut self, target: &AdbTarget) -> &mut Self {
+        self.target = target.clone();
+        self
+    }
+
     pub fn activity(&mut self, activity: bool) -> &mut Self {
         self.activity = activity;
         self
@@ -49,7 +57,37 @@ impl AdbShellDumpsys {
     }
 
     pub fn run(&self) -> Result<()> {
-        let mut dumpsys = Command::new("adb");
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut dumpsys = adb_tool()?;
+        self.target.apply(&mut dumpsys);
         dumpsys.arg("shell");
         dumpsys.arg("dumpsys");
         if self.activity {
@@ -70,7 +108,11 @@ impl AdbShellDumpsys {
         if let Some(battery_set_usb) = &self.battery_set_usb {
             dumpsys.arg("battery set usb").arg(battery_set_usb);
         }
-        dumpsys.output_err(true)?;
-        Ok(())
+        Ok(dumpsys)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
@@ -1,10 +1,10 @@
-use crate::error::*;
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::{
     path::{Path, PathBuf},
     process::Command,
 };
 
-use super::InstallLocation;
+use super::{adb_tool, AdbTarget, InstallLocation};
 
 #[derive(Clone, Default)]
 pub struct AdbShellPm {
@@ -46,6 +46,7 @@ pub struct AdbShellPm {
     fastdeploy: bool,
     no_incremental: bool,
     user: Option<String>,
+    target: AdbTarget,
 }
 
 impl AdbShellPm {
@@ -55,6 +56,12 @@ impl AdbShellPm {
         }
     }
 
+    /// Selects the adb server and the device to run the command on
+    pub fn target(&mut self, target: &AdbTarget) -> &mut Self {
+        self.target = target.clone();
+        self
+    }
+
     /// Prints all packages, optionally only those whose package name contains
     /// the text in filter.
     ///
@@ -133,14 +140,14 @@ impl AdbShellPm {
     /// Options:
     /// * `-r`: Reinstall an existing app, keeping its data.
     /// * `-t`: Allow test APKs to be installed. Gradle generates a test APK when
-    /// you have only run or debugged your app or have used the Android Studio
-    /// Build > Build APK command. If the APK is built using a developer preview
-    /// SDK (if the targetSdkVersion is a letter instead of a number), you must
-    /// include the -t option with the install command if you are installing a
-    /// test APK.
+    ///   you have only run or debugged your app or have used the Android Studio
+    ///   Build > Build APK command. If the APK is built using a developer preview
+    ///   SDK (if the targetSdkVersion is a letter instead of a number), you must
+    ///   include the -t option with the install command if you are installing a
+    ///   test APK.
     /// * `-i installer_package_name`: Specify the installer package name.
     /// * `--install-location location`: Sets the install location using one of
-    /// the following values:
+    ///   the following values:
     ///     * `0`: Use the default install location
     ///     * `1`: Install on internal device storage
     ///     * `2`: Install on external media
@@ -148,15 +155,15 @@ impl AdbShellPm {
     /// * `-d`: Allow version code downgrade.
     /// * `-g`: Grant all permissions listed in the app manifest.
     /// * `--fastdeploy`: Quickly update an installed package by only updating the
-    /// parts of the APK that changed.
+    ///   parts of the APK that changed.
     /// * `--incremental`: Installs enough of the APK to launch the app while
-    /// streaming the remaining data in the background. To use this feature, you
-    /// must sign the APK, create an [`APK Signature Scheme v4 file`], and place this
-    /// file in the same directory as the APK. This feature is only supported on
-    /// certain devices. This option forces adb to use the feature or fail if it
-    /// is not supported (with verbose information on why it failed). Append the
-    /// `--wait` option to wait until the APK is fully installed before granting
-    /// access to the APK.
+    ///   streaming the remaining data in the background. To use this feature, you
+    ///   must sign the APK, create an [`APK Signature Scheme v4 file`], and place this
+    ///   file in the same directory as the APK. This feature is only supported on
+    ///   certain devices. This option forces adb to use the feature or fail if it
+    ///   is not supported (with verbose information on why it failed). Append the
+    ///   `--wait` option to wait until the APK is fully installed before granting
+    ///   access to the APK.
     pub fn install_path(&mut self, install_path: &Path) -> &mut Self {
         self.install_path = Some(install_path.to_owned());
         self
@@ -340,7 +347,37 @@ impl AdbShellPm {
     }
 
     pub fn run(&self) -> Result<()> {
-        let mut pm = Command::new("adb");
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut pm = adb_tool()?;
+        self.target.apply(&mut pm);
         pm.arg("shell");
         pm.arg("pm");
         if let Some(list_packages) = &self.list_packages {
@@ -460,7 +497,11 @@ impl AdbShellPm {
         if let Some(user) = &self.user {
             pm.arg("--user").arg(user);
         }
-        pm.output_err(true)?;
-        Ok(())
+        Ok(pm)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
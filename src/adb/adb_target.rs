@@ -0,0 +1,110 @@
+use std::process::Command;
+
+/// Selects the adb server and the device that an adb command runs against.
+///
+/// The same target can be shared between all adb builders, so a lab with several
+/// attached devices can run every command against a chosen one:
+///
+/// ```no_run
+/// use android_tools::adb::{AdbShellPm, AdbTarget};
+///
+/// let mut target = AdbTarget::new();
+/// target.serial("emulator-5554");
+/// AdbShellPm::new().target(&target).list_features(true).run().unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdbTarget {
+    serial: Option<String>,
+    transport_id: Option<u32>,
+    usb: bool,
+    emulator: bool,
+    host: Option<String>,
+    port: Option<u16>,
+}
+
+impl AdbTarget {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Use device with given serial (overrides $ANDROID_SERIAL)
+    pub fn serial(&mut self, serial: &str) -> &mut Self {
+        self.serial = Some(serial.to_owned());
+        self
+    }
+
+    /// Use device with given transport id
+    pub fn transport_id(&mut self, transport_id: u32) -> &mut Self {
+        self.transport_id = Some(transport_id);
+        self
+    }
+
+    /// Use USB device (error if multiple devices connected)
+    pub fn usb(&mut self, usb: bool) -> &mut Self {
+        self.usb = usb;
+        self
+    }
+
+    /// Use TCP/IP device (error if multiple TCP/IP devices available)
+    pub fn emulator(&mut self, emulator: bool) -> &mut Self {
+        self.emulator = emulator;
+        self
+    }
+
+    /// Name of adb server host [default=localhost]
+    pub fn host(&mut self, host: &str) -> &mut Self {
+        self.host = Some(host.to_owned());
+        self
+    }
+
+    /// Port of adb server [default=5037]
+    pub fn port(&mut self, port: u16) -> &mut Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Adds global adb options selecting the server and the device to given command.
+    /// Must be called before the adb subcommand is added
+    pub(crate) fn apply(&self, adb: &mut Command) {
+        if let Some(host) = &self.host {
+            adb.arg("-H").arg(host);
+        }
+        if let Some(port) = &self.port {
+            adb.arg("-P").arg(port.to_string());
+        }
+        if let Some(serial) = &self.serial {
+            adb.arg("-s").arg(serial);
+        }
+        if let Some(transport_id) = &self.transport_id {
+            adb.arg("-t").arg(transport_id.to_string());
+        }
+        if self.usb {
+            adb.arg("-d");
+        }
+        if self.emulator {
+            adb.arg("-e");
+        }
+    }
+
+    /// Host and port of the adb server, if they are selected
+    pub(crate) fn server(&self) -> (Option<&str>, Option<u16>) {
+        (self.host.as_deref(), self.port)
+    }
+
+    /// Host request that switches a connection to the adb server to the target device
+    pub(crate) fn transport_request(&self) -> String {
+        if let Some(serial) = &self.serial {
+            format!("host:transport:{}", serial)
+        } else if let Some(transport_id) = &self.transport_id {
+            format!("host:transport-id:{}", transport_id)
+        } else if self.usb {
+            "host:transport-usb".to_owned()
+        } else if self.emulator {
+            "host:transport-local".to_owned()
+        } else {
+            "host:transport-any".to_owned()
+        }
+    }
+}
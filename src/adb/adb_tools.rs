@@ -1,4 +1,5 @@
-use crate::error::*;
+use super::{adb_tool, AdbTarget, Device};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::{
     path::{Path, PathBuf},
     process::Command,
@@ -9,19 +10,13 @@ pub struct AdbTools {
     install: Option<PathBuf>,
     forward: Option<String>,
     connect: Option<String>,
-    devices: bool,
-    devices_l: bool,
     kill_server: bool,
     tcpip: Option<String>,
     help: bool,
-    d: bool,
-    e: bool,
-    s: bool,
     a: bool,
-    h: Option<String>,
-    p: Option<String>,
     l: Option<String>,
     version: bool,
+    target: AdbTarget,
 }
 
 impl AdbTools {
@@ -47,16 +42,6 @@ impl AdbTools {
         self
     }
 
-    pub fn devices(&mut self, devices: bool) -> &mut Self {
-        self.devices = devices;
-        self
-    }
-
-    pub fn devices_l(&mut self, devices_l: bool) -> &mut Self {
-        self.devices_l = devices_l;
-        self
-    }
-
     pub fn kill_server(&mut self, kill_server: bool) -> &mut Self {
         self.kill_server = kill_server;
         self
@@ -73,21 +58,27 @@ impl AdbTools {
         self
     }
 
+    /// Selects the adb server and the device to run the command on
+    pub fn target(&mut self, target: &AdbTarget) -> &mut Self {
+        self.target = target.clone();
+        self
+    }
+
     /// Use USB device (error if multiple devices connected)
     pub fn d(&mut self, d: bool) -> &mut Self {
-        self.d = d;
+        self.target.usb(d);
         self
     }
 
     /// Use TCP/IP device (error if multiple TCP/IP devices available)
     pub fn e(&mut self, e: bool) -> &mut Self {
-        self.e = e;
+        self.target.emulator(e);
         self
     }
 
     /// Use device with given serial (overrides $ANDROID_SERIAL)
-    pub fn s(&mut self, s: bool) -> &mut Self {
-        self.s = s;
+    pub fn s(&mut self, serial: &str) -> &mut Self {
+        self.target.serial(serial);
         self
     }
 
@@ -98,14 +89,14 @@ impl AdbTools {
     }
 
     /// Name of adb server host [default=localhost]
-    pub fn h(&mut self, h: String) -> &mut Self {
-        self.h = Some(h);
+    pub fn h(&mut self, h: &str) -> &mut Self {
+        self.target.host(h);
         self
     }
 
     /// Port of adb server [default=5037]
-    pub fn p(&mut self, p: String) -> &mut Self {
-        self.p = Some(p);
+    pub fn p(&mut self, p: u16) -> &mut Self {
+        self.target.port(p);
         self
     }
 
@@ -119,10 +110,53 @@ impl AdbTools {
         self.version = version;
         self
     }
+
+    /// Runs `adb devices -l` and returns the list of attached devices
+    pub fn devices(&self) -> Result<Vec<Device>> {
+        let mut adb = adb_tool()?;
+        self.target.apply(&mut adb);
+        adb.arg("devices").arg("-l");
+        let output = adb.output_err(false)?;
+        Device::parse_list(&String::from_utf8_lossy(&output.stdout))
+    }
+
     pub fn run(&self) -> Result<()> {
-        let mut adb = Command::new("adb");
-        if self.devices {
-            adb.arg("devices");
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut adb = adb_tool()?;
+        self.target.apply(&mut adb);
+        if self.a {
+            adb.arg("-a");
+        }
+        if let Some(l) = &self.l {
+            adb.arg("-L").arg(l);
         }
         if let Some(install) = &self.install {
             adb.arg("install").arg(install);
@@ -133,9 +167,6 @@ impl AdbTools {
         if let Some(connect) = &self.connect {
             adb.arg("connect").arg(connect);
         }
-        if self.devices_l {
-            adb.arg("devices -l");
-        }
         if self.kill_server {
             adb.arg("kill-server");
         }
@@ -145,31 +176,14 @@ impl AdbTools {
         if self.help {
             adb.arg("--help");
         }
-        if self.d {
-            adb.arg("-d");
-        }
-        if self.e {
-            adb.arg("-e");
-        }
-        if self.s {
-            adb.arg("-s");
-        }
-        if self.a {
-            adb.arg("-a");
-        }
-        if let Some(h) = &self.h {
-            adb.arg("-H").arg(h);
-        }
-        if let Some(p) = &self.p {
-            adb.arg("-P").arg(p);
-        }
-        if let Some(l) = &self.l {
-            adb.arg("-L").arg(l);
-        }
         if self.version {
             adb.arg("--version");
         }
-        adb.output_err(true)?;
-        Ok(())
+        Ok(adb)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
@@ -1,4 +1,10 @@
+use crate::{error::*, executor::current_executor, sdk::AndroidSdk};
+use std::process::Command;
+
+mod adb_client;
+mod adb_devices;
 mod adb_enum;
+mod adb_logcat;
 mod adb_pull;
 mod adb_push;
 mod adb_shell;
@@ -6,9 +12,13 @@ mod adb_shell_am;
 mod adb_shell_dpm;
 mod adb_shell_dumpsys;
 mod adb_shell_pm;
+mod adb_target;
 mod adb_tools;
 
+pub use adb_client::*;
+pub use adb_devices::*;
 pub use adb_enum::*;
+pub use adb_logcat::*;
 pub use adb_pull::*;
 pub use adb_push::*;
 pub use adb_shell::*;
@@ -16,4 +26,27 @@ pub use adb_shell_am::*;
 pub use adb_shell_dpm::*;
 pub use adb_shell_dumpsys::*;
 pub use adb_shell_pm::*;
+pub use adb_target::*;
 pub use adb_tools::*;
+
+/// Find `adb` executable binary file and initialize it. The path from `ADB_PATH`
+/// environment variable is used if it's set, then `adb` from `PATH` with `which`
+/// feature, then `adb` from `platform-tools` of the Android SDK
+pub fn adb_tool() -> Result<Command> {
+    current_executor().locate("adb", &|| {
+        if let Some(adb) = std::env::var_os("ADB_PATH") {
+            return Ok(Command::new(adb));
+        }
+        #[cfg(feature = "which")]
+        if let Ok(adb) = which::which(bin!("adb")) {
+            return Ok(Command::new(adb));
+        }
+        let adb = AndroidSdk::from_env()
+            .ok()
+            .and_then(|sdk| sdk.platform_tools_dir())
+            .map(|platform_tools| platform_tools.join(bin!("adb")))
+            .filter(|adb| adb.exists())
+            .ok_or_else(|| Error::CmdNotFound("adb".to_owned()))?;
+        Ok(Command::new(adb))
+    })
+}
@@ -0,0 +1,179 @@
+use super::{
+    signing_block::*, SigningCertificateLineage, SigningKey, LINEAGE_ATTR_ID,
+    STRIPPING_PROTECTION_ATTR_ID,
+};
+use crate::error::*;
+use std::path::{Path, PathBuf};
+
+/// Android 9, the first platform that verifies v3 signatures
+pub(crate) const V3_MIN_SDK_VERSION: u32 = 28;
+pub(crate) const V3_MAX_SDK_VERSION: u32 = i32::MAX as u32;
+
+/// # ApkSigner
+/// Signs APK with APK Signature Scheme v2 and v3 without running any external tools.
+/// The APK Signing Block is inserted before the central directory, replacing existing
+/// one. JAR (v1) signatures are left as they are, use
+/// [`JarSigner`](crate::java_tools::JarSigner) before `ApkSigner` when v1 is needed.
+///
+/// With key rotation the v3 block is signed with the newest key of the lineage, while
+/// v2 should be signed with the original key, as devices older than Android 9 only
+/// verify v2.
+///
+/// ```no_run
+/// use android_tools::apksig::{ApkSigner, SigningKey};
+/// use std::path::Path;
+///
+/// let key = SigningKey::from_pkcs12(Path::new("release.p12"), "android", None).unwrap();
+/// ApkSigner::new(Path::new("app-unsigned.apk"), Path::new("app.apk"), &key)
+///     .run()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct ApkSigner {
+    input_apk: PathBuf,
+    output_apk: PathBuf,
+    key: SigningKey,
+    v2_key: Option<SigningKey>,
+    lineage: Option<SigningCertificateLineage>,
+    v2_signing_enabled: bool,
+    v3_signing_enabled: bool,
+}
+
+impl ApkSigner {
+    /// Initialize signer with input APK, path to write signed APK to and the signing key.
+    /// Output path may be the same as the input path
+    pub fn new(input_apk: &Path, output_apk: &Path, key: &SigningKey) -> Self {
+        Self {
+            input_apk: input_apk.to_owned(),
+            output_apk: output_apk.to_owned(),
+            key: key.clone(),
+            v2_key: None,
+            lineage: None,
+            v2_signing_enabled: true,
+            v3_signing_enabled: true,
+        }
+    }
+
+    /// Whether to sign with APK Signature Scheme v2 [default: true]
+    pub fn v2_signing_enabled(&mut self, v2_signing_enabled: bool) -> &mut Self {
+        self.v2_signing_enabled = v2_signing_enabled;
+        self
+    }
+
+    /// Whether to sign with APK Signature Scheme v3 [default: true]
+    pub fn v3_signing_enabled(&mut self, v3_signing_enabled: bool) -> &mut Self {
+        self.v3_signing_enabled = v3_signing_enabled;
+        self
+    }
+
+    /// Key to sign v2 block with instead of the signing key. With key rotation it's
+    /// the original key of the lineage
+    pub fn v2_key(&mut self, v2_key: &SigningKey) -> &mut Self {
+        self.v2_key = Some(v2_key.clone());
+        self
+    }
+
+    /// Signing certificate lineage added to v3 signature. The signing key must be the
+    /// newest key of the lineage
+    pub fn lineage(&mut self, lineage: &SigningCertificateLineage) -> &mut Self {
+        self.lineage = Some(lineage.clone());
+        self
+    }
+
+    /// Signs the input APK and writes it to the output path
+    pub fn run(&self) -> Result<()> {
+        let apk = std::fs::read(&self.input_apk)?;
+        let signed = self.sign(&apk)?;
+        std::fs::write(&self.output_apk, signed)?;
+        Ok(())
+    }
+
+    /// Returns signed copy of APK contents
+    pub fn sign(&self, apk: &[u8]) -> Result<Vec<u8>> {
+        if !self.v2_signing_enabled && !self.v3_signing_enabled {
+            return Err(Error::InvalidArguments(
+                "at least one of v2 and v3 signing must be enabled".to_owned(),
+            ));
+        }
+        if let Some(lineage) = &self.lineage {
+            if lineage.last_certificate() != self.key.certificate() {
+                return Err(Error::InvalidArguments(
+                    "signing key isn't the newest key of the lineage".to_owned(),
+                ));
+            }
+        }
+        let sections = ZipSections::find(apk)?;
+        let digest = sections.content_digest(apk, ContentDigest::Sha256);
+        let mut blocks = Vec::new();
+        if self.v2_signing_enabled {
+            let key = self.v2_key.as_ref().unwrap_or(&self.key);
+            blocks.push((V2_BLOCK_ID, self.v2_block(key, &digest)?));
+        }
+        if self.v3_signing_enabled {
+            blocks.push((V3_BLOCK_ID, self.v3_block(&digest)?));
+        }
+        Ok(sections.with_signing_block(apk, &encode_signing_block(&blocks)))
+    }
+
+    fn v2_block(&self, key: &SigningKey, digest: &[u8]) -> Result<Vec<u8>> {
+        let mut attributes = Vec::new();
+        if self.v3_signing_enabled {
+            // Prevents stripping v3 signature to make devices fall back to v2
+            let mut attribute = BlockWriter::default();
+            attribute.u32(STRIPPING_PROTECTION_ATTR_ID).u32(3);
+            attributes.push(attribute.0);
+        }
+        let mut signed_data = BlockWriter::default();
+        signed_data
+            .sequence(&[digest_item(digest)])
+            .sequence(key.certificates())
+            .sequence(&attributes);
+        let mut signer = BlockWriter::default();
+        signer
+            .prefixed(&signed_data.0)
+            .sequence(&[signature_item(&key.sign(&signed_data.0))])
+            .prefixed(&key.public_key()?);
+        let mut block = BlockWriter::default();
+        block.sequence(&[signer.0]);
+        Ok(block.0)
+    }
+
+    fn v3_block(&self, digest: &[u8]) -> Result<Vec<u8>> {
+        let mut attributes = Vec::new();
+        if let Some(lineage) = &self.lineage {
+            let mut attribute = BlockWriter::default();
+            attribute.u32(LINEAGE_ATTR_ID);
+            attribute.0.extend_from_slice(&lineage.to_bytes());
+            attributes.push(attribute.0);
+        }
+        let mut signed_data = BlockWriter::default();
+        signed_data
+            .sequence(&[digest_item(digest)])
+            .sequence(self.key.certificates())
+            .u32(V3_MIN_SDK_VERSION)
+            .u32(V3_MAX_SDK_VERSION)
+            .sequence(&attributes);
+        let mut signer = BlockWriter::default();
+        signer
+            .prefixed(&signed_data.0)
+            .u32(V3_MIN_SDK_VERSION)
+            .u32(V3_MAX_SDK_VERSION)
+            .sequence(&[signature_item(&self.key.sign(&signed_data.0))])
+            .prefixed(&self.key.public_key()?);
+        let mut block = BlockWriter::default();
+        block.sequence(&[signer.0]);
+        Ok(block.0)
+    }
+}
+
+fn digest_item(digest: &[u8]) -> Vec<u8> {
+    let mut item = BlockWriter::default();
+    item.u32(RSA_PKCS1_V1_5_WITH_SHA256).prefixed(digest);
+    item.0
+}
+
+fn signature_item(signature: &[u8]) -> Vec<u8> {
+    let mut item = BlockWriter::default();
+    item.u32(RSA_PKCS1_V1_5_WITH_SHA256).prefixed(signature);
+    item.0
+}
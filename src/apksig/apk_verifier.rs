@@ -0,0 +1,313 @@
+use super::{
+    apk_signer::{V3_MAX_SDK_VERSION, V3_MIN_SDK_VERSION},
+    signing_block::*,
+    signing_key::{is_supported_signature, public_key_der, verify_signature},
+    SigningCertificateLineage, LINEAGE_ATTR_ID, STRIPPING_PROTECTION_ATTR_ID,
+};
+use crate::error::*;
+use der::Decode;
+use std::{
+    cell::OnceCell,
+    path::{Path, PathBuf},
+};
+use x509_cert::Certificate;
+
+/// APK signature scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SignatureScheme {
+    /// JAR signing
+    V1,
+    /// APK Signature Scheme v2
+    V2,
+    /// APK Signature Scheme v3
+    V3,
+}
+
+impl std::fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::V1 => write!(f, "v1"),
+            Self::V2 => write!(f, "v2"),
+            Self::V3 => write!(f, "v3"),
+        }
+    }
+}
+
+/// # ApkVerifier
+/// Verifies APK Signature Scheme v2 and v3 signatures and reports signature schemes
+/// the APK is signed with. Presence of JAR (v1) signature is reported, but the JAR
+/// signature itself isn't verified.
+///
+/// Only RSA signatures are verified. Signers with ECDSA or DSA signatures only are
+/// reported with [`ApkSignerInfo::verified`] set to `false`, after their content
+/// digests are checked.
+///
+/// ```no_run
+/// use android_tools::apksig::ApkVerifier;
+/// use std::path::Path;
+///
+/// let verification = ApkVerifier::new(Path::new("app.apk")).run().unwrap();
+/// for signer in &verification.v3_signers {
+///     println!("{}", signer.certificate_subject().unwrap());
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct ApkVerifier {
+    apk: PathBuf,
+}
+
+/// Result of the successful APK verification
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApkVerification {
+    /// Signature schemes the APK is signed with
+    pub schemes: Vec<SignatureScheme>,
+    pub v2_signers: Vec<ApkSignerInfo>,
+    pub v3_signers: Vec<ApkSignerInfo>,
+}
+
+/// Signer of v2 or v3 signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApkSignerInfo {
+    /// IDs of the signature algorithms of the signer, e.g. `0x0103` for
+    /// RSASSA-PKCS1-v1_5 with SHA2-256
+    pub signature_algorithms: Vec<u32>,
+    /// Whether a signature of the signer was verified. It's `false` if none of the
+    /// signature algorithms is supported
+    pub verified: bool,
+    /// DER encoded certificate chain starting with the signing certificate
+    pub certificates: Vec<Vec<u8>>,
+    /// Platform versions v3 signer applies to
+    pub min_sdk_version: Option<u32>,
+    pub max_sdk_version: Option<u32>,
+    /// Signing certificate lineage of v3 signer
+    pub lineage: Option<SigningCertificateLineage>,
+}
+
+impl ApkVerifier {
+    pub fn new(apk: &Path) -> Self {
+        Self {
+            apk: apk.to_owned(),
+        }
+    }
+
+    /// Verifies the APK. Returns error if any of v2 or v3 signatures is invalid.
+    /// Signatures of unsupported algorithms are skipped
+    pub fn run(&self) -> Result<ApkVerification> {
+        Self::verify(&std::fs::read(&self.apk)?)
+    }
+
+    /// Verifies APK contents
+    pub fn verify(apk: &[u8]) -> Result<ApkVerification> {
+        let sections = ZipSections::find(apk)?;
+        let mut verification = ApkVerification::default();
+        let has_v1 = sections.entry_names(apk).iter().any(|name| {
+            let name = name.to_ascii_uppercase();
+            name.starts_with("META-INF/") && name.ends_with(".SF")
+        });
+        if has_v1 {
+            verification.schemes.push(SignatureScheme::V1);
+        }
+        let Some(block) = &sections.signing_block else {
+            return Ok(verification);
+        };
+        let pairs = parse_signing_block(&apk[block.clone()])?;
+        let find = |id| {
+            pairs
+                .iter()
+                .find(|(block_id, _)| *block_id == id)
+                .map(|(_, value)| *value)
+        };
+        let v2_block = find(V2_BLOCK_ID);
+        let v3_block = find(V3_BLOCK_ID);
+        if v2_block.is_none() && v3_block.is_none() {
+            return Ok(verification);
+        }
+        let digests = ContentDigests {
+            apk,
+            sections: &sections,
+            sha256: OnceCell::new(),
+            sha512: OnceCell::new(),
+        };
+        if let Some(v2_block) = v2_block {
+            for signer in BlockReader(v2_block).sequence()? {
+                let (signer, attributes) = verify_signer(signer, &digests, false)?;
+                let stripped = attributes.iter().any(|(id, value)| {
+                    *id == STRIPPING_PROTECTION_ATTR_ID
+                        && value.get(..4) == Some(&3u32.to_le_bytes()[..])
+                }) && v3_block.is_none();
+                if stripped {
+                    return Err(Error::SignatureVerificationFailed(
+                        "v2 signature requires v3 signature, which was stripped".to_owned(),
+                    ));
+                }
+                verification.v2_signers.push(signer);
+            }
+            verification.schemes.push(SignatureScheme::V2);
+        }
+        if let Some(v3_block) = v3_block {
+            for signer in BlockReader(v3_block).sequence()? {
+                let (mut signer, attributes) = verify_signer(signer, &digests, true)?;
+                if let Some((_, lineage)) = attributes.iter().find(|(id, _)| *id == LINEAGE_ATTR_ID)
+                {
+                    let lineage = SigningCertificateLineage::from_bytes(lineage)?;
+                    if lineage.last_certificate() != signer.certificates[0] {
+                        return Err(Error::SignatureVerificationFailed(
+                            "signing certificate isn't the last certificate of the lineage"
+                                .to_owned(),
+                        ));
+                    }
+                    signer.lineage = Some(lineage);
+                }
+                verification.v3_signers.push(signer);
+            }
+            verification.schemes.push(SignatureScheme::V3);
+        }
+        Ok(verification)
+    }
+}
+
+impl ApkVerification {
+    pub fn is_signed_with(&self, scheme: SignatureScheme) -> bool {
+        self.schemes.contains(&scheme)
+    }
+
+    /// Whether signatures of all v2 and v3 signers were verified
+    pub fn is_verified(&self) -> bool {
+        self.v2_signers
+            .iter()
+            .chain(&self.v3_signers)
+            .all(|signer| signer.verified)
+    }
+}
+
+impl ApkSignerInfo {
+    /// Distinguished name of the signing certificate subject, e.g. `CN=Android Debug`
+    pub fn certificate_subject(&self) -> Result<String> {
+        let certificate = Certificate::from_der(&self.certificates[0])
+            .map_err(|err| Error::InvalidSigningKey(format!("invalid certificate: {}", err)))?;
+        Ok(certificate.tbs_certificate.subject.to_string())
+    }
+}
+
+/// ID-value pairs of additional attributes of the signed data
+type Attributes<'a> = Vec<(u32, &'a [u8])>;
+
+/// Content digests of the APK, computed once for every digest algorithm
+struct ContentDigests<'a> {
+    apk: &'a [u8],
+    sections: &'a ZipSections,
+    sha256: OnceCell<Vec<u8>>,
+    sha512: OnceCell<Vec<u8>>,
+}
+
+impl ContentDigests<'_> {
+    fn get(&self, digest: ContentDigest) -> &[u8] {
+        let cell = match digest {
+            ContentDigest::Sha256 => &self.sha256,
+            ContentDigest::Sha512 => &self.sha512,
+        };
+        cell.get_or_init(|| self.sections.content_digest(self.apk, digest))
+    }
+}
+
+/// Verifies signatures and content digests of the signer. Signatures of unsupported
+/// algorithms are skipped. Returns the signer and its additional attributes
+fn verify_signer<'a>(
+    signer: &'a [u8],
+    content_digests: &ContentDigests,
+    v3: bool,
+) -> Result<(ApkSignerInfo, Attributes<'a>)> {
+    let failed = |reason: &str| Error::SignatureVerificationFailed(reason.to_owned());
+    let mut reader = BlockReader(signer);
+    let signed_data = reader.prefixed()?;
+    let sdk_versions = if v3 {
+        Some((reader.u32()?, reader.u32()?))
+    } else {
+        None
+    };
+    let signatures = reader.sequence()?;
+    let public_key = reader.prefixed()?;
+
+    let mut signature_algorithms = Vec::new();
+    let mut verified = false;
+    for signature in signatures {
+        let mut signature = BlockReader(signature);
+        let algorithm = signature.u32()?;
+        signature_algorithms.push(algorithm);
+        if is_supported_signature(algorithm) {
+            verify_signature(public_key, algorithm, signed_data, signature.prefixed()?)?;
+            verified = true;
+        }
+    }
+    if signature_algorithms.is_empty() {
+        return Err(failed("signer has no signatures"));
+    }
+
+    let mut signed = BlockReader(signed_data);
+    let digests = signed.sequence()?;
+    let certificates = signed
+        .sequence()?
+        .into_iter()
+        .map(<[u8]>::to_vec)
+        .collect::<Vec<_>>();
+    if v3 {
+        let signed_versions = (signed.u32()?, signed.u32()?);
+        if Some(signed_versions) != sdk_versions {
+            return Err(failed(
+                "SDK versions of signer and signed data are different",
+            ));
+        }
+    }
+    let attributes = signed
+        .sequence()?
+        .into_iter()
+        .map(|attribute| {
+            let mut attribute = BlockReader(attribute);
+            Ok((attribute.u32()?, attribute.0))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut digest_algorithms = Vec::new();
+    for digest in digests {
+        let mut digest = BlockReader(digest);
+        let algorithm = digest.u32()?;
+        digest_algorithms.push(algorithm);
+        let signed_digest = digest.prefixed()?;
+        if let Some(content_digest) = ContentDigest::of(algorithm) {
+            if signed_digest != content_digests.get(content_digest) {
+                return Err(failed(
+                    "APK contents digest doesn't match the signed digest",
+                ));
+            }
+        }
+    }
+    if digest_algorithms != signature_algorithms {
+        return Err(failed("signature and digest algorithms are different"));
+    }
+    let certificate = certificates
+        .first()
+        .ok_or_else(|| failed("signer has no certificates"))?;
+    if public_key_der(certificate)? != public_key {
+        return Err(failed("public key doesn't match the signing certificate"));
+    }
+    let (min_sdk_version, max_sdk_version) = match sdk_versions {
+        Some((min, max)) => {
+            if min < V3_MIN_SDK_VERSION || max > V3_MAX_SDK_VERSION || min > max {
+                return Err(failed("invalid SDK versions of v3 signer"));
+            }
+            (Some(min), Some(max))
+        }
+        None => (None, None),
+    };
+    Ok((
+        ApkSignerInfo {
+            signature_algorithms,
+            verified,
+            certificates,
+            min_sdk_version,
+            max_sdk_version,
+            lineage: None,
+        },
+        attributes,
+    ))
+}
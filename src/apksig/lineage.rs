@@ -0,0 +1,175 @@
+use super::{
+    signing_block::{BlockReader, BlockWriter, RSA_PKCS1_V1_5_WITH_SHA256},
+    signing_key::{is_supported_signature, public_key_der, verify_signature},
+    SigningKey,
+};
+use crate::error::*;
+
+/// Version of the encoded lineage
+const LINEAGE_VERSION: u32 = 1;
+
+/// Capabilities granted to the previous signing certificates by default: installed
+/// data, shared user ID, permissions and auth. Rollback is not allowed
+pub const DEFAULT_CAPABILITIES: u32 = 0x01 | 0x02 | 0x04 | 0x10;
+
+/// Signing certificate lineage of APK Signature Scheme v3. It proves the rotation of the
+/// signing key: every certificate is signed by the key of the previous one, so devices
+/// trust APKs signed by the newest key as updates of APKs signed by the older ones.
+///
+/// ```no_run
+/// use android_tools::apksig::{SigningCertificateLineage, SigningKey};
+///
+/// let old_key = SigningKey::generate(2048, "CN=Old").unwrap();
+/// let new_key = SigningKey::generate(2048, "CN=New").unwrap();
+/// let mut lineage = SigningCertificateLineage::new(&old_key);
+/// lineage.rotate(&old_key, &new_key).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningCertificateLineage {
+    nodes: Vec<LineageNode>,
+}
+
+/// Certificate of the signing certificate lineage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineageNode {
+    /// DER encoded certificate
+    pub certificate: Vec<u8>,
+    /// Capabilities the certificate keeps after rotation
+    pub flags: u32,
+    /// Signature algorithm the previous certificate's key signed this node with, `0`
+    /// for the first node
+    pub parent_signature_algorithm: u32,
+    /// Signature algorithm this certificate's key signed the next node with, `0` for
+    /// the last node
+    pub signature_algorithm: u32,
+    /// Signature of the previous certificate's key, empty for the first node
+    pub signature: Vec<u8>,
+}
+
+impl SigningCertificateLineage {
+    /// Starts the lineage with the original signing key
+    pub fn new(first: &SigningKey) -> Self {
+        Self {
+            nodes: vec![LineageNode {
+                certificate: first.certificate().to_vec(),
+                flags: DEFAULT_CAPABILITIES,
+                parent_signature_algorithm: 0,
+                signature_algorithm: 0,
+                signature: Vec::new(),
+            }],
+        }
+    }
+
+    /// Adds the next signing key signed by the current key. The current key must be the
+    /// last key of the lineage
+    pub fn rotate(&mut self, current: &SigningKey, next: &SigningKey) -> Result<&mut Self> {
+        let last = self.nodes.last_mut().expect("lineage is never empty");
+        if last.certificate != current.certificate() {
+            return Err(Error::InvalidArguments(
+                "current key isn't the last key of the lineage".to_owned(),
+            ));
+        }
+        last.signature_algorithm = RSA_PKCS1_V1_5_WITH_SHA256;
+        let signed_data = signed_data(next.certificate(), RSA_PKCS1_V1_5_WITH_SHA256);
+        self.nodes.push(LineageNode {
+            certificate: next.certificate().to_vec(),
+            flags: DEFAULT_CAPABILITIES,
+            parent_signature_algorithm: RSA_PKCS1_V1_5_WITH_SHA256,
+            signature_algorithm: 0,
+            signature: current.sign(&signed_data),
+        });
+        Ok(self)
+    }
+
+    /// Certificates from the original to the newest
+    pub fn nodes(&self) -> &[LineageNode] {
+        &self.nodes
+    }
+
+    /// DER encoded certificate of the newest key
+    pub fn last_certificate(&self) -> &[u8] {
+        &self
+            .nodes
+            .last()
+            .expect("lineage is never empty")
+            .certificate
+    }
+
+    /// Encodes the lineage as it's stored in the v3 signer attribute
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut lineage = BlockWriter::default();
+        lineage.u32(LINEAGE_VERSION);
+        for node in &self.nodes {
+            let mut encoded = BlockWriter::default();
+            encoded
+                .prefixed(&signed_data(
+                    &node.certificate,
+                    node.parent_signature_algorithm,
+                ))
+                .u32(node.flags)
+                .u32(node.signature_algorithm)
+                .prefixed(&node.signature);
+            lineage.prefixed(&encoded.0);
+        }
+        lineage.0
+    }
+
+    /// Decodes the lineage and verifies that every certificate is signed by the key of
+    /// the previous one
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let invalid = |reason: &str| {
+            Error::SignatureVerificationFailed(format!(
+                "invalid signing certificate lineage: {}",
+                reason
+            ))
+        };
+        let mut reader = BlockReader(bytes);
+        if reader.u32()? != LINEAGE_VERSION {
+            return Err(invalid("unsupported version"));
+        }
+        let mut nodes: Vec<LineageNode> = Vec::new();
+        while !reader.is_empty() {
+            let mut node = BlockReader(reader.prefixed()?);
+            let signed_data = node.prefixed()?;
+            let flags = node.u32()?;
+            let signature_algorithm = node.u32()?;
+            let signature = node.prefixed()?.to_vec();
+            let mut signed = BlockReader(signed_data);
+            let certificate = signed.prefixed()?.to_vec();
+            let parent_signature_algorithm = signed.u32()?;
+            if let Some(parent) = nodes.last() {
+                if !is_supported_signature(parent.signature_algorithm)
+                    || parent_signature_algorithm != parent.signature_algorithm
+                {
+                    return Err(invalid("unsupported signature algorithm"));
+                }
+                verify_signature(
+                    &public_key_der(&parent.certificate)?,
+                    parent.signature_algorithm,
+                    signed_data,
+                    &signature,
+                )?;
+            }
+            nodes.push(LineageNode {
+                certificate,
+                flags,
+                parent_signature_algorithm,
+                signature_algorithm,
+                signature,
+            });
+        }
+        if nodes.is_empty() {
+            return Err(invalid("lineage is empty"));
+        }
+        Ok(Self { nodes })
+    }
+}
+
+/// Data signed by the previous key in the lineage node
+fn signed_data(certificate: &[u8], parent_signature_algorithm: u32) -> Vec<u8> {
+    let mut signed_data = BlockWriter::default();
+    signed_data
+        .prefixed(certificate)
+        .u32(parent_signature_algorithm);
+    signed_data.0
+}
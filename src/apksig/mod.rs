@@ -0,0 +1,21 @@
+//! Native implementation of APK Signature Scheme v2 and v3.
+//!
+//! Signs and verifies APKs without `apksigner`, Java or Android SDK. See
+//! [`APK Signature Scheme v2`](https://source.android.com/docs/security/features/apksigning/v2)
+//! and [`v3`](https://source.android.com/docs/security/features/apksigning/v3).
+
+mod apk_signer;
+mod apk_verifier;
+mod lineage;
+mod signing_block;
+mod signing_key;
+
+pub use apk_signer::*;
+pub use apk_verifier::*;
+pub use lineage::*;
+pub use signing_key::SigningKey;
+
+/// ID of the v2 signer attribute that requires v3 signature to be present
+pub(crate) const STRIPPING_PROTECTION_ATTR_ID: u32 = 0xbeef_f00d;
+/// ID of the v3 signer attribute with the signing certificate lineage
+pub(crate) const LINEAGE_ATTR_ID: u32 = 0x3ba0_6f8c;
@@ -0,0 +1,297 @@
+use crate::error::*;
+use sha2::{Digest, Sha256, Sha512};
+use std::ops::Range;
+
+/// ID of the APK Signature Scheme v2 block in the APK Signing Block
+pub(crate) const V2_BLOCK_ID: u32 = 0x7109_871a;
+/// ID of the APK Signature Scheme v3 block in the APK Signing Block
+pub(crate) const V3_BLOCK_ID: u32 = 0xf053_68c0;
+/// RSASSA-PSS with SHA2-256 digest, content digested by 1 MB chunks with SHA2-256
+pub(crate) const RSA_PSS_WITH_SHA256: u32 = 0x0101;
+/// RSASSA-PSS with SHA2-512 digest, content digested by 1 MB chunks with SHA2-512
+pub(crate) const RSA_PSS_WITH_SHA512: u32 = 0x0102;
+/// RSASSA-PKCS1-v1_5 with SHA2-256 digest, content digested by 1 MB chunks with SHA2-256
+pub(crate) const RSA_PKCS1_V1_5_WITH_SHA256: u32 = 0x0103;
+/// RSASSA-PKCS1-v1_5 with SHA2-512 digest, content digested by 1 MB chunks with SHA2-512.
+/// Used by apksigner for RSA keys longer than 3072 bits
+pub(crate) const RSA_PKCS1_V1_5_WITH_SHA512: u32 = 0x0104;
+/// ECDSA with SHA2-256 digest, content digested by 1 MB chunks with SHA2-256
+pub(crate) const ECDSA_WITH_SHA256: u32 = 0x0201;
+/// ECDSA with SHA2-512 digest, content digested by 1 MB chunks with SHA2-512
+pub(crate) const ECDSA_WITH_SHA512: u32 = 0x0202;
+/// DSA with SHA2-256 digest, content digested by 1 MB chunks with SHA2-256
+pub(crate) const DSA_WITH_SHA256: u32 = 0x0301;
+
+const APK_SIG_BLOCK_MAGIC: &[u8; 16] = b"APK Sig Block 42";
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const EOCD_MIN_SIZE: usize = 22;
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Digest of the APK contents a signature algorithm signs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ContentDigest {
+    Sha256,
+    Sha512,
+}
+
+impl ContentDigest {
+    /// Content digest of the signature algorithm. `None` for algorithms digesting the
+    /// contents differently, like the ones with verity, and unknown algorithms
+    pub fn of(algorithm: u32) -> Option<Self> {
+        match algorithm {
+            RSA_PSS_WITH_SHA256
+            | RSA_PKCS1_V1_5_WITH_SHA256
+            | ECDSA_WITH_SHA256
+            | DSA_WITH_SHA256 => Some(Self::Sha256),
+            RSA_PSS_WITH_SHA512 | RSA_PKCS1_V1_5_WITH_SHA512 | ECDSA_WITH_SHA512 => {
+                Some(Self::Sha512)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Offsets of the APK parts covered by v2 and v3 signatures
+#[derive(Debug, Clone)]
+pub(crate) struct ZipSections {
+    /// Position of the existing APK Signing Block
+    pub signing_block: Option<Range<usize>>,
+    /// End of the local file entries, where the APK Signing Block starts
+    pub entries_end: usize,
+    pub central_directory: Range<usize>,
+    pub eocd_offset: usize,
+}
+
+impl ZipSections {
+    /// Finds the End of Central Directory record and the APK Signing Block before the
+    /// central directory. ZIP64 archives aren't supported
+    pub fn find(apk: &[u8]) -> Result<Self> {
+        if apk.len() < EOCD_MIN_SIZE {
+            return Err(Error::InvalidApk(
+                "file is too short to be a ZIP archive".to_owned(),
+            ));
+        }
+        let max_comment = (apk.len() - EOCD_MIN_SIZE).min(u16::MAX as usize);
+        let eocd_offset = (0..=max_comment)
+            .map(|comment| apk.len() - EOCD_MIN_SIZE - comment)
+            .find(|&offset| {
+                read_u32(apk, offset) == EOCD_SIGNATURE
+                    && read_u16(apk, offset + 20) as usize == apk.len() - offset - EOCD_MIN_SIZE
+            })
+            .ok_or_else(|| Error::InvalidApk("End of Central Directory not found".to_owned()))?;
+        let cd_size = read_u32(apk, eocd_offset + 12) as usize;
+        let cd_offset = read_u32(apk, eocd_offset + 16) as usize;
+        if cd_offset == u32::MAX as usize || cd_offset + cd_size != eocd_offset {
+            return Err(Error::InvalidApk(
+                "unexpected central directory offset, ZIP64 isn't supported".to_owned(),
+            ));
+        }
+        let signing_block = find_signing_block(apk, cd_offset)?;
+        Ok(Self {
+            entries_end: signing_block
+                .as_ref()
+                .map_or(cd_offset, |block| block.start),
+            signing_block,
+            central_directory: cd_offset..eocd_offset,
+            eocd_offset,
+        })
+    }
+
+    /// Names of the entries listed in the central directory
+    pub fn entry_names<'a>(&self, apk: &'a [u8]) -> Vec<&'a str> {
+        let mut names = Vec::new();
+        let mut offset = self.central_directory.start;
+        while offset + 46 <= self.central_directory.end && read_u32(apk, offset) == 0x0201_4b50 {
+            let name_len = read_u16(apk, offset + 28) as usize;
+            let extra_len = read_u16(apk, offset + 30) as usize;
+            let comment_len = read_u16(apk, offset + 32) as usize;
+            if let Some(name) = apk
+                .get(offset + 46..offset + 46 + name_len)
+                .and_then(|name| std::str::from_utf8(name).ok())
+            {
+                names.push(name);
+            }
+            offset += 46 + name_len + extra_len + comment_len;
+        }
+        names
+    }
+
+    /// Digest of the entries, central directory and End of Central Directory as v2 and
+    /// v3 schemes compute it. The central directory offset in the EOCD is replaced with
+    /// the start of the APK Signing Block
+    pub fn content_digest(&self, apk: &[u8], digest: ContentDigest) -> Vec<u8> {
+        match digest {
+            ContentDigest::Sha256 => self.chunked_digest::<Sha256>(apk),
+            ContentDigest::Sha512 => self.chunked_digest::<Sha512>(apk),
+        }
+    }
+
+    fn chunked_digest<D: Digest>(&self, apk: &[u8]) -> Vec<u8> {
+        let mut eocd = apk[self.eocd_offset..].to_vec();
+        eocd[16..20].copy_from_slice(&(self.entries_end as u32).to_le_bytes());
+        let sections = [
+            &apk[..self.entries_end],
+            &apk[self.central_directory.clone()],
+            &eocd[..],
+        ];
+        let chunks = sections
+            .iter()
+            .flat_map(|section| section.chunks(CHUNK_SIZE))
+            .collect::<Vec<_>>();
+        let mut digest = D::new();
+        digest.update([0x5a]);
+        digest.update((chunks.len() as u32).to_le_bytes());
+        for chunk in chunks {
+            let mut chunk_digest = D::new();
+            chunk_digest.update([0xa5]);
+            chunk_digest.update((chunk.len() as u32).to_le_bytes());
+            chunk_digest.update(chunk);
+            digest.update(chunk_digest.finalize());
+        }
+        digest.finalize().to_vec()
+    }
+
+    /// Returns the APK with the signing block replaced by given block
+    pub fn with_signing_block(&self, apk: &[u8], block: &[u8]) -> Vec<u8> {
+        let mut signed = Vec::with_capacity(self.entries_end + block.len() + apk.len());
+        signed.extend_from_slice(&apk[..self.entries_end]);
+        signed.extend_from_slice(block);
+        signed.extend_from_slice(&apk[self.central_directory.clone()]);
+        let eocd_offset = signed.len();
+        signed.extend_from_slice(&apk[self.eocd_offset..]);
+        let cd_offset = (self.entries_end + block.len()) as u32;
+        signed[eocd_offset + 16..eocd_offset + 20].copy_from_slice(&cd_offset.to_le_bytes());
+        signed
+    }
+}
+
+/// Returns the range of the APK Signing Block that ends at the central directory
+fn find_signing_block(apk: &[u8], cd_offset: usize) -> Result<Option<Range<usize>>> {
+    if cd_offset < 32 || &apk[cd_offset - 16..cd_offset] != APK_SIG_BLOCK_MAGIC {
+        return Ok(None);
+    }
+    let size = u64::from_le_bytes(apk[cd_offset - 24..cd_offset - 16].try_into().unwrap());
+    let start = usize::try_from(size)
+        .ok()
+        .filter(|&size| size >= 24)
+        .and_then(|size| cd_offset.checked_sub(size + 8))
+        .ok_or_else(|| Error::InvalidApk("APK Signing Block size is out of range".to_owned()))?;
+    let size_in_header = u64::from_le_bytes(apk[start..start + 8].try_into().unwrap());
+    if size_in_header != size {
+        return Err(Error::InvalidApk(
+            "APK Signing Block sizes in header and footer are different".to_owned(),
+        ));
+    }
+    Ok(Some(start..cd_offset))
+}
+
+/// Returns ID-value pairs of the APK Signing Block
+pub(crate) fn parse_signing_block(block: &[u8]) -> Result<Vec<(u32, &[u8])>> {
+    let mut pairs = &block[8..block.len() - 24];
+    let mut values = Vec::new();
+    while !pairs.is_empty() {
+        let len = pairs
+            .get(..8)
+            .map(|len| u64::from_le_bytes(len.try_into().unwrap()) as usize)
+            .filter(|&len| len >= 4 && len <= pairs.len() - 8)
+            .ok_or_else(|| Error::InvalidApk("malformed APK Signing Block pair".to_owned()))?;
+        let id = read_u32(pairs, 8);
+        values.push((id, &pairs[12..8 + len]));
+        pairs = &pairs[8 + len..];
+    }
+    Ok(values)
+}
+
+/// Encodes the APK Signing Block with given ID-value pairs
+pub(crate) fn encode_signing_block(pairs: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let pairs_len = pairs
+        .iter()
+        .map(|(_, value)| 12 + value.len())
+        .sum::<usize>();
+    // Size excludes the leading size field itself
+    let size = (pairs_len + 8 + 16) as u64;
+    let mut block = Vec::with_capacity(pairs_len + 32);
+    block.extend_from_slice(&size.to_le_bytes());
+    for (id, value) in pairs {
+        block.extend_from_slice(&((value.len() + 4) as u64).to_le_bytes());
+        block.extend_from_slice(&id.to_le_bytes());
+        block.extend_from_slice(value);
+    }
+    block.extend_from_slice(&size.to_le_bytes());
+    block.extend_from_slice(APK_SIG_BLOCK_MAGIC);
+    block
+}
+
+/// Writer of little-endian values and length-prefixed byte sequences used by the
+/// signature blocks
+#[derive(Default)]
+pub(crate) struct BlockWriter(pub Vec<u8>);
+
+impl BlockWriter {
+    pub fn u32(&mut self, value: u32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn prefixed(&mut self, value: &[u8]) -> &mut Self {
+        self.u32(value.len() as u32);
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    /// Writes length-prefixed sequence of length-prefixed items
+    pub fn sequence<T: AsRef<[u8]>>(&mut self, items: &[T]) -> &mut Self {
+        let mut sequence = BlockWriter::default();
+        items.iter().for_each(|item| {
+            sequence.prefixed(item.as_ref());
+        });
+        self.prefixed(&sequence.0)
+    }
+}
+
+/// Reader of values written by [`BlockWriter`]
+#[derive(Clone, Copy)]
+pub(crate) struct BlockReader<'a>(pub &'a [u8]);
+
+impl<'a> BlockReader<'a> {
+    pub fn u32(&mut self) -> Result<u32> {
+        let value = self.take(4)?;
+        Ok(u32::from_le_bytes(value.try_into().unwrap()))
+    }
+
+    pub fn prefixed(&mut self) -> Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    /// Reads length-prefixed sequence of length-prefixed items
+    pub fn sequence(&mut self) -> Result<Vec<&'a [u8]>> {
+        let mut sequence = BlockReader(self.prefixed()?);
+        let mut items = Vec::new();
+        while !sequence.0.is_empty() {
+            items.push(sequence.prefixed()?);
+        }
+        Ok(items)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(Error::InvalidApk("signature block is truncated".to_owned()));
+        }
+        let (value, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(value)
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
@@ -0,0 +1,357 @@
+use super::signing_block::{
+    RSA_PKCS1_V1_5_WITH_SHA256, RSA_PKCS1_V1_5_WITH_SHA512, RSA_PSS_WITH_SHA256,
+    RSA_PSS_WITH_SHA512,
+};
+use crate::error::*;
+use der::{asn1::OctetString, Decode, Encode};
+use p12_keystore::{KeyStore, KeyStoreEntry, PrivateKeyChain};
+use rsa::{
+    pkcs1v15,
+    pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey},
+    signature::{SignatureEncoding, Signer, Verifier},
+    RsaPrivateKey, RsaPublicKey,
+};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::{path::Path, str::FromStr, time::Duration};
+use x509_cert::{
+    builder::{Builder, CertificateBuilder, Profile},
+    name::Name,
+    serial_number::SerialNumber,
+    spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned},
+    time::Validity,
+    Certificate,
+};
+
+/// OID of the proprietary key protection algorithm of JKS keystores
+const JKS_KEY_PROTECTOR_OID: &str = "1.3.6.1.4.1.42.2.17.1.1";
+const JKS_MAGIC: u32 = 0xfeed_feed;
+
+/// RSA private key with its X.509 certificate chain used to sign APKs.
+///
+/// Only RSA keys are supported, the signatures are RSASSA-PKCS1-v1_5 with SHA2-256.
+#[derive(Clone, Debug)]
+pub struct SigningKey {
+    private_key: RsaPrivateKey,
+    certificates: Vec<Vec<u8>>,
+}
+
+impl SigningKey {
+    /// Creates signing key from PKCS#8 DER encoded private key and DER encoded
+    /// certificates. The first certificate must contain the public key of the private key
+    pub fn new(private_key: &[u8], certificates: Vec<Vec<u8>>) -> Result<Self> {
+        let private_key = RsaPrivateKey::from_pkcs8_der(private_key).map_err(|err| {
+            Error::InvalidSigningKey(format!("only RSA keys are supported: {}", err))
+        })?;
+        let certificate = certificates
+            .first()
+            .ok_or_else(|| Error::InvalidSigningKey("certificate is missing".to_owned()))?;
+        if RsaPublicKey::from_public_key_der(&public_key_der(certificate)?).ok()
+            != Some(private_key.to_public_key())
+        {
+            return Err(Error::InvalidSigningKey(
+                "certificate doesn't match the private key".to_owned(),
+            ));
+        }
+        Ok(Self {
+            private_key,
+            certificates,
+        })
+    }
+
+    /// Generates RSA key with self-signed certificate valid for 10000 days. Subject is a
+    /// distinguished name, e.g. `CN=Android Debug,O=Android,C=US`
+    pub fn generate(bits: usize, subject: &str) -> Result<Self> {
+        let invalid = |err: &dyn std::fmt::Display| Error::InvalidSigningKey(err.to_string());
+        let private_key =
+            RsaPrivateKey::new(&mut rand::thread_rng(), bits).map_err(|e| invalid(&e))?;
+        let signer = pkcs1v15::SigningKey::<Sha256>::new(private_key.clone());
+        let public_key = SubjectPublicKeyInfoOwned::from_key(private_key.to_public_key())
+            .map_err(|e| invalid(&e))?;
+        let mut serial = rand::random::<[u8; 8]>();
+        // Serial number must be positive
+        serial[0] &= 0x7f;
+        let builder = CertificateBuilder::new(
+            Profile::Root,
+            SerialNumber::new(&serial).map_err(|e| invalid(&e))?,
+            Validity::from_now(Duration::from_secs(10000 * 24 * 60 * 60))
+                .map_err(|e| invalid(&e))?,
+            Name::from_str(subject).map_err(|e| invalid(&e))?,
+            public_key,
+            &signer,
+        )
+        .map_err(|e| invalid(&e))?;
+        let certificate = builder
+            .build::<pkcs1v15::Signature>()
+            .map_err(|e| invalid(&e))?
+            .to_der()
+            .map_err(|e| invalid(&e))?;
+        Ok(Self {
+            private_key,
+            certificates: vec![certificate],
+        })
+    }
+
+    /// Loads the key from PKCS#12 keystore. Without alias the first key entry is used
+    pub fn from_pkcs12(keystore: &Path, password: &str, alias: Option<&str>) -> Result<Self> {
+        let keystore = KeyStore::from_pkcs12(&std::fs::read(keystore)?, password)
+            .map_err(|err| Error::InvalidSigningKey(err.to_string()))?;
+        let chain = match alias {
+            Some(alias) => match keystore.entry(alias) {
+                Some(KeyStoreEntry::PrivateKeyChain(chain)) => Some(chain),
+                _ => None,
+            },
+            None => keystore.private_key_chain().map(|(_, chain)| chain),
+        }
+        .ok_or_else(|| Error::InvalidSigningKey("private key entry is not found".to_owned()))?;
+        let certificates = chain
+            .chain()
+            .iter()
+            .map(|certificate| certificate.as_der().to_vec())
+            .collect();
+        Self::new(chain.key(), certificates)
+    }
+
+    /// Loads the key from JKS keystore. Without alias the first key entry is used
+    pub fn from_jks(
+        keystore: &Path,
+        storepass: &str,
+        alias: Option<&str>,
+        keypass: &str,
+    ) -> Result<Self> {
+        let (protected_key, certificates) = read_jks(&std::fs::read(keystore)?, storepass, alias)?;
+        Self::new(&unprotect_jks_key(&protected_key, keypass)?, certificates)
+    }
+
+    /// Writes the key to PKCS#12 keystore
+    pub fn to_pkcs12(&self, alias: &str, password: &str) -> Result<Vec<u8>> {
+        let invalid = |err: &dyn std::fmt::Display| Error::InvalidSigningKey(err.to_string());
+        let private_key = self.private_key.to_pkcs8_der().map_err(|e| invalid(&e))?;
+        let certificates = self
+            .certificates
+            .iter()
+            .map(|certificate| p12_keystore::Certificate::from_der(certificate))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| invalid(&e))?;
+        let local_key_id = Sha1::digest(&self.certificates[0]);
+        let mut keystore = KeyStore::new();
+        keystore.add_entry(
+            alias,
+            KeyStoreEntry::PrivateKeyChain(PrivateKeyChain::new(
+                private_key.as_bytes(),
+                local_key_id,
+                certificates,
+            )),
+        );
+        keystore.writer(password).write().map_err(|e| invalid(&e))
+    }
+
+    /// DER encoded signing certificate
+    pub fn certificate(&self) -> &[u8] {
+        &self.certificates[0]
+    }
+
+    /// DER encoded certificate chain starting with the signing certificate
+    pub fn certificates(&self) -> &[Vec<u8>] {
+        &self.certificates
+    }
+
+    /// DER encoded SubjectPublicKeyInfo of the signing certificate
+    pub(crate) fn public_key(&self) -> Result<Vec<u8>> {
+        public_key_der(self.certificate())
+    }
+
+    pub(crate) fn sign(&self, data: &[u8]) -> Vec<u8> {
+        pkcs1v15::SigningKey::<Sha256>::new(self.private_key.clone())
+            .sign(data)
+            .to_vec()
+    }
+}
+
+/// Returns DER encoded SubjectPublicKeyInfo of the certificate
+pub(crate) fn public_key_der(certificate: &[u8]) -> Result<Vec<u8>> {
+    Certificate::from_der(certificate)
+        .and_then(|certificate| certificate.tbs_certificate.subject_public_key_info.to_der())
+        .map_err(|err| Error::InvalidSigningKey(format!("invalid certificate: {}", err)))
+}
+
+/// Whether signatures of the algorithm can be verified. Only RSA signatures are
+/// supported
+pub(crate) fn is_supported_signature(algorithm: u32) -> bool {
+    matches!(
+        algorithm,
+        RSA_PSS_WITH_SHA256
+            | RSA_PSS_WITH_SHA512
+            | RSA_PKCS1_V1_5_WITH_SHA256
+            | RSA_PKCS1_V1_5_WITH_SHA512
+    )
+}
+
+/// Verifies signature of the algorithm made with the key of given SubjectPublicKeyInfo
+pub(crate) fn verify_signature(
+    public_key: &[u8],
+    algorithm: u32,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<()> {
+    let failed = |reason: &str| Error::SignatureVerificationFailed(reason.to_owned());
+    let key = RsaPublicKey::from_public_key_der(public_key)
+        .map_err(|_| failed("only RSA public keys are supported"))?;
+    let verified = match algorithm {
+        RSA_PSS_WITH_SHA256 => verify(rsa::pss::VerifyingKey::<Sha256>::new(key), data, signature),
+        RSA_PSS_WITH_SHA512 => verify(rsa::pss::VerifyingKey::<Sha512>::new(key), data, signature),
+        RSA_PKCS1_V1_5_WITH_SHA256 => {
+            verify(pkcs1v15::VerifyingKey::<Sha256>::new(key), data, signature)
+        }
+        RSA_PKCS1_V1_5_WITH_SHA512 => {
+            verify(pkcs1v15::VerifyingKey::<Sha512>::new(key), data, signature)
+        }
+        _ => return Err(failed("unsupported signature algorithm")),
+    };
+    if !verified {
+        return Err(failed("signature doesn't match the signed data"));
+    }
+    Ok(())
+}
+
+fn verify<S>(key: impl Verifier<S>, data: &[u8], signature: &[u8]) -> bool
+where
+    S: for<'a> TryFrom<&'a [u8]>,
+{
+    S::try_from(signature).is_ok_and(|signature| key.verify(data, &signature).is_ok())
+}
+
+#[derive(der::Sequence)]
+struct EncryptedPrivateKeyInfo {
+    algorithm: AlgorithmIdentifierOwned,
+    encrypted_data: OctetString,
+}
+
+/// Returns the protected key and the certificate chain of the key entry of JKS keystore
+fn read_jks(data: &[u8], storepass: &str, alias: Option<&str>) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+    let invalid =
+        |reason: &str| Error::InvalidSigningKey(format!("invalid JKS keystore: {}", reason));
+    if data.len() < 32 {
+        return Err(invalid("file is too short"));
+    }
+    let (content, digest) = data.split_at(data.len() - 20);
+    let mut expected = Sha1::new();
+    expected.update(utf16_be(storepass));
+    expected.update(b"Mighty Aphrodite");
+    expected.update(content);
+    if expected.finalize().as_slice() != digest {
+        return Err(invalid(
+            "keystore was tampered with, or password was incorrect",
+        ));
+    }
+    let mut reader = JksReader(content);
+    if reader.u32()? != JKS_MAGIC {
+        return Err(invalid("not a JKS keystore"));
+    }
+    let version = reader.u32()?;
+    for _ in 0..reader.u32()? {
+        let tag = reader.u32()?;
+        let entry_alias = reader.utf()?;
+        let _timestamp = reader.take(8)?;
+        match tag {
+            1 => {
+                let protected_key = reader.bytes()?.to_vec();
+                let mut certificates = Vec::new();
+                for _ in 0..reader.u32()? {
+                    if version == 2 {
+                        let _certificate_type = reader.utf()?;
+                    }
+                    certificates.push(reader.bytes()?.to_vec());
+                }
+                if alias.is_none_or(|alias| alias.eq_ignore_ascii_case(&entry_alias)) {
+                    return Ok((protected_key, certificates));
+                }
+            }
+            2 => {
+                if version == 2 {
+                    let _certificate_type = reader.utf()?;
+                }
+                reader.bytes()?;
+            }
+            _ => return Err(invalid("unknown entry type")),
+        }
+    }
+    Err(Error::InvalidSigningKey(
+        "private key entry is not found".to_owned(),
+    ))
+}
+
+/// Decrypts PKCS#8 private key protected with the JKS key protector
+fn unprotect_jks_key(protected_key: &[u8], keypass: &str) -> Result<Vec<u8>> {
+    let invalid = |reason: &str| Error::InvalidSigningKey(format!("invalid JKS key: {}", reason));
+    let info =
+        EncryptedPrivateKeyInfo::from_der(protected_key).map_err(|_| invalid("malformed key"))?;
+    if info.algorithm.oid.to_string() != JKS_KEY_PROTECTOR_OID {
+        return Err(invalid("unsupported key protection algorithm"));
+    }
+    let encrypted = info.encrypted_data.as_bytes();
+    if encrypted.len() < 40 {
+        return Err(invalid("malformed key"));
+    }
+    let (salt, rest) = encrypted.split_at(20);
+    let (encrypted, check) = rest.split_at(rest.len() - 20);
+    let password = utf16_be(keypass);
+    let mut keystream = Vec::with_capacity(encrypted.len() + 20);
+    let mut digest = salt.to_vec();
+    while keystream.len() < encrypted.len() {
+        digest = Sha1::new()
+            .chain_update(&password)
+            .chain_update(&digest)
+            .finalize()
+            .to_vec();
+        keystream.extend_from_slice(&digest);
+    }
+    let key = encrypted
+        .iter()
+        .zip(keystream)
+        .map(|(byte, key)| byte ^ key)
+        .collect::<Vec<_>>();
+    let expected = Sha1::new()
+        .chain_update(&password)
+        .chain_update(&key)
+        .finalize();
+    if expected.as_slice() != check {
+        return Err(invalid("key password is incorrect"));
+    }
+    Ok(key)
+}
+
+fn utf16_be(password: &str) -> Vec<u8> {
+    password.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
+
+/// Reader of big-endian values written by Java `DataOutputStream`
+struct JksReader<'a>(&'a [u8]);
+
+impl<'a> JksReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(Error::InvalidSigningKey(
+                "invalid JKS keystore: file is truncated".to_owned(),
+            ));
+        }
+        let (value, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(value)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    /// Reads modified UTF-8 string. Aliases and certificate types are ASCII in practice
+    fn utf(&mut self) -> Result<String> {
+        let len = u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as usize;
+        Ok(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+}
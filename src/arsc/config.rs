@@ -0,0 +1,463 @@
+use crate::{axml::chunk::*, error::*};
+
+pub const DENSITY_DEFAULT: u16 = 0;
+pub const DENSITY_LDPI: u16 = 120;
+pub const DENSITY_MDPI: u16 = 160;
+pub const DENSITY_TVDPI: u16 = 213;
+pub const DENSITY_HDPI: u16 = 240;
+pub const DENSITY_XHDPI: u16 = 320;
+pub const DENSITY_XXHDPI: u16 = 480;
+pub const DENSITY_XXXHDPI: u16 = 640;
+pub const DENSITY_ANY: u16 = 0xfffe;
+pub const DENSITY_NONE: u16 = 0xffff;
+
+const DENSITIES: [(&str, u16); 9] = [
+    ("ldpi", DENSITY_LDPI),
+    ("mdpi", DENSITY_MDPI),
+    ("tvdpi", DENSITY_TVDPI),
+    ("hdpi", DENSITY_HDPI),
+    ("xhdpi", DENSITY_XHDPI),
+    ("xxhdpi", DENSITY_XXHDPI),
+    ("xxxhdpi", DENSITY_XXXHDPI),
+    ("anydpi", DENSITY_ANY),
+    ("nodpi", DENSITY_NONE),
+];
+const ORIENTATIONS: [&str; 3] = ["port", "land", "square"];
+const UI_MODE_TYPES: [&str; 7] = [
+    "",
+    "desk",
+    "car",
+    "television",
+    "appliance",
+    "watch",
+    "vrheadset",
+];
+const TOUCHSCREENS: [&str; 3] = ["notouch", "stylus", "finger"];
+const KEYBOARDS: [&str; 3] = ["nokeys", "qwerty", "12key"];
+const NAVIGATIONS: [&str; 4] = ["nonav", "dpad", "trackball", "wheel"];
+const SCREEN_SIZES: [&str; 4] = ["small", "normal", "large", "xlarge"];
+
+const MASK_LAYOUT_DIR: u8 = 0xc0;
+const MASK_SCREEN_SIZE: u8 = 0x0f;
+const MASK_SCREEN_LONG: u8 = 0x30;
+const MASK_UI_MODE_TYPE: u8 = 0x0f;
+const MASK_UI_MODE_NIGHT: u8 = 0x30;
+
+/// Configuration the resource value is defined for, `ResTable_config`. Zero and empty
+/// fields are not specified.
+///
+/// Also describes the device when resolving resources, e.g. `"fr-xhdpi".parse()`.
+/// Fields of the device configuration that aren't specified don't exclude values,
+/// except the locale: values for other locales are excluded then.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ResTableConfig {
+    pub mcc: u16,
+    pub mnc: u16,
+    /// Language code, e.g. `fr`
+    pub language: String,
+    /// Region code, e.g. `FR`
+    pub region: String,
+    pub orientation: u8,
+    pub touchscreen: u8,
+    /// Density in dpi, e.g. [`DENSITY_XHDPI`]
+    pub density: u16,
+    pub keyboard: u8,
+    pub navigation: u8,
+    pub input_flags: u8,
+    pub screen_width: u16,
+    pub screen_height: u16,
+    pub sdk_version: u16,
+    pub minor_version: u16,
+    /// Layout direction, screen size and long screen flags
+    pub screen_layout: u8,
+    /// UI mode type and night mode flags
+    pub ui_mode: u8,
+    pub smallest_screen_width_dp: u16,
+    pub screen_width_dp: u16,
+    pub screen_height_dp: u16,
+    /// Script code, e.g. `Latn`
+    pub script: String,
+    pub variant: String,
+    pub screen_layout2: u8,
+    pub color_mode: u8,
+}
+
+impl ResTableConfig {
+    /// Parses `ResTable_config` prefixed with its size. Fields beyond the size are left
+    /// unspecified
+    pub(crate) fn read(data: &[u8]) -> Result<Self> {
+        let size = (read_u32(data, 0)? as usize).min(data.len());
+        let data = &data[..size];
+        let u8_at = |offset: usize| data.get(offset).copied().unwrap_or(0);
+        let u16_at = |offset: usize| read_u16(data, offset).unwrap_or(0);
+        let text_at = |offset: usize, len: usize| {
+            data.get(offset..offset + len)
+                .map(|bytes| {
+                    let end = bytes.iter().position(|byte| *byte == 0).unwrap_or(len);
+                    String::from_utf8_lossy(&bytes[..end]).into_owned()
+                })
+                .unwrap_or_default()
+        };
+        Ok(Self {
+            mcc: u16_at(4),
+            mnc: u16_at(6),
+            language: unpack_locale_code([u8_at(8), u8_at(9)], b'a'),
+            region: unpack_locale_code([u8_at(10), u8_at(11)], b'0'),
+            orientation: u8_at(12),
+            touchscreen: u8_at(13),
+            density: u16_at(14),
+            keyboard: u8_at(16),
+            navigation: u8_at(17),
+            input_flags: u8_at(18),
+            screen_width: u16_at(20),
+            screen_height: u16_at(22),
+            sdk_version: u16_at(24),
+            minor_version: u16_at(26),
+            screen_layout: u8_at(28),
+            ui_mode: u8_at(29),
+            smallest_screen_width_dp: u16_at(30),
+            screen_width_dp: u16_at(32),
+            screen_height_dp: u16_at(34),
+            script: text_at(36, 4),
+            variant: text_at(40, 8),
+            screen_layout2: u8_at(48),
+            color_mode: u8_at(49),
+        })
+    }
+
+    /// Returns whether the value for this configuration can be used on the device
+    pub fn matches(&self, device: &ResTableConfig) -> bool {
+        let differs = |value: u8, device: u8| value != 0 && device != 0 && value != device;
+        let exceeds = |value: u16, device: u16| value != 0 && device != 0 && value > device;
+        if (self.mcc != 0 && device.mcc != 0 && self.mcc != device.mcc)
+            || (self.mnc != 0 && device.mnc != 0 && self.mnc != device.mnc)
+        {
+            return false;
+        }
+        if (!self.language.is_empty() && self.language != device.language)
+            || (!self.region.is_empty() && self.region != device.region)
+            || (!self.script.is_empty() && self.script != device.script)
+            || (!self.variant.is_empty() && self.variant != device.variant)
+        {
+            return false;
+        }
+        let screen_size = |config: &Self| config.screen_layout & MASK_SCREEN_SIZE;
+        !(differs(
+            self.screen_layout & MASK_LAYOUT_DIR,
+            device.screen_layout & MASK_LAYOUT_DIR,
+        ) || exceeds(
+            self.smallest_screen_width_dp,
+            device.smallest_screen_width_dp,
+        ) || exceeds(self.screen_width_dp, device.screen_width_dp)
+            || exceeds(self.screen_height_dp, device.screen_height_dp)
+            || exceeds(screen_size(self) as u16, screen_size(device) as u16)
+            || differs(
+                self.screen_layout & MASK_SCREEN_LONG,
+                device.screen_layout & MASK_SCREEN_LONG,
+            )
+            || differs(self.orientation, device.orientation)
+            || differs(
+                self.ui_mode & MASK_UI_MODE_TYPE,
+                device.ui_mode & MASK_UI_MODE_TYPE,
+            )
+            || differs(
+                self.ui_mode & MASK_UI_MODE_NIGHT,
+                device.ui_mode & MASK_UI_MODE_NIGHT,
+            )
+            || differs(self.touchscreen, device.touchscreen)
+            || differs(self.keyboard, device.keyboard)
+            || differs(self.navigation, device.navigation)
+            || exceeds(self.sdk_version, device.sdk_version))
+    }
+
+    /// Returns whether this configuration matches the device better than other one.
+    /// Both configurations must match the device. Follows the qualifier precedence of
+    /// Android
+    pub fn is_better_than(&self, other: &ResTableConfig, device: &ResTableConfig) -> bool {
+        // Qualifiers in the order of precedence, the more specific value wins
+        let specific = [
+            (self.mcc != 0, other.mcc != 0),
+            (self.mnc != 0, other.mnc != 0),
+            (!self.language.is_empty(), !other.language.is_empty()),
+            (!self.script.is_empty(), !other.script.is_empty()),
+            (!self.region.is_empty(), !other.region.is_empty()),
+            (!self.variant.is_empty(), !other.variant.is_empty()),
+            (
+                self.screen_layout & MASK_LAYOUT_DIR != 0,
+                other.screen_layout & MASK_LAYOUT_DIR != 0,
+            ),
+        ];
+        if let Some((this, _)) = specific.iter().find(|(this, other)| this != other) {
+            return *this;
+        }
+        // Larger screen sizes fitting the device win
+        let larger = [
+            (
+                self.smallest_screen_width_dp,
+                other.smallest_screen_width_dp,
+            ),
+            (self.screen_width_dp, other.screen_width_dp),
+            (self.screen_height_dp, other.screen_height_dp),
+            (
+                (self.screen_layout & MASK_SCREEN_SIZE) as u16,
+                (other.screen_layout & MASK_SCREEN_SIZE) as u16,
+            ),
+        ];
+        if let Some((this, other)) = larger.iter().find(|(this, other)| this != other) {
+            return this > other;
+        }
+        let specific = [
+            (
+                self.screen_layout & MASK_SCREEN_LONG != 0,
+                other.screen_layout & MASK_SCREEN_LONG != 0,
+            ),
+            (self.orientation != 0, other.orientation != 0),
+            (
+                self.ui_mode & MASK_UI_MODE_TYPE != 0,
+                other.ui_mode & MASK_UI_MODE_TYPE != 0,
+            ),
+            (
+                self.ui_mode & MASK_UI_MODE_NIGHT != 0,
+                other.ui_mode & MASK_UI_MODE_NIGHT != 0,
+            ),
+        ];
+        if let Some((this, _)) = specific.iter().find(|(this, other)| this != other) {
+            return *this;
+        }
+        if self.density != other.density {
+            return is_better_density(self.density, other.density, device.density);
+        }
+        let specific = [
+            (self.touchscreen != 0, other.touchscreen != 0),
+            (self.keyboard != 0, other.keyboard != 0),
+            (self.navigation != 0, other.navigation != 0),
+        ];
+        if let Some((this, _)) = specific.iter().find(|(this, other)| this != other) {
+            return *this;
+        }
+        self.sdk_version > other.sdk_version
+    }
+}
+
+/// Picks density closer to the requested one, preferring to scale down rather than up
+fn is_better_density(density: u16, other: u16, requested: u16) -> bool {
+    if density == DENSITY_ANY || other == DENSITY_ANY {
+        return density == DENSITY_ANY;
+    }
+    let normalize = |density: u16| match density {
+        DENSITY_DEFAULT => DENSITY_MDPI as i64,
+        density => density as i64,
+    };
+    let (density, other, requested) = (normalize(density), normalize(other), normalize(requested));
+    let (high, low) = (density.max(other), density.min(other));
+    let bigger = density > other;
+    if requested >= high {
+        return bigger;
+    }
+    // Between the densities, the lower one wins unless scaling it up is too large
+    let lower_wins = low >= requested || (2 * low - requested) * high > requested * requested;
+    bigger != lower_wins
+}
+
+/// Returns qualifier of the 1-based enum value
+fn name(names: &[&str], value: u8) -> Option<String> {
+    let index = (value as usize).checked_sub(1)?;
+    names
+        .get(index)
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_string())
+}
+
+/// Language and region codes are two ASCII letters, or three letters packed in two bytes
+fn unpack_locale_code(code: [u8; 2], base: u8) -> String {
+    if code[0] & 0x80 != 0 {
+        let first = code[1] & 0x1f;
+        let second = ((code[1] & 0xe0) >> 5) | ((code[0] & 0x03) << 3);
+        let third = (code[0] & 0x7c) >> 2;
+        return [first, second, third]
+            .iter()
+            .map(|letter| (base + letter) as char)
+            .collect();
+    }
+    code.iter()
+        .take_while(|byte| **byte != 0)
+        .map(|byte| *byte as char)
+        .collect()
+}
+
+/// Prints the qualifiers of configuration, e.g. `fr-rFR-xhdpi-v21`. The default
+/// configuration is printed as empty string
+impl std::fmt::Display for ResTableConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut qualifiers = Vec::new();
+        if self.mcc != 0 {
+            qualifiers.push(format!("mcc{}", self.mcc));
+        }
+        if self.mnc != 0 {
+            qualifiers.push(format!("mnc{}", self.mnc));
+        }
+        if !self.script.is_empty() || !self.variant.is_empty() {
+            let subtags = [&self.language, &self.script, &self.region, &self.variant];
+            let subtags: Vec<_> = subtags.iter().filter(|tag| !tag.is_empty()).collect();
+            qualifiers.push(format!(
+                "b+{}",
+                subtags
+                    .iter()
+                    .map(|tag| tag.as_str())
+                    .collect::<Vec<_>>()
+                    .join("+")
+            ));
+        } else if !self.language.is_empty() {
+            qualifiers.push(self.language.clone());
+            if !self.region.is_empty() {
+                qualifiers.push(format!("r{}", self.region));
+            }
+        }
+        match self.screen_layout & MASK_LAYOUT_DIR {
+            0x40 => qualifiers.push("ldltr".to_owned()),
+            0x80 => qualifiers.push("ldrtl".to_owned()),
+            _ => {}
+        }
+        if self.smallest_screen_width_dp != 0 {
+            qualifiers.push(format!("sw{}dp", self.smallest_screen_width_dp));
+        }
+        if self.screen_width_dp != 0 {
+            qualifiers.push(format!("w{}dp", self.screen_width_dp));
+        }
+        if self.screen_height_dp != 0 {
+            qualifiers.push(format!("h{}dp", self.screen_height_dp));
+        }
+        let named = [
+            (&SCREEN_SIZES[..], self.screen_layout & MASK_SCREEN_SIZE),
+            (
+                &["notlong", "long"],
+                (self.screen_layout & MASK_SCREEN_LONG) >> 4,
+            ),
+            (&ORIENTATIONS, self.orientation),
+            (&UI_MODE_TYPES, self.ui_mode & MASK_UI_MODE_TYPE),
+            (
+                &["notnight", "night"],
+                (self.ui_mode & MASK_UI_MODE_NIGHT) >> 4,
+            ),
+        ];
+        qualifiers.extend(
+            named
+                .iter()
+                .filter_map(|(names, value)| name(names, *value)),
+        );
+        match DENSITIES
+            .iter()
+            .find(|(_, density)| *density == self.density)
+        {
+            Some((name, _)) => qualifiers.push(name.to_string()),
+            None if self.density != 0 => qualifiers.push(format!("{}dpi", self.density)),
+            None => {}
+        }
+        let named = [
+            (&TOUCHSCREENS[..], self.touchscreen),
+            (&KEYBOARDS, self.keyboard),
+            (&NAVIGATIONS, self.navigation),
+        ];
+        qualifiers.extend(
+            named
+                .iter()
+                .filter_map(|(names, value)| name(names, *value)),
+        );
+        if self.sdk_version != 0 {
+            qualifiers.push(format!("v{}", self.sdk_version));
+        }
+        write!(f, "{}", qualifiers.join("-"))
+    }
+}
+
+/// Parses qualifiers of resource directory, e.g. `fr-rFR-xhdpi-v21` or `b+sr+Latn`
+impl std::str::FromStr for ResTableConfig {
+    type Err = Error;
+
+    fn from_str(qualifiers: &str) -> Result<Self> {
+        let mut config = Self::default();
+        let named = |names: &[&str], qualifier: &str| {
+            names
+                .iter()
+                .position(|name| !name.is_empty() && *name == qualifier)
+                .map(|index| index as u8 + 1)
+        };
+        let number = |qualifier: &str, prefix: &str, suffix: &str| {
+            qualifier
+                .strip_prefix(prefix)?
+                .strip_suffix(suffix)?
+                .parse::<u16>()
+                .ok()
+        };
+        for qualifier in qualifiers
+            .split('-')
+            .filter(|qualifier| !qualifier.is_empty())
+        {
+            if let Some(mcc) = number(qualifier, "mcc", "") {
+                config.mcc = mcc;
+            } else if let Some(mnc) = number(qualifier, "mnc", "") {
+                config.mnc = mnc;
+            } else if let Some(tags) = qualifier.strip_prefix("b+") {
+                let mut tags = tags.split('+');
+                config.language = tags.next().unwrap_or_default().to_lowercase();
+                for tag in tags {
+                    match tag.len() {
+                        4 if tag.chars().all(|c| c.is_ascii_alphabetic()) => {
+                            config.script = tag.to_owned()
+                        }
+                        2 | 3 => config.region = tag.to_uppercase(),
+                        _ => config.variant = tag.to_owned(),
+                    }
+                }
+            } else if qualifier == "ldltr" {
+                config.screen_layout |= 0x40;
+            } else if qualifier == "ldrtl" {
+                config.screen_layout |= 0x80;
+            } else if let Some(width) = number(qualifier, "sw", "dp") {
+                config.smallest_screen_width_dp = width;
+            } else if let Some(width) = number(qualifier, "w", "dp") {
+                config.screen_width_dp = width;
+            } else if let Some(height) = number(qualifier, "h", "dp") {
+                config.screen_height_dp = height;
+            } else if let Some(size) = named(&SCREEN_SIZES, qualifier) {
+                config.screen_layout |= size;
+            } else if let Some(long) = named(&["notlong", "long"], qualifier) {
+                config.screen_layout |= long << 4;
+            } else if let Some(orientation) = named(&ORIENTATIONS, qualifier) {
+                config.orientation = orientation;
+            } else if let Some(ui_mode_type) = named(&UI_MODE_TYPES, qualifier) {
+                config.ui_mode |= ui_mode_type;
+            } else if let Some(night) = named(&["notnight", "night"], qualifier) {
+                config.ui_mode |= night << 4;
+            } else if let Some((_, density)) = DENSITIES.iter().find(|(name, _)| *name == qualifier)
+            {
+                config.density = *density;
+            } else if let Some(density) = number(qualifier, "", "dpi") {
+                config.density = density;
+            } else if let Some(touchscreen) = named(&TOUCHSCREENS, qualifier) {
+                config.touchscreen = touchscreen;
+            } else if let Some(keyboard) = named(&KEYBOARDS, qualifier) {
+                config.keyboard = keyboard;
+            } else if let Some(navigation) = named(&NAVIGATIONS, qualifier) {
+                config.navigation = navigation;
+            } else if let Some(sdk_version) = number(qualifier, "v", "") {
+                config.sdk_version = sdk_version;
+            } else if let Some(region) = qualifier
+                .strip_prefix('r')
+                .filter(|region| region.len() == 2 && !config.language.is_empty())
+            {
+                config.region = region.to_uppercase();
+            } else if (2..=3).contains(&qualifier.len())
+                && qualifier.chars().all(|c| c.is_ascii_lowercase())
+            {
+                config.language = qualifier.to_owned();
+            } else {
+                return Err(Error::InvalidArguments(format!(
+                    "unknown configuration qualifier {}",
+                    qualifier
+                )));
+            }
+        }
+        Ok(config)
+    }
+}
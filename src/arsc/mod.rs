@@ -0,0 +1,20 @@
+//! Native parser of Android binary resource table, `resources.arsc`.
+//!
+//! Reads packages, types, configurations and entries of the table, and resolves
+//! resources by ID or by name for the device configuration without aapt2.
+//!
+//! ```no_run
+//! use android_tools::arsc::ResTable;
+//! use std::path::Path;
+//!
+//! let table = ResTable::from_file(Path::new("resources.arsc")).unwrap();
+//! let device = "fr-xhdpi".parse().unwrap();
+//! let app_name = table.resolve_name("@string/app_name", &device).unwrap();
+//! println!("{:?}", app_name.value);
+//! ```
+
+mod config;
+mod res_table;
+
+pub use config::*;
+pub use res_table::*;
@@ -0,0 +1,353 @@
+use super::ResTableConfig;
+use crate::{
+    axml::{chunk::*, ResValue, StringPool},
+    error::*,
+};
+use std::collections::BTreeMap;
+
+const TYPE_FLAG_SPARSE: u8 = 0x01;
+const TYPE_FLAG_OFFSET16: u8 = 0x02;
+const ENTRY_FLAG_COMPLEX: u16 = 0x0001;
+const ENTRY_FLAG_COMPACT: u16 = 0x0008;
+const NO_ENTRY16: u16 = 0xffff;
+/// Maximum depth of references followed by [`ResTable::resolve_value`]
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// Binary resource table, `resources.arsc` of the APK
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResTable {
+    /// Global pool with string values of all packages
+    pub strings: StringPool,
+    pub packages: Vec<ResTablePackage>,
+}
+
+/// Package of the table, `ResTable_package`, e.g. `com.example` with id `0x7f`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResTablePackage {
+    pub id: u8,
+    pub name: String,
+    pub types: Vec<ResTableType>,
+}
+
+/// Resource type with its values in every configuration, e.g. `string`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResTableType {
+    pub id: u8,
+    pub name: String,
+    /// Configuration change flags of every entry from `ResTable_typeSpec`
+    pub spec_flags: Vec<u32>,
+    pub configs: Vec<ResTableTypeConfig>,
+}
+
+/// Entries of the type defined for the configuration, `ResTable_type`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResTableTypeConfig {
+    pub config: ResTableConfig,
+    /// Entries by their index
+    pub entries: BTreeMap<u16, ResTableEntry>,
+}
+
+/// Value of resource in a configuration, `ResTable_entry`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResTableEntry {
+    /// Name of the resource without type, e.g. `app_name`
+    pub key: String,
+    pub flags: u16,
+    pub value: ResTableEntryValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResTableEntryValue {
+    Simple(ResValue),
+    /// Bag of values, like style, array or plurals, `ResTable_map_entry`
+    Complex {
+        /// ID of the parent style or `0`
+        parent: u32,
+        /// Values by attribute or key ID
+        values: Vec<(u32, ResValue)>,
+    },
+}
+
+impl ResTable {
+    /// Parses `resources.arsc`
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let table = Chunk::read(data, 0)?.expect(RES_TABLE_TYPE)?;
+        let mut strings = StringPool::default();
+        let mut packages = Vec::new();
+        for chunk in Chunk::read_all(table.body)? {
+            match chunk.chunk_type {
+                RES_STRING_POOL_TYPE => strings = StringPool::from_bytes(chunk.data)?,
+                RES_TABLE_PACKAGE_TYPE => packages.push(read_package(chunk, &strings)?),
+                _ => {}
+            }
+        }
+        Ok(Self { strings, packages })
+    }
+
+    /// Reads `resources.arsc` from the file
+    pub fn from_file(path: &std::path::Path) -> Result<Self> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Returns package by ID, e.g. `0x7f` for the app package
+    pub fn package(&self, id: u8) -> Option<&ResTablePackage> {
+        self.packages.iter().find(|package| package.id == id)
+    }
+
+    /// Returns values of the resource in every configuration it's defined for
+    pub fn entries(&self, id: u32) -> Vec<(&ResTableConfig, &ResTableEntry)> {
+        let Some(resource_type) = self.resource_type(id) else {
+            return Vec::new();
+        };
+        let index = id as u16;
+        resource_type
+            .configs
+            .iter()
+            .filter_map(|config| Some((&config.config, config.entries.get(&index)?)))
+            .collect()
+    }
+
+    /// Returns the value of the resource best matching the device configuration
+    pub fn resolve(&self, id: u32, device: &ResTableConfig) -> Option<&ResTableEntry> {
+        self.entries(id)
+            .into_iter()
+            .filter(|(config, _)| config.matches(device))
+            .reduce(
+                |best, candidate| match candidate.0.is_better_than(best.0, device) {
+                    true => candidate,
+                    false => best,
+                },
+            )
+            .map(|(_, entry)| entry)
+    }
+
+    /// Resolves the resource like [`ResTable::resolve`] and follows references to other
+    /// resources, e.g. from `@string/app_name` to `@string/brand`. References to packages
+    /// missing in the table, like `@android:color/black`, are returned unresolved
+    pub fn resolve_value(&self, id: u32, device: &ResTableConfig) -> Option<&ResTableEntryValue> {
+        let mut value = &self.resolve(id, device)?.value;
+        for _ in 0..MAX_REFERENCE_DEPTH {
+            match value {
+                ResTableEntryValue::Simple(ResValue::Reference(id))
+                    if *id != 0 && self.package((id >> 24) as u8).is_some() =>
+                {
+                    value = &self.resolve(*id, device)?.value
+                }
+                value => return Some(value),
+            }
+        }
+        None
+    }
+
+    /// Returns ID of the resource by name like `string/app_name`, `@string/app_name` or
+    /// `com.example:string/app_name`. The first package is searched if the package isn't
+    /// specified
+    pub fn id(&self, name: &str) -> Option<u32> {
+        let name = name.trim_start_matches('@');
+        let (package, name) = match name.split_once(':') {
+            Some((package, name)) => (self.packages.iter().find(|p| p.name == package)?, name),
+            None => (self.packages.first()?, name),
+        };
+        let (type_name, key) = name.split_once('/')?;
+        let resource_type = package.types.iter().find(|t| t.name == type_name)?;
+        let index = resource_type.configs.iter().find_map(|config| {
+            config
+                .entries
+                .iter()
+                .find(|(_, entry)| entry.key == key)
+                .map(|(index, _)| *index)
+        })?;
+        Some(u32::from_be_bytes([package.id, resource_type.id, 0, 0]) | index as u32)
+    }
+
+    /// Returns name of the resource like `com.example:string/app_name`
+    pub fn name(&self, id: u32) -> Option<String> {
+        let package = self.package((id >> 24) as u8)?;
+        let resource_type = self.resource_type(id)?;
+        let (_, entry) = self.entries(id).into_iter().next()?;
+        Some(format!(
+            "{}:{}/{}",
+            package.name, resource_type.name, entry.key
+        ))
+    }
+
+    /// Resolves the resource by name like [`ResTable::resolve`], e.g. `@string/app_name`
+    pub fn resolve_name(&self, name: &str, device: &ResTableConfig) -> Option<&ResTableEntry> {
+        self.resolve(self.id(name)?, device)
+    }
+
+    fn resource_type(&self, id: u32) -> Option<&ResTableType> {
+        let type_id = (id >> 16) as u8;
+        self.package((id >> 24) as u8)?
+            .types
+            .iter()
+            .find(|resource_type| resource_type.id == type_id)
+    }
+}
+
+fn read_package(chunk: Chunk, strings: &StringPool) -> Result<ResTablePackage> {
+    let header = chunk.header;
+    let id = read_u32(header, 8)? as u8;
+    let name: Vec<u16> = (0..128)
+        .map(|index| read_u16(header, 12 + index * 2))
+        .take_while(|unit| !matches!(unit, Ok(0)))
+        .collect::<Result<_>>()?;
+    let type_strings_offset = read_u32(header, 268)? as usize;
+    let key_strings_offset = read_u32(header, 276)? as usize;
+    let type_id_offset = match header.len() >= 288 {
+        true => read_u32(header, 284)? as usize,
+        false => 0,
+    };
+    let mut type_strings = StringPool::default();
+    let mut key_strings = StringPool::default();
+    let mut types: Vec<ResTableType> = Vec::new();
+    let mut offset = header.len();
+    for child in Chunk::read_all(chunk.body)? {
+        match child.chunk_type {
+            RES_STRING_POOL_TYPE if offset == type_strings_offset => {
+                type_strings = StringPool::from_bytes(child.data)?
+            }
+            RES_STRING_POOL_TYPE if offset == key_strings_offset => {
+                key_strings = StringPool::from_bytes(child.data)?
+            }
+            RES_TABLE_TYPE_SPEC_TYPE => {
+                let type_id = read_u8(child.header, 8)?;
+                let entry_count = read_u32(child.header, 12)? as usize;
+                let spec_flags = (0..entry_count)
+                    .map(|index| read_u32(child.body, index * 4))
+                    .collect::<Result<_>>()?;
+                type_entry(&mut types, type_id, &type_strings, type_id_offset)?.spec_flags =
+                    spec_flags;
+            }
+            RES_TABLE_TYPE_TYPE => {
+                let type_id = read_u8(child.header, 8)?;
+                let config = read_type(child, strings, &key_strings)?;
+                type_entry(&mut types, type_id, &type_strings, type_id_offset)?
+                    .configs
+                    .push(config);
+            }
+            _ => {}
+        }
+        offset += child.data.len();
+    }
+    Ok(ResTablePackage {
+        id,
+        name: String::from_utf16_lossy(&name),
+        types,
+    })
+}
+
+/// Returns the type with given ID, adding it if it's not found yet
+fn type_entry<'a>(
+    types: &'a mut Vec<ResTableType>,
+    id: u8,
+    type_strings: &StringPool,
+    type_id_offset: usize,
+) -> Result<&'a mut ResTableType> {
+    if let Some(index) = types
+        .iter()
+        .position(|resource_type| resource_type.id == id)
+    {
+        return Ok(&mut types[index]);
+    }
+    let name = (id as usize)
+        .checked_sub(1 + type_id_offset)
+        .and_then(|index| type_strings.get(index as u32))
+        .ok_or_else(|| invalid(format!("name of type {} not found", id)))?;
+    types.push(ResTableType {
+        id,
+        name: name.to_owned(),
+        ..Default::default()
+    });
+    Ok(types.last_mut().unwrap())
+}
+
+/// Parses `ResTable_type` with entries for single configuration
+fn read_type(
+    chunk: Chunk,
+    strings: &StringPool,
+    key_strings: &StringPool,
+) -> Result<ResTableTypeConfig> {
+    let header = chunk.header;
+    let flags = read_u8(header, 9)?;
+    let entry_count = read_u32(header, 12)? as usize;
+    let entries_start = read_u32(header, 16)? as usize;
+    let config = ResTableConfig::read(&header[20.min(header.len())..])?;
+    let body = chunk.body;
+    let offsets: Vec<(u16, usize)> = if flags & TYPE_FLAG_SPARSE != 0 {
+        (0..entry_count)
+            .map(|index| {
+                Ok((
+                    read_u16(body, index * 4)?,
+                    read_u16(body, index * 4 + 2)? as usize * 4,
+                ))
+            })
+            .collect::<Result<_>>()?
+    } else if flags & TYPE_FLAG_OFFSET16 != 0 {
+        (0..entry_count)
+            .map(|index| Ok((index as u16, read_u16(body, index * 2)?)))
+            .filter(|offset| !matches!(offset, Ok((_, NO_ENTRY16))))
+            .map(|offset| offset.map(|(index, offset)| (index, offset as usize * 4)))
+            .collect::<Result<_>>()?
+    } else {
+        (0..entry_count)
+            .map(|index| Ok((index as u16, read_u32(body, index * 4)?)))
+            .filter(|offset| !matches!(offset, Ok((_, NO_INDEX))))
+            .map(|offset| offset.map(|(index, offset)| (index, offset as usize)))
+            .collect::<Result<_>>()?
+    };
+    let entries = offsets
+        .into_iter()
+        .map(|(index, offset)| {
+            let entry = read_entry(chunk.data, entries_start + offset, strings, key_strings)?;
+            Ok((index, entry))
+        })
+        .collect::<Result<_>>()?;
+    Ok(ResTableTypeConfig { config, entries })
+}
+
+fn read_entry(
+    data: &[u8],
+    offset: usize,
+    strings: &StringPool,
+    key_strings: &StringPool,
+) -> Result<ResTableEntry> {
+    let size = read_u16(data, offset)?;
+    let flags = read_u16(data, offset + 2)?;
+    let key = |index: u32| {
+        key_strings
+            .get(index)
+            .map(str::to_owned)
+            .ok_or_else(|| invalid(format!("key {} not found", index)))
+    };
+    if flags & ENTRY_FLAG_COMPACT != 0 {
+        // Compact entry keeps the key in the size field and the value type in the flags
+        let value = ResValue::from_raw((flags >> 8) as u8, read_u32(data, offset + 4)?, strings)?;
+        return Ok(ResTableEntry {
+            key: key(size as u32)?,
+            flags,
+            value: ResTableEntryValue::Simple(value),
+        });
+    }
+    let key = key(read_u32(data, offset + 4)?)?;
+    let value_offset = offset + size as usize;
+    let value = if flags & ENTRY_FLAG_COMPLEX != 0 {
+        let count = read_u32(data, offset + 12)? as usize;
+        let values = (0..count)
+            .map(|index| {
+                let offset = value_offset + index * 12;
+                Ok((
+                    read_u32(data, offset)?,
+                    ResValue::read(data, offset + 4, strings)?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        ResTableEntryValue::Complex {
+            parent: read_u32(data, offset + 8)?,
+            values,
+        }
+    } else {
+        ResTableEntryValue::Simple(ResValue::read(data, value_offset, strings)?)
+    };
+    Ok(ResTableEntry { key, flags, value })
+}
@@ -0,0 +1,135 @@
+//! Contains `output_err_async` used by `run_async()` and `run_async_with()` methods of
+//! the builders. Enabled by `async` feature.
+//!
+//! Commands are executed by [`ToolExecutor::execute_async`] of the executor that is
+//! current on the thread polling the future when the command starts, so replayed and
+//! recorded invocations cover `run_async()` too. [`ProcessExecutor`] runs them with [`tokio::process`] and kills
+//! the child process when the future is dropped, so an invocation is cancelled by
+//! dropping it, e.g. with `tokio::select!`. Other executors run the blocking
+//! [`ToolExecutor::execute`] by default.
+//!
+//! [`ProcessExecutor`]: crate::executor::ProcessExecutor
+//! [`ToolExecutor::execute`]: crate::executor::ToolExecutor::execute
+//! [`ToolExecutor::execute_async`]: crate::executor::ToolExecutor::execute_async
+
+use crate::{
+    command_line::CommandLine,
+    error::*,
+    executor::{current_executor, ExecOptions, LineSplitter, OutputStream},
+};
+use std::{
+    process::{Command, Output, Stdio},
+    time::Instant,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+/// Executes the command asynchronously with given options, then captures an output and
+/// return it. If command termination wasn't successful wraps an output into error and
+/// return it. If the timeout elapses the child process is killed and
+/// [`Error::CmdTimeout`] is returned
+pub async fn output_err_async(command: Command, options: &ExecOptions) -> Result<Output> {
+    let started = Instant::now();
+    let (command, output) = output_async(command, options).await?;
+    if !output.status.success() {
+        return Err(CommandFailure::new(
+            &command,
+            output.status,
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+            started.elapsed(),
+        )
+        .into());
+    }
+    Ok(output)
+}
+
+/// Executes the command asynchronously with given options and returns it back with the
+/// output, whatever the exit status is. The output is captured even if it's printed.
+/// If the timeout elapses the child process is killed and [`Error::CmdTimeout`] is
+/// returned
+pub async fn output_async(
+    mut command: Command,
+    options: &ExecOptions,
+) -> Result<(Command, Output)> {
+    let executor = current_executor();
+    let output = executor.execute_async(&mut command, options).await?;
+    Ok((command, output))
+}
+
+/// Runs the command as a child process with [`tokio::process`]. Used by
+/// [`ProcessExecutor`](crate::executor::ProcessExecutor)
+pub(crate) async fn execute_process(
+    command: &mut Command,
+    options: &ExecOptions,
+) -> Result<Output> {
+    // Output is always captured to report diagnostics of failed commands, and printed
+    // by the readers if needed
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    // The command is moved into tokio and given back once the child is spawned
+    let mut tokio_command =
+        tokio::process::Command::from(std::mem::replace(command, Command::new("")));
+    let child = tokio_command.kill_on_drop(options.kill_on_timeout).spawn();
+    *command = tokio_command.into_std();
+    let mut child = child?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let output = async {
+        let (stdout, stderr, status) = tokio::join!(
+            read_lines(stdout, OutputStream::Stdout, options),
+            read_lines(stderr, OutputStream::Stderr, options),
+            child.wait(),
+        );
+        Ok::<_, std::io::Error>(Output {
+            status: status?,
+            stdout: stdout?,
+            stderr: stderr?,
+        })
+    };
+    match options.timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, output).await {
+            Ok(output) => Ok(output?),
+            Err(_) => {
+                let command_line = CommandLine::new(command);
+                Err(Error::CmdTimeout(command_line.to_string(), timeout))
+            }
+        },
+        None => Ok(output.await?),
+    }
+}
+
+/// Reads output of the command, passing every line to the line callback. The output is
+/// printed as soon as it's read if needed, so prompts and progress without a line
+/// ending are shown too
+async fn read_lines(
+    output: Option<impl AsyncRead + Unpin>,
+    stream: OutputStream,
+    options: &ExecOptions,
+) -> std::io::Result<Vec<u8>> {
+    let mut captured = Vec::new();
+    let Some(mut output) = output else {
+        return Ok(captured);
+    };
+    let mut lines = LineSplitter::new(options, stream);
+    let mut buffer = [0; 8192];
+    loop {
+        let read = output.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        let chunk = &buffer[..read];
+        if options.print_logs {
+            match stream {
+                OutputStream::Stdout => {
+                    let mut stdout = tokio::io::stdout();
+                    stdout.write_all(chunk).await?;
+                    stdout.flush().await?;
+                }
+                OutputStream::Stderr => tokio::io::stderr().write_all(chunk).await?,
+            }
+        }
+        lines.push(chunk);
+        captured.extend_from_slice(chunk);
+    }
+    lines.finish();
+    Ok(captured)
+}
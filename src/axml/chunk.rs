@@ -0,0 +1,103 @@
+use crate::error::*;
+
+pub(crate) const RES_STRING_POOL_TYPE: u16 = 0x0001;
+pub(crate) const RES_TABLE_TYPE: u16 = 0x0002;
+pub(crate) const RES_XML_TYPE: u16 = 0x0003;
+pub(crate) const RES_XML_START_NAMESPACE_TYPE: u16 = 0x0100;
+pub(crate) const RES_XML_END_NAMESPACE_TYPE: u16 = 0x0101;
+pub(crate) const RES_XML_START_ELEMENT_TYPE: u16 = 0x0102;
+pub(crate) const RES_XML_END_ELEMENT_TYPE: u16 = 0x0103;
+pub(crate) const RES_XML_CDATA_TYPE: u16 = 0x0104;
+pub(crate) const RES_XML_RESOURCE_MAP_TYPE: u16 = 0x0180;
+pub(crate) const RES_TABLE_PACKAGE_TYPE: u16 = 0x0200;
+pub(crate) const RES_TABLE_TYPE_TYPE: u16 = 0x0201;
+pub(crate) const RES_TABLE_TYPE_SPEC_TYPE: u16 = 0x0202;
+
+/// Index of the absent string in the string pool
+pub(crate) const NO_INDEX: u32 = 0xffff_ffff;
+
+/// Chunk of the binary resource file, `ResChunk_header` followed by the rest of the
+/// header and the body
+#[derive(Clone, Copy)]
+pub(crate) struct Chunk<'a> {
+    pub chunk_type: u16,
+    /// Whole header including `ResChunk_header`
+    pub header: &'a [u8],
+    pub body: &'a [u8],
+    /// Whole chunk
+    pub data: &'a [u8],
+}
+
+impl<'a> Chunk<'a> {
+    pub fn read(data: &'a [u8], offset: usize) -> Result<Self> {
+        let chunk_type = read_u16(data, offset)?;
+        let header_size = read_u16(data, offset + 2)? as usize;
+        let size = read_u32(data, offset + 4)? as usize;
+        if header_size < 8 || size < header_size {
+            return Err(invalid(format!("malformed chunk 0x{:04x}", chunk_type)));
+        }
+        let data = data
+            .get(offset..offset + size)
+            .ok_or_else(|| invalid(format!("chunk 0x{:04x} is truncated", chunk_type)))?;
+        Ok(Self {
+            chunk_type,
+            header: &data[..header_size],
+            body: &data[header_size..],
+            data,
+        })
+    }
+
+    /// Reads chunks that follow each other till the end of data
+    pub fn read_all(data: &'a [u8]) -> Result<Vec<Self>> {
+        let mut chunks = Vec::new();
+        let mut offset = 0;
+        while offset + 8 <= data.len() {
+            let chunk = Self::read(data, offset)?;
+            offset += chunk.data.len();
+            chunks.push(chunk);
+        }
+        Ok(chunks)
+    }
+
+    pub fn expect(self, chunk_type: u16) -> Result<Self> {
+        if self.chunk_type != chunk_type {
+            return Err(invalid(format!(
+                "expected chunk 0x{:04x}, found 0x{:04x}",
+                chunk_type, self.chunk_type
+            )));
+        }
+        Ok(self)
+    }
+}
+
+/// Appends chunk with given header fields after `ResChunk_header` and body
+pub(crate) fn write_chunk(out: &mut Vec<u8>, chunk_type: u16, header: &[u8], body: &[u8]) {
+    let header_size = 8 + header.len();
+    out.extend_from_slice(&chunk_type.to_le_bytes());
+    out.extend_from_slice(&(header_size as u16).to_le_bytes());
+    out.extend_from_slice(&((header_size + body.len()) as u32).to_le_bytes());
+    out.extend_from_slice(header);
+    out.extend_from_slice(body);
+}
+
+pub(crate) fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidBinaryResource(reason.into())
+}
+
+pub(crate) fn read_u8(data: &[u8], offset: usize) -> Result<u8> {
+    data.get(offset)
+        .copied()
+        .ok_or_else(|| invalid("unexpected end of data"))
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|value| u16::from_le_bytes(value.try_into().unwrap()))
+        .ok_or_else(|| invalid("unexpected end of data"))
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|value| u32::from_le_bytes(value.try_into().unwrap()))
+        .ok_or_else(|| invalid("unexpected end of data"))
+}
@@ -0,0 +1,463 @@
+use super::{chunk::*, ResValue, StringPool, ANDROID_NS};
+use crate::error::*;
+use std::collections::HashMap;
+
+const ATTRIBUTE_SIZE: u16 = 20;
+
+/// Binary XML document, e.g. `AndroidManifest.xml` or layout compiled by aapt2
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlDocument {
+    pub root: XmlElement,
+}
+
+/// Node of the element content
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XmlElement {
+    /// Namespace URI of the element
+    pub namespace: Option<String>,
+    pub name: String,
+    /// Namespaces declared on the element
+    pub namespaces: Vec<XmlNamespace>,
+    pub attributes: Vec<XmlAttribute>,
+    pub children: Vec<XmlNode>,
+    /// Line of the element in the source file
+    pub line_number: u32,
+}
+
+/// Namespace declaration, e.g. `xmlns:android="http://schemas.android.com/apk/res/android"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlNamespace {
+    pub prefix: String,
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlAttribute {
+    /// Namespace URI of the attribute
+    pub namespace: Option<String>,
+    pub name: String,
+    /// Resource ID of the attribute, e.g. `0x0101021b` for `android:versionCode`. The
+    /// platform looks attributes up by ID, not by name
+    pub resource_id: Option<u32>,
+    /// Original string value of the attribute, kept for string values
+    pub raw_value: Option<String>,
+    pub value: ResValue,
+}
+
+impl XmlDocument {
+    /// Parses binary XML chunk
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let xml = Chunk::read(data, 0)?.expect(RES_XML_TYPE)?;
+        let mut strings = StringPool::default();
+        let mut resource_map = Vec::new();
+        let mut namespaces = Vec::new();
+        let mut stack: Vec<XmlElement> = Vec::new();
+        let mut root = None;
+        for chunk in Chunk::read_all(xml.body)? {
+            let line_number = match chunk.header.len() >= 12 {
+                true => read_u32(chunk.header, 8)?,
+                false => 0,
+            };
+            let body = chunk.body;
+            let string = |index: u32| -> Result<String> {
+                strings
+                    .get(index)
+                    .map(str::to_owned)
+                    .ok_or_else(|| invalid(format!("string {} not found", index)))
+            };
+            let optional_string = |index: u32| strings.get(index).map(str::to_owned);
+            match chunk.chunk_type {
+                RES_STRING_POOL_TYPE => strings = StringPool::from_bytes(chunk.data)?,
+                RES_XML_RESOURCE_MAP_TYPE => {
+                    resource_map = body
+                        .chunks_exact(4)
+                        .map(|id| u32::from_le_bytes(id.try_into().unwrap()))
+                        .collect();
+                }
+                RES_XML_START_NAMESPACE_TYPE => namespaces.push(XmlNamespace {
+                    prefix: optional_string(read_u32(body, 0)?).unwrap_or_default(),
+                    uri: string(read_u32(body, 4)?)?,
+                }),
+                RES_XML_END_NAMESPACE_TYPE => {}
+                RES_XML_START_ELEMENT_TYPE => {
+                    let attribute_start = read_u16(body, 8)? as usize;
+                    let attribute_size = read_u16(body, 10)? as usize;
+                    let attribute_count = read_u16(body, 12)? as usize;
+                    let attributes = (0..attribute_count)
+                        .map(|index| {
+                            let offset = attribute_start + index * attribute_size;
+                            let name = read_u32(body, offset + 4)?;
+                            Ok(XmlAttribute {
+                                namespace: optional_string(read_u32(body, offset)?),
+                                name: string(name)?,
+                                resource_id: resource_map
+                                    .get(name as usize)
+                                    .copied()
+                                    .filter(|id| *id != 0),
+                                raw_value: optional_string(read_u32(body, offset + 8)?),
+                                value: ResValue::read(body, offset + 12, &strings)?,
+                            })
+                        })
+                        .collect::<Result<_>>()?;
+                    stack.push(XmlElement {
+                        namespace: optional_string(read_u32(body, 0)?),
+                        name: string(read_u32(body, 4)?)?,
+                        namespaces: std::mem::take(&mut namespaces),
+                        attributes,
+                        children: Vec::new(),
+                        line_number,
+                    });
+                }
+                RES_XML_END_ELEMENT_TYPE => {
+                    let element = stack
+                        .pop()
+                        .ok_or_else(|| invalid("unexpected end of element"))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(XmlNode::Element(element)),
+                        None if root.is_none() => root = Some(element),
+                        None => return Err(invalid("multiple root elements")),
+                    }
+                }
+                RES_XML_CDATA_TYPE => {
+                    if let Some(parent) = stack.last_mut() {
+                        parent
+                            .children
+                            .push(XmlNode::Text(string(read_u32(body, 0)?)?));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !stack.is_empty() {
+            return Err(invalid("element is not closed"));
+        }
+        Ok(Self {
+            root: root.ok_or_else(|| invalid("root element not found"))?,
+        })
+    }
+
+    /// Serializes the document into binary XML chunk. Attributes with resource IDs are
+    /// written first, sorted by ID, as the platform expects
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut pool = PoolBuilder::default();
+        let mut attribute_ids = Vec::new();
+        collect_attribute_ids(&self.root, &mut attribute_ids);
+        attribute_ids.sort_by(|(a_name, a_id), (b_name, b_id)| (a_id, a_name).cmp(&(b_id, b_name)));
+        attribute_ids.dedup();
+        for (name, id) in attribute_ids {
+            pool.attribute_names
+                .insert((name.clone(), id), pool.strings.len() as u32);
+            pool.strings.push(name);
+            pool.resource_map.push(id);
+        }
+        let mut nodes = Vec::new();
+        write_element(&self.root, &mut pool, &mut nodes);
+
+        let strings = StringPool {
+            strings: pool.strings,
+            ..Default::default()
+        };
+        let mut resource_map = Vec::new();
+        let ids: Vec<u8> = pool
+            .resource_map
+            .iter()
+            .flat_map(|id| id.to_le_bytes())
+            .collect();
+        write_chunk(&mut resource_map, RES_XML_RESOURCE_MAP_TYPE, &[], &ids);
+        let mut out = Vec::new();
+        write_chunk(
+            &mut out,
+            RES_XML_TYPE,
+            &[],
+            &[strings.to_bytes()?, resource_map, nodes].concat(),
+        );
+        Ok(out)
+    }
+}
+
+impl XmlElement {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the attribute by name in any namespace
+    pub fn attribute(&self, name: &str) -> Option<&XmlAttribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+
+    /// Returns the mutable attribute by name in any namespace
+    pub fn attribute_mut(&mut self, name: &str) -> Option<&mut XmlAttribute> {
+        self.attributes
+            .iter_mut()
+            .find(|attribute| attribute.name == name)
+    }
+
+    /// Adds the attribute or replaces the attribute with the same namespace and name
+    pub fn set_attribute(&mut self, attribute: XmlAttribute) -> &mut Self {
+        match self.attributes.iter_mut().find(|existing| {
+            existing.namespace == attribute.namespace && existing.name == attribute.name
+        }) {
+            Some(existing) => *existing = attribute,
+            None => self.attributes.push(attribute),
+        }
+        self
+    }
+
+    /// Removes the attribute by name in any namespace
+    pub fn remove_attribute(&mut self, name: &str) -> Option<XmlAttribute> {
+        let index = self
+            .attributes
+            .iter()
+            .position(|attribute| attribute.name == name)?;
+        Some(self.attributes.remove(index))
+    }
+
+    /// Returns child elements
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|child| match child {
+            XmlNode::Element(element) => Some(element),
+            XmlNode::Text(_) => None,
+        })
+    }
+
+    /// Returns mutable child elements
+    pub fn elements_mut(&mut self) -> impl Iterator<Item = &mut XmlElement> {
+        self.children.iter_mut().filter_map(|child| match child {
+            XmlNode::Element(element) => Some(element),
+            XmlNode::Text(_) => None,
+        })
+    }
+
+    /// Returns the first child element with given name
+    pub fn element(&self, name: &str) -> Option<&XmlElement> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// Returns the first mutable child element with given name
+    pub fn element_mut(&mut self, name: &str) -> Option<&mut XmlElement> {
+        self.elements_mut().find(|element| element.name == name)
+    }
+}
+
+impl XmlAttribute {
+    /// Creates attribute in the `android` namespace, e.g.
+    /// `XmlAttribute::android("debuggable", ATTR_DEBUGGABLE, ResValue::Boolean(true))`
+    pub fn android(name: &str, resource_id: u32, value: ResValue) -> Self {
+        let raw_value = match &value {
+            ResValue::String(string) => Some(string.clone()),
+            _ => None,
+        };
+        Self {
+            namespace: Some(ANDROID_NS.to_owned()),
+            name: name.to_owned(),
+            resource_id: Some(resource_id),
+            raw_value,
+            value,
+        }
+    }
+}
+
+/// Prints the document as text XML
+impl std::fmt::Display for XmlDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+        fmt_element(f, &self.root, 0, &mut Vec::new())
+    }
+}
+
+fn fmt_element<'a>(
+    f: &mut std::fmt::Formatter<'_>,
+    element: &'a XmlElement,
+    depth: usize,
+    scope: &mut Vec<&'a XmlNamespace>,
+) -> std::fmt::Result {
+    scope.extend(&element.namespaces);
+    let prefixed = |namespace: &Option<String>, name: &str| match namespace {
+        Some(uri) => match scope.iter().rev().find(|namespace| &namespace.uri == uri) {
+            Some(namespace) => format!("{}:{}", namespace.prefix, name),
+            None => format!("{{{}}}{}", uri, name),
+        },
+        None => name.to_owned(),
+    };
+    let indent = "  ".repeat(depth);
+    let name = prefixed(&element.namespace, &element.name);
+    write!(f, "{}<{}", indent, name)?;
+    for namespace in &element.namespaces {
+        write!(
+            f,
+            r#" xmlns:{}="{}""#,
+            namespace.prefix,
+            escape(&namespace.uri)
+        )?;
+    }
+    for attribute in &element.attributes {
+        let value = match &attribute.raw_value {
+            Some(raw_value) => raw_value.clone(),
+            None => attribute.value.to_string(),
+        };
+        write!(
+            f,
+            r#" {}="{}""#,
+            prefixed(&attribute.namespace, &attribute.name),
+            escape(&value)
+        )?;
+    }
+    if element.children.is_empty() {
+        writeln!(f, "/>")?;
+    } else {
+        writeln!(f, ">")?;
+        for child in &element.children {
+            match child {
+                XmlNode::Element(child) => fmt_element(f, child, depth + 1, scope)?,
+                XmlNode::Text(text) => writeln!(f, "{}  {}", indent, escape(text))?,
+            }
+        }
+        writeln!(f, "{}</{}>", indent, name)?;
+    }
+    scope.truncate(scope.len() - element.namespaces.len());
+    Ok(())
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Strings of the document. Attribute names with resource IDs are kept apart from
+/// other strings, so the same name without ID isn't mapped to the resource
+#[derive(Default)]
+struct PoolBuilder {
+    strings: Vec<String>,
+    indexes: HashMap<String, u32>,
+    attribute_names: HashMap<(String, u32), u32>,
+    resource_map: Vec<u32>,
+}
+
+impl PoolBuilder {
+    fn intern(&mut self, string: &str) -> u32 {
+        if let Some(index) = self.indexes.get(string) {
+            return *index;
+        }
+        let index = self.strings.len() as u32;
+        self.strings.push(string.to_owned());
+        self.indexes.insert(string.to_owned(), index);
+        index
+    }
+
+    fn intern_optional(&mut self, string: Option<&str>) -> u32 {
+        string.map_or(NO_INDEX, |string| self.intern(string))
+    }
+
+    fn attribute_name(&mut self, attribute: &XmlAttribute) -> u32 {
+        match attribute.resource_id {
+            Some(id) => self.attribute_names[&(attribute.name.clone(), id)],
+            None => self.intern(&attribute.name),
+        }
+    }
+}
+
+fn collect_attribute_ids(element: &XmlElement, ids: &mut Vec<(String, u32)>) {
+    for attribute in &element.attributes {
+        if let Some(id) = attribute.resource_id {
+            ids.push((attribute.name.clone(), id));
+        }
+    }
+    for child in element.elements() {
+        collect_attribute_ids(child, ids);
+    }
+}
+
+/// Appends `ResXMLTree_node` with comment-less header and given extension
+fn write_node(out: &mut Vec<u8>, node_type: u16, line_number: u32, ext: &[u8]) {
+    let mut header = Vec::with_capacity(8);
+    header.extend_from_slice(&line_number.to_le_bytes());
+    header.extend_from_slice(&NO_INDEX.to_le_bytes());
+    write_chunk(out, node_type, &header, ext);
+}
+
+fn write_element(element: &XmlElement, pool: &mut PoolBuilder, out: &mut Vec<u8>) {
+    let line = element.line_number;
+    for namespace in &element.namespaces {
+        let ext = [pool.intern(&namespace.prefix), pool.intern(&namespace.uri)];
+        write_node(out, RES_XML_START_NAMESPACE_TYPE, line, &u32s(&ext));
+    }
+    let mut attributes: Vec<&XmlAttribute> = element.attributes.iter().collect();
+    attributes.sort_by(|a, b| match (a.resource_id, b.resource_id) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)),
+    });
+    // 1-based indexes of `android:id`, `class` and `style` attributes
+    let index_of = |namespace: Option<&str>, name: &str| {
+        attributes
+            .iter()
+            .position(|attribute| {
+                attribute.namespace.as_deref() == namespace && attribute.name == name
+            })
+            .map_or(0, |index| index as u16 + 1)
+    };
+    let special_indexes = [
+        index_of(Some(ANDROID_NS), "id"),
+        index_of(None, "class"),
+        index_of(None, "style"),
+    ];
+    let namespace = pool.intern_optional(element.namespace.as_deref());
+    let name = pool.intern(&element.name);
+    let mut ext = u32s(&[namespace, name]);
+    ext.extend_from_slice(&ATTRIBUTE_SIZE.to_le_bytes());
+    ext.extend_from_slice(&ATTRIBUTE_SIZE.to_le_bytes());
+    ext.extend_from_slice(&(attributes.len() as u16).to_le_bytes());
+    for index in special_indexes {
+        ext.extend_from_slice(&index.to_le_bytes());
+    }
+    for attribute in attributes {
+        let namespace = pool.intern_optional(attribute.namespace.as_deref());
+        let name = pool.attribute_name(attribute);
+        let raw_value = pool.intern_optional(attribute.raw_value.as_deref());
+        ext.extend_from_slice(&u32s(&[namespace, name, raw_value]));
+        ext.extend_from_slice(&attribute.value.to_bytes(&mut |string| pool.intern(string)));
+    }
+    write_node(out, RES_XML_START_ELEMENT_TYPE, line, &ext);
+    for child in &element.children {
+        match child {
+            XmlNode::Element(child) => write_element(child, pool, out),
+            XmlNode::Text(text) => {
+                let mut ext = u32s(&[pool.intern(text)]);
+                ext.extend_from_slice(&ResValue::Null.to_bytes(&mut |_| NO_INDEX));
+                write_node(out, RES_XML_CDATA_TYPE, line, &ext);
+            }
+        }
+    }
+    write_node(
+        out,
+        RES_XML_END_ELEMENT_TYPE,
+        line,
+        &u32s(&[namespace, name]),
+    );
+    for namespace in element.namespaces.iter().rev() {
+        let ext = [pool.intern(&namespace.prefix), pool.intern(&namespace.uri)];
+        write_node(out, RES_XML_END_NAMESPACE_TYPE, line, &u32s(&ext));
+    }
+}
+
+fn u32s(values: &[u32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
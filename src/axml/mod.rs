@@ -0,0 +1,49 @@
+//! Native reader and writer of Android binary XML (AXML).
+//!
+//! Parses compiled `AndroidManifest.xml` and layouts from APKs into a tree of elements
+//! with typed attribute values, and serializes the tree back, so manifests can be
+//! inspected and patched without aapt2.
+//!
+//! ```no_run
+//! use android_tools::axml::{ResValue, XmlAttribute, XmlDocument, ATTR_VERSION_CODE};
+//!
+//! let data = std::fs::read("AndroidManifest.xml").unwrap();
+//! let mut manifest = XmlDocument::from_bytes(&data).unwrap();
+//! manifest.root.set_attribute(XmlAttribute::android(
+//!     "versionCode",
+//!     ATTR_VERSION_CODE,
+//!     ResValue::Int(2),
+//! ));
+//! std::fs::write("AndroidManifest.xml", manifest.to_bytes().unwrap()).unwrap();
+//! ```
+
+pub(crate) mod chunk;
+mod document;
+mod res_value;
+mod string_pool;
+
+pub use document::*;
+pub use res_value::*;
+pub use string_pool::*;
+
+/// URI of the `android` namespace
+pub const ANDROID_NS: &str = "http://schemas.android.com/apk/res/android";
+
+/// Resource ID of `android:label` attribute
+pub const ATTR_LABEL: u32 = 0x0101_0001;
+/// Resource ID of `android:icon` attribute
+pub const ATTR_ICON: u32 = 0x0101_0002;
+/// Resource ID of `android:name` attribute
+pub const ATTR_NAME: u32 = 0x0101_0003;
+/// Resource ID of `android:debuggable` attribute
+pub const ATTR_DEBUGGABLE: u32 = 0x0101_000f;
+/// Resource ID of `android:exported` attribute
+pub const ATTR_EXPORTED: u32 = 0x0101_0010;
+/// Resource ID of `android:minSdkVersion` attribute
+pub const ATTR_MIN_SDK_VERSION: u32 = 0x0101_020c;
+/// Resource ID of `android:versionCode` attribute
+pub const ATTR_VERSION_CODE: u32 = 0x0101_021b;
+/// Resource ID of `android:versionName` attribute
+pub const ATTR_VERSION_NAME: u32 = 0x0101_021c;
+/// Resource ID of `android:targetSdkVersion` attribute
+pub const ATTR_TARGET_SDK_VERSION: u32 = 0x0101_0270;
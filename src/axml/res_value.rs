@@ -0,0 +1,176 @@
+use super::{chunk::*, StringPool};
+use crate::error::*;
+
+const TYPE_NULL: u8 = 0x00;
+const TYPE_REFERENCE: u8 = 0x01;
+const TYPE_ATTRIBUTE: u8 = 0x02;
+const TYPE_STRING: u8 = 0x03;
+const TYPE_FLOAT: u8 = 0x04;
+const TYPE_DIMENSION: u8 = 0x05;
+const TYPE_FRACTION: u8 = 0x06;
+const TYPE_DYNAMIC_REFERENCE: u8 = 0x07;
+const TYPE_DYNAMIC_ATTRIBUTE: u8 = 0x08;
+const TYPE_INT_DEC: u8 = 0x10;
+const TYPE_INT_HEX: u8 = 0x11;
+const TYPE_INT_BOOLEAN: u8 = 0x12;
+const TYPE_INT_COLOR_ARGB8: u8 = 0x1c;
+const TYPE_INT_COLOR_RGB8: u8 = 0x1d;
+const TYPE_INT_COLOR_ARGB4: u8 = 0x1e;
+const TYPE_INT_COLOR_RGB4: u8 = 0x1f;
+const DATA_NULL_EMPTY: u32 = 1;
+
+const DIMENSION_UNITS: [&str; 6] = ["px", "dp", "sp", "pt", "in", "mm"];
+const FRACTION_UNITS: [&str; 2] = ["%", "%p"];
+
+/// Typed value of attribute or resource, `Res_value`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResValue {
+    /// Undefined value, `@null`
+    Null,
+    /// Explicitly empty value, `@empty`
+    Empty,
+    /// Reference to resource, e.g. `@string/app_name`
+    Reference(u32),
+    /// Reference to theme attribute, e.g. `?attr/colorPrimary`
+    Attribute(u32),
+    String(String),
+    Float(f32),
+    /// Dimension in the complex format, see [`ResValue::complex_value`]
+    Dimension(u32),
+    /// Fraction in the complex format, see [`ResValue::complex_value`]
+    Fraction(u32),
+    /// Reference to resource of shared library, resolved at runtime
+    DynamicReference(u32),
+    /// Reference to theme attribute of shared library, resolved at runtime
+    DynamicAttribute(u32),
+    Int(i32),
+    Hex(u32),
+    Boolean(bool),
+    /// Color in `#aarrggbb` format
+    ColorArgb8(u32),
+    /// Color in `#rrggbb` format
+    ColorRgb8(u32),
+    /// Color in `#argb` format
+    ColorArgb4(u32),
+    /// Color in `#rgb` format
+    ColorRgb4(u32),
+    /// Value of unknown type
+    Other {
+        data_type: u8,
+        data: u32,
+    },
+}
+
+impl ResValue {
+    /// Parses `Res_value` at the offset. String values are resolved with the pool
+    pub(crate) fn read(data: &[u8], offset: usize, strings: &StringPool) -> Result<Self> {
+        Self::from_raw(
+            read_u8(data, offset + 3)?,
+            read_u32(data, offset + 4)?,
+            strings,
+        )
+    }
+
+    /// Creates value from `Res_value` type and data
+    pub(crate) fn from_raw(data_type: u8, data: u32, strings: &StringPool) -> Result<Self> {
+        Ok(match data_type {
+            TYPE_NULL if data == DATA_NULL_EMPTY => Self::Empty,
+            TYPE_NULL => Self::Null,
+            TYPE_REFERENCE => Self::Reference(data),
+            TYPE_ATTRIBUTE => Self::Attribute(data),
+            TYPE_STRING => Self::String(
+                strings
+                    .get(data)
+                    .ok_or_else(|| invalid(format!("string {} not found", data)))?
+                    .to_owned(),
+            ),
+            TYPE_FLOAT => Self::Float(f32::from_bits(data)),
+            TYPE_DIMENSION => Self::Dimension(data),
+            TYPE_FRACTION => Self::Fraction(data),
+            TYPE_DYNAMIC_REFERENCE => Self::DynamicReference(data),
+            TYPE_DYNAMIC_ATTRIBUTE => Self::DynamicAttribute(data),
+            TYPE_INT_DEC => Self::Int(data as i32),
+            TYPE_INT_HEX => Self::Hex(data),
+            TYPE_INT_BOOLEAN => Self::Boolean(data != 0),
+            TYPE_INT_COLOR_ARGB8 => Self::ColorArgb8(data),
+            TYPE_INT_COLOR_RGB8 => Self::ColorRgb8(data),
+            TYPE_INT_COLOR_ARGB4 => Self::ColorArgb4(data),
+            TYPE_INT_COLOR_RGB4 => Self::ColorRgb4(data),
+            data_type => Self::Other { data_type, data },
+        })
+    }
+
+    /// Encodes `Res_value`. String values are added to the pool with `intern`
+    pub(crate) fn to_bytes(&self, intern: &mut impl FnMut(&str) -> u32) -> [u8; 8] {
+        let (data_type, data) = match self {
+            Self::Null => (TYPE_NULL, 0),
+            Self::Empty => (TYPE_NULL, DATA_NULL_EMPTY),
+            Self::Reference(data) => (TYPE_REFERENCE, *data),
+            Self::Attribute(data) => (TYPE_ATTRIBUTE, *data),
+            Self::String(string) => (TYPE_STRING, intern(string)),
+            Self::Float(value) => (TYPE_FLOAT, value.to_bits()),
+            Self::Dimension(data) => (TYPE_DIMENSION, *data),
+            Self::Fraction(data) => (TYPE_FRACTION, *data),
+            Self::DynamicReference(data) => (TYPE_DYNAMIC_REFERENCE, *data),
+            Self::DynamicAttribute(data) => (TYPE_DYNAMIC_ATTRIBUTE, *data),
+            Self::Int(value) => (TYPE_INT_DEC, *value as u32),
+            Self::Hex(data) => (TYPE_INT_HEX, *data),
+            Self::Boolean(value) => (TYPE_INT_BOOLEAN, if *value { u32::MAX } else { 0 }),
+            Self::ColorArgb8(data) => (TYPE_INT_COLOR_ARGB8, *data),
+            Self::ColorRgb8(data) => (TYPE_INT_COLOR_RGB8, *data),
+            Self::ColorArgb4(data) => (TYPE_INT_COLOR_ARGB4, *data),
+            Self::ColorRgb4(data) => (TYPE_INT_COLOR_RGB4, *data),
+            Self::Other { data_type, data } => (*data_type, *data),
+        };
+        let mut value = [0; 8];
+        value[..2].copy_from_slice(&8u16.to_le_bytes());
+        value[3] = data_type;
+        value[4..].copy_from_slice(&data.to_le_bytes());
+        value
+    }
+
+    /// Returns number and unit index of dimension or fraction stored in the complex
+    /// format: 24-bit mantissa, radix and unit
+    pub fn complex_value(data: u32) -> (f32, usize) {
+        const RADIX_MULTIPLIERS: [f32; 4] = [
+            1.0 / (1 << 8) as f32,
+            1.0 / (1 << 15) as f32,
+            1.0 / (1 << 23) as f32,
+            1.0 / (1u64 << 31) as f32,
+        ];
+        let mantissa = (data & 0xffff_ff00) as i32 as f32;
+        let radix = ((data >> 4) & 0x3) as usize;
+        (mantissa * RADIX_MULTIPLIERS[radix], (data & 0xf) as usize)
+    }
+}
+
+impl std::fmt::Display for ResValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Null => write!(f, "@null"),
+            Self::Empty => write!(f, "@empty"),
+            Self::Reference(id) | Self::DynamicReference(id) => write!(f, "@0x{:08x}", id),
+            Self::Attribute(id) | Self::DynamicAttribute(id) => write!(f, "?0x{:08x}", id),
+            Self::String(string) => write!(f, "{}", string),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::Dimension(data) => {
+                let (value, unit) = Self::complex_value(*data);
+                let unit = DIMENSION_UNITS.get(unit).copied().unwrap_or("");
+                write!(f, "{}{}", value, unit)
+            }
+            Self::Fraction(data) => {
+                let (value, unit) = Self::complex_value(*data);
+                let unit = FRACTION_UNITS.get(unit).copied().unwrap_or("");
+                write!(f, "{}{}", value * 100.0, unit)
+            }
+            Self::Int(value) => write!(f, "{}", value),
+            Self::Hex(value) => write!(f, "0x{:x}", value),
+            Self::Boolean(value) => write!(f, "{}", value),
+            Self::ColorArgb8(argb) | Self::ColorArgb4(argb) => write!(f, "#{:08x}", argb),
+            Self::ColorRgb8(argb) | Self::ColorRgb4(argb) => {
+                write!(f, "#{:06x}", argb & 0x00ff_ffff)
+            }
+            Self::Other { data_type, data } => write!(f, "(0x{:02x}) 0x{:08x}", data_type, data),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use super::chunk::*;
+use crate::error::*;
+
+const SORTED_FLAG: u32 = 1 << 0;
+const UTF8_FLAG: u32 = 1 << 8;
+const SPAN_END: u32 = 0xffff_ffff;
+/// Longest string of UTF-8 pools, both in bytes and in UTF-16 units
+const MAX_UTF8_LEN: usize = 0x7fff;
+/// Longest string of UTF-16 pools in UTF-16 units
+const MAX_UTF16_LEN: usize = 0x7fff_ffff;
+
+/// String pool chunk, `ResStringPool`, shared by binary XML and resource table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StringPool {
+    pub strings: Vec<String>,
+    /// Style spans of the first strings of the pool, e.g. `<b>` in a string resource
+    pub styles: Vec<Vec<StringSpan>>,
+    /// Strings are encoded in UTF-8 instead of UTF-16. Pools with strings longer than
+    /// 32767 bytes are written in UTF-16 anyway
+    pub utf8: bool,
+    pub sorted: bool,
+}
+
+/// Styled range of the string, `ResStringPool_span`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StringSpan {
+    /// Index of the style tag name in the pool, e.g. `b`
+    pub name: u32,
+    pub first_char: u32,
+    pub last_char: u32,
+}
+
+impl StringPool {
+    /// Parses the whole string pool chunk
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let chunk = Chunk::read(data, 0)?.expect(RES_STRING_POOL_TYPE)?;
+        let data = chunk.data;
+        let string_count = read_u32(data, 8)? as usize;
+        let style_count = read_u32(data, 12)? as usize;
+        let flags = read_u32(data, 16)?;
+        let strings_start = read_u32(data, 20)? as usize;
+        let styles_start = read_u32(data, 24)? as usize;
+        let utf8 = flags & UTF8_FLAG != 0;
+        let offsets = chunk.header.len();
+        let strings = (0..string_count)
+            .map(|index| {
+                let offset = strings_start + read_u32(data, offsets + index * 4)? as usize;
+                if utf8 {
+                    read_utf8(data, offset)
+                } else {
+                    read_utf16(data, offset)
+                }
+            })
+            .collect::<Result<_>>()?;
+        let styles = (0..style_count)
+            .map(|index| {
+                let offset =
+                    read_u32(data, offsets + (string_count + index) * 4)? as usize + styles_start;
+                let mut spans = Vec::new();
+                loop {
+                    let name = read_u32(data, offset + spans.len() * 12)?;
+                    if name == SPAN_END {
+                        return Ok(spans);
+                    }
+                    spans.push(StringSpan {
+                        name,
+                        first_char: read_u32(data, offset + spans.len() * 12 + 4)?,
+                        last_char: read_u32(data, offset + spans.len() * 12 + 8)?,
+                    });
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            strings,
+            styles,
+            utf8,
+            sorted: flags & SORTED_FLAG != 0,
+        })
+    }
+
+    /// Returns string by index, `None` for `0xffffffff` or out of bounds index
+    pub fn get(&self, index: u32) -> Option<&str> {
+        self.strings.get(index as usize).map(String::as_str)
+    }
+
+    /// Serializes the pool into string pool chunk. Fails if a string is longer than
+    /// string pools can encode, `0x7fffffff` UTF-16 units
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        // UTF-16 units of a string never outnumber its UTF-8 bytes
+        let too_long = self.strings.iter().find(|string| {
+            string.len() > MAX_UTF16_LEN && string.encode_utf16().count() > MAX_UTF16_LEN
+        });
+        if let Some(string) = too_long {
+            return Err(invalid(format!(
+                "string of {} UTF-16 units is too long for string pool",
+                string.encode_utf16().count()
+            )));
+        }
+        let utf8 = self.utf8
+            && self
+                .strings
+                .iter()
+                .all(|string| string.len() <= MAX_UTF8_LEN);
+        let mut strings = Vec::new();
+        let mut offsets = Vec::new();
+        for string in &self.strings {
+            offsets.extend_from_slice(&(strings.len() as u32).to_le_bytes());
+            if utf8 {
+                write_utf8(&mut strings, string);
+            } else {
+                write_utf16(&mut strings, string);
+            }
+        }
+        strings.resize(strings.len().next_multiple_of(4), 0);
+        let mut styles = Vec::new();
+        for spans in &self.styles {
+            offsets.extend_from_slice(&(styles.len() as u32).to_le_bytes());
+            for span in spans {
+                styles.extend_from_slice(&span.name.to_le_bytes());
+                styles.extend_from_slice(&span.first_char.to_le_bytes());
+                styles.extend_from_slice(&span.last_char.to_le_bytes());
+            }
+            styles.extend_from_slice(&SPAN_END.to_le_bytes());
+        }
+        if !self.styles.is_empty() {
+            styles.extend_from_slice(&SPAN_END.to_le_bytes());
+            styles.extend_from_slice(&SPAN_END.to_le_bytes());
+        }
+        let header_size = 28;
+        let strings_start = header_size + offsets.len();
+        let styles_start = match self.styles.is_empty() {
+            true => 0,
+            false => strings_start + strings.len(),
+        };
+        let mut flags = 0;
+        if utf8 {
+            flags |= UTF8_FLAG;
+        }
+        if self.sorted {
+            flags |= SORTED_FLAG;
+        }
+        let mut header = Vec::with_capacity(20);
+        header.extend_from_slice(&(self.strings.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(self.styles.len() as u32).to_le_bytes());
+        header.extend_from_slice(&flags.to_le_bytes());
+        header.extend_from_slice(&(strings_start as u32).to_le_bytes());
+        header.extend_from_slice(&(styles_start as u32).to_le_bytes());
+        let mut out = Vec::new();
+        write_chunk(
+            &mut out,
+            RES_STRING_POOL_TYPE,
+            &header,
+            &[offsets, strings, styles].concat(),
+        );
+        Ok(out)
+    }
+}
+
+/// Reads UTF-8 string prefixed with its length in UTF-16 units and in bytes
+fn read_utf8(data: &[u8], offset: usize) -> Result<String> {
+    let utf8_len = |offset: usize| -> Result<(usize, usize)> {
+        let first = read_u8(data, offset)? as usize;
+        if first & 0x80 == 0 {
+            return Ok((first, 1));
+        }
+        Ok((
+            ((first & 0x7f) << 8) | read_u8(data, offset + 1)? as usize,
+            2,
+        ))
+    };
+    let (_, skip) = utf8_len(offset)?;
+    let (len, size) = utf8_len(offset + skip)?;
+    let start = offset + skip + size;
+    let bytes = data
+        .get(start..start + len)
+        .ok_or_else(|| invalid("string is truncated"))?;
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Reads UTF-16 string prefixed with its length in UTF-16 units
+fn read_utf16(data: &[u8], offset: usize) -> Result<String> {
+    let first = read_u16(data, offset)? as usize;
+    let (len, start) = match first & 0x8000 {
+        0 => (first, offset + 2),
+        _ => (
+            ((first & 0x7fff) << 16) | read_u16(data, offset + 2)? as usize,
+            offset + 4,
+        ),
+    };
+    let units = (0..len)
+        .map(|index| read_u16(data, start + index * 2))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn write_utf8(out: &mut Vec<u8>, string: &str) {
+    let write_len = |out: &mut Vec<u8>, len: usize| {
+        if len < 0x80 {
+            out.push(len as u8);
+        } else {
+            out.push(0x80 | (len >> 8) as u8);
+            out.push(len as u8);
+        }
+    };
+    write_len(out, string.encode_utf16().count());
+    write_len(out, string.len());
+    out.extend_from_slice(string.as_bytes());
+    out.push(0);
+}
+
+fn write_utf16(out: &mut Vec<u8>, string: &str) {
+    let units: Vec<u16> = string.encode_utf16().collect();
+    if units.len() < 0x8000 {
+        out.extend_from_slice(&(units.len() as u16).to_le_bytes());
+    } else {
+        out.extend_from_slice(&(0x8000 | (units.len() >> 16) as u16).to_le_bytes());
+        out.extend_from_slice(&(units.len() as u16).to_le_bytes());
+    }
+    for unit in units.into_iter().chain([0]) {
+        out.extend_from_slice(&unit.to_le_bytes());
+    }
+}
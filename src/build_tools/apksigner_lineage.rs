@@ -0,0 +1,92 @@
+use super::{apksigner_tool, ApksignerCertificate};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// # Lineage
+/// Prints certificates of the signing certificate lineage created by
+/// [`ApksignerRotate`](super::ApksignerRotate), or of the lineage the APK is signed with.
+#[derive(Clone, Default)]
+pub struct ApksignerLineage {
+    input: PathBuf,
+    out: Option<PathBuf>,
+    print_certs: bool,
+}
+
+impl ApksignerLineage {
+    /// Initialize lineage with the lineage file or the signed APK. Certificates are
+    /// printed by default
+    pub fn new(input: &Path) -> Self {
+        Self {
+            input: input.to_owned(),
+            print_certs: true,
+            ..Default::default()
+        }
+    }
+
+    /// Writes the lineage to given file, e.g. to extract it from APK
+    pub fn out(&mut self, out: &Path) -> &mut Self {
+        self.out = Some(out.to_owned());
+        self
+    }
+
+    /// Show information about the certificates of the lineage [default: true]
+    pub fn print_certs(&mut self, print_certs: bool) -> &mut Self {
+        self.print_certs = print_certs;
+        self
+    }
+
+    /// Executes apksigner lineage and returns the certificates from the oldest to the
+    /// newest
+    pub fn run(&self) -> Result<Vec<ApksignerCertificate>> {
+        self.run_with(ExecOptions::new().print_logs(false))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<Vec<ApksignerCertificate>> {
+        let output = self.command()?.output_with(options)?;
+        Ok(ApksignerCertificate::parse_output(
+            &String::from_utf8_lossy(&output.stdout),
+        ))
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<Vec<ApksignerCertificate>> {
+        self.run_async_with(&ExecOptions::new()).await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<Vec<ApksignerCertificate>> {
+        let output = crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(ApksignerCertificate::parse_output(
+            &String::from_utf8_lossy(&output.stdout),
+        ))
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut apksigner = apksigner_tool()?;
+        apksigner.arg("lineage");
+        apksigner.arg("--in").arg(&self.input);
+        if let Some(out) = &self.out {
+            apksigner.arg("--out").arg(out);
+        }
+        if self.print_certs {
+            apksigner.arg("--print-certs");
+        }
+        Ok(apksigner)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+}
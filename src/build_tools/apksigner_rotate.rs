@@ -0,0 +1,98 @@
+use super::{apksigner_tool, ApksignerSigner};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// # Rotate
+/// Creates the signing certificate lineage, or adds the new signer to existing one.
+/// The lineage is passed to [`ApksignerSign::lineage`](super::ApksignerSign::lineage)
+/// to sign APKs with the rotated key.
+#[derive(Clone, Default)]
+pub struct ApksignerRotate {
+    out: PathBuf,
+    input: Option<PathBuf>,
+    old_signer: ApksignerSigner,
+    new_signer: ApksignerSigner,
+    min_sdk_version: Option<u32>,
+}
+
+impl ApksignerRotate {
+    /// Initialize rotate with the output lineage file, the current and the new signers
+    pub fn new(out: &Path, old_signer: &ApksignerSigner, new_signer: &ApksignerSigner) -> Self {
+        Self {
+            out: out.to_owned(),
+            old_signer: old_signer.clone(),
+            new_signer: new_signer.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Existing lineage to add the new signer to. The old signer must be the last
+    /// signer of the lineage
+    pub fn input(&mut self, input: &Path) -> &mut Self {
+        self.input = Some(input.to_owned());
+        self
+    }
+
+    /// The lowest API level the new signer is used on
+    pub fn min_sdk_version(&mut self, min_sdk_version: u32) -> &mut Self {
+        self.min_sdk_version = Some(min_sdk_version);
+        self
+    }
+
+    /// Executes apksigner rotate and returns path to the lineage
+    pub fn run(&self) -> Result<PathBuf> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        self.command()?.output_with(options)?;
+        Ok(self.out.clone())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<PathBuf> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(self.out.clone())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut apksigner = apksigner_tool()?;
+        apksigner.arg("rotate");
+        apksigner.arg("--out").arg(&self.out);
+        if let Some(input) = &self.input {
+            apksigner.arg("--in").arg(input);
+        }
+        if let Some(min_sdk_version) = self.min_sdk_version {
+            apksigner
+                .arg("--min-sdk-version")
+                .arg(min_sdk_version.to_string());
+        }
+        apksigner.arg("--old-signer");
+        self.old_signer.apply(&mut apksigner);
+        apksigner.arg("--new-signer");
+        self.new_signer.apply(&mut apksigner);
+        Ok(apksigner)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+}
@@ -0,0 +1,210 @@
+use super::{apksigner_tool, ApksignerSigner};
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// # Sign
+/// Signs APK in place or writes signed copy to `out`. Signature schemes are enabled
+/// by `apksigner` according to min and max SDK versions unless they are set
+/// explicitly.
+///
+/// ```no_run
+/// use android_tools::build_tools::{Apksigner, ApksignerSigner};
+/// use std::path::Path;
+///
+/// let mut signer = ApksignerSigner::new();
+/// signer
+///     .ks(Path::new("release.keystore"))
+///     .ks_pass_pass("android".to_owned());
+/// Apksigner
+///     .sign(Path::new("app.apk"), &signer)
+///     .v4_signing_enabled(false)
+///     .run()
+///     .unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct ApksignerSign {
+    apk: PathBuf,
+    signers: Vec<ApksignerSigner>,
+    out: Option<PathBuf>,
+    v1_signing_enabled: Option<bool>,
+    v2_signing_enabled: Option<bool>,
+    v3_signing_enabled: Option<bool>,
+    v4_signing_enabled: Option<bool>,
+    min_sdk_version: Option<u32>,
+    max_sdk_version: Option<u32>,
+    lineage: Option<PathBuf>,
+    rotation_min_sdk_version: Option<u32>,
+    verbose: bool,
+}
+
+impl ApksignerSign {
+    /// Initialize sign with APK and the signer
+    pub fn new(apk: &Path, signer: &ApksignerSigner) -> Self {
+        Self {
+            apk: apk.to_owned(),
+            signers: vec![signer.clone()],
+            ..Default::default()
+        }
+    }
+
+    /// Adds the next signer. The signers after the first one are used with key
+    /// rotation or to sign with several keys
+    pub fn next_signer(&mut self, signer: &ApksignerSigner) -> &mut Self {
+        self.signers.push(signer.clone());
+        self
+    }
+
+    /// The location where you'd like to save the signed APK. If this option isn't
+    /// provided explicitly, the APK package is signed in-place
+    pub fn out(&mut self, out: &Path) -> &mut Self {
+        self.out = Some(out.to_owned());
+        self
+    }
+
+    /// Whether to sign with JAR signing scheme (v1)
+    pub fn v1_signing_enabled(&mut self, v1_signing_enabled: bool) -> &mut Self {
+        self.v1_signing_enabled = Some(v1_signing_enabled);
+        self
+    }
+
+    /// Whether to sign with APK Signature Scheme v2
+    pub fn v2_signing_enabled(&mut self, v2_signing_enabled: bool) -> &mut Self {
+        self.v2_signing_enabled = Some(v2_signing_enabled);
+        self
+    }
+
+    /// Whether to sign with APK Signature Scheme v3
+    pub fn v3_signing_enabled(&mut self, v3_signing_enabled: bool) -> &mut Self {
+        self.v3_signing_enabled = Some(v3_signing_enabled);
+        self
+    }
+
+    /// Whether to sign with APK Signature Scheme v4. The signature is written to the
+    /// separate `.idsig` file
+    pub fn v4_signing_enabled(&mut self, v4_signing_enabled: bool) -> &mut Self {
+        self.v4_signing_enabled = Some(v4_signing_enabled);
+        self
+    }
+
+    /// The lowest Android API level that the APK is signed for. Defaults to
+    /// `minSdkVersion` of the manifest
+    pub fn min_sdk_version(&mut self, min_sdk_version: u32) -> &mut Self {
+        self.min_sdk_version = Some(min_sdk_version);
+        self
+    }
+
+    /// The highest Android API level that the APK is signed for
+    pub fn max_sdk_version(&mut self, max_sdk_version: u32) -> &mut Self {
+        self.max_sdk_version = Some(max_sdk_version);
+        self
+    }
+
+    /// The signing certificate lineage created by `apksigner rotate` to sign with the
+    /// rotated key
+    pub fn lineage(&mut self, lineage: &Path) -> &mut Self {
+        self.lineage = Some(lineage.to_owned());
+        self
+    }
+
+    /// The lowest API level the rotated signing key is used on
+    pub fn rotation_min_sdk_version(&mut self, rotation_min_sdk_version: u32) -> &mut Self {
+        self.rotation_min_sdk_version = Some(rotation_min_sdk_version);
+        self
+    }
+
+    /// Enables verbose output mode
+    pub fn verbose(&mut self, verbose: bool) -> &mut Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Executes apksigner sign and returns path to the signed APK
+    pub fn run(&self) -> Result<PathBuf> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        self.command()?.output_with(options)?;
+        Ok(self.signed_apk())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<PathBuf> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(self.signed_apk())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut apksigner = apksigner_tool()?;
+        apksigner.arg("sign");
+        for (index, signer) in self.signers.iter().enumerate() {
+            if index > 0 {
+                apksigner.arg("--next-signer");
+            }
+            signer.apply(&mut apksigner);
+        }
+        if let Some(out) = &self.out {
+            apksigner.arg("--out").arg(out);
+        }
+        let schemes = [
+            ("--v1-signing-enabled", self.v1_signing_enabled),
+            ("--v2-signing-enabled", self.v2_signing_enabled),
+            ("--v3-signing-enabled", self.v3_signing_enabled),
+            ("--v4-signing-enabled", self.v4_signing_enabled),
+        ];
+        for (flag, enabled) in schemes {
+            if let Some(enabled) = enabled {
+                apksigner.arg(flag).arg(enabled.to_string());
+            }
+        }
+        if let Some(min_sdk_version) = self.min_sdk_version {
+            apksigner
+                .arg("--min-sdk-version")
+                .arg(min_sdk_version.to_string());
+        }
+        if let Some(max_sdk_version) = self.max_sdk_version {
+            apksigner
+                .arg("--max-sdk-version")
+                .arg(max_sdk_version.to_string());
+        }
+        if let Some(lineage) = &self.lineage {
+            apksigner.arg("--lineage").arg(lineage);
+        }
+        if let Some(rotation_min_sdk_version) = self.rotation_min_sdk_version {
+            apksigner
+                .arg("--rotation-min-sdk-version")
+                .arg(rotation_min_sdk_version.to_string());
+        }
+        if self.verbose {
+            apksigner.arg("-v");
+        }
+        apksigner.arg(&self.apk);
+        Ok(apksigner)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+
+    fn signed_apk(&self) -> PathBuf {
+        self.out.clone().unwrap_or_else(|| self.apk.clone())
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Signer options of `apksigner`: the keystore and the key, or the private key and
+/// certificate files
+#[derive(Clone, Default, Debug)]
+pub struct ApksignerSigner {
+    ks: Option<PathBuf>,
+    ks_key_alias: Option<String>,
+    ks_pass: Option<String>,
+    key_pass: Option<String>,
+    ks_type: Option<String>,
+    key: Option<PathBuf>,
+    cert: Option<PathBuf>,
+}
+
+impl ApksignerSigner {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// The signer's private key and certificate chain reside in the given Java-based
+    /// KeyStore file
+    pub fn ks(&mut self, ks: &Path) -> &mut Self {
+        self.ks = Some(ks.to_owned());
+        self
+    }
+
+    /// The name of the alias that represents the signer's private key and certificate
+    /// data within the KeyStore. Required if the KeyStore contains multiple keys
+    pub fn ks_key_alias(&mut self, ks_key_alias: String) -> &mut Self {
+        self.ks_key_alias = Some(ks_key_alias);
+        self
+    }
+
+    /// Password of the KeyStore
+    pub fn ks_pass_pass(&mut self, ks_pass: String) -> &mut Self {
+        self.ks_pass = Some(format!("pass:{}", ks_pass));
+        self
+    }
+
+    /// File with the password of the KeyStore on the first line
+    pub fn ks_pass_file(&mut self, ks_pass: &Path) -> &mut Self {
+        self.ks_pass = Some(format!("file:{}", ks_pass.to_string_lossy()));
+        self
+    }
+
+    /// Password of the signer's private key. Defaults to the KeyStore password
+    pub fn key_pass_pass(&mut self, key_pass: String) -> &mut Self {
+        self.key_pass = Some(format!("pass:{}", key_pass));
+        self
+    }
+
+    /// File with the password of the signer's private key on the first line
+    pub fn key_pass_file(&mut self, key_pass: &Path) -> &mut Self {
+        self.key_pass = Some(format!("file:{}", key_pass.to_string_lossy()));
+        self
+    }
+
+    /// The type of the KeyStore, e.g. `PKCS12` or `JKS`
+    pub fn ks_type(&mut self, ks_type: String) -> &mut Self {
+        self.ks_type = Some(ks_type);
+        self
+    }
+
+    /// The signer's private key in PKCS #8 DER format. Used with `cert` instead of the
+    /// KeyStore
+    pub fn key(&mut self, key: &Path) -> &mut Self {
+        self.key = Some(key.to_owned());
+        self
+    }
+
+    /// The signer's certificate chain in X.509 format. Used with `key` instead of the
+    /// KeyStore
+    pub fn cert(&mut self, cert: &Path) -> &mut Self {
+        self.cert = Some(cert.to_owned());
+        self
+    }
+
+    /// Adds the signer options to the command
+    pub(crate) fn apply(&self, apksigner: &mut Command) {
+        if let Some(ks) = &self.ks {
+            apksigner.arg("--ks").arg(ks);
+        }
+        if let Some(ks_key_alias) = &self.ks_key_alias {
+            apksigner.arg("--ks-key-alias").arg(ks_key_alias);
+        }
+        if let Some(ks_pass) = &self.ks_pass {
+            apksigner.arg("--ks-pass").arg(ks_pass);
+        }
+        if let Some(key_pass) = &self.key_pass {
+            apksigner.arg("--key-pass").arg(key_pass);
+        }
+        if let Some(ks_type) = &self.ks_type {
+            apksigner.arg("--ks-type").arg(ks_type);
+        }
+        if let Some(key) = &self.key {
+            apksigner.arg("--key").arg(key);
+        }
+        if let Some(cert) = &self.cert {
+            apksigner.arg("--cert").arg(cert);
+        }
+    }
+}
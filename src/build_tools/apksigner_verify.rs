@@ -0,0 +1,220 @@
+use super::apksigner_tool;
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// # Verify
+/// Checks whether the APK's signatures will be verified successfully on all platforms
+/// the APK supports. The command fails with [`Error::CmdFailed`] if the APK doesn't
+/// verify.
+///
+/// ```no_run
+/// use android_tools::build_tools::Apksigner;
+/// use std::path::Path;
+///
+/// let verification = Apksigner.verify(Path::new("app.apk")).run().unwrap();
+/// for signer in verification.signers {
+///     println!("{}: {:?}", signer.dn, signer.sha256_digest);
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct ApksignerVerify {
+    apk: PathBuf,
+    print_certs: bool,
+    verbose: bool,
+    min_sdk_version: Option<u32>,
+    max_sdk_version: Option<u32>,
+}
+
+impl ApksignerVerify {
+    /// Initialize verify with APK. Certificates and verified schemes are printed by
+    /// default to parse them into [`ApksignerVerification`]
+    pub fn new(apk: &Path) -> Self {
+        Self {
+            apk: apk.to_owned(),
+            print_certs: true,
+            verbose: true,
+            ..Default::default()
+        }
+    }
+
+    /// Show information about the APK's signing certificates [default: true]
+    pub fn print_certs(&mut self, print_certs: bool) -> &mut Self {
+        self.print_certs = print_certs;
+        self
+    }
+
+    /// Show verified signature schemes, key algorithms and warnings [default: true]
+    pub fn verbose(&mut self, verbose: bool) -> &mut Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// The lowest Android API level to verify the APK for. Defaults to `minSdkVersion`
+    /// of the manifest
+    pub fn min_sdk_version(&mut self, min_sdk_version: u32) -> &mut Self {
+        self.min_sdk_version = Some(min_sdk_version);
+        self
+    }
+
+    /// The highest Android API level to verify the APK for
+    pub fn max_sdk_version(&mut self, max_sdk_version: u32) -> &mut Self {
+        self.max_sdk_version = Some(max_sdk_version);
+        self
+    }
+
+    /// Executes apksigner verify and parses its output
+    pub fn run(&self) -> Result<ApksignerVerification> {
+        self.run_with(ExecOptions::new().print_logs(false))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<ApksignerVerification> {
+        let output = self.command()?.output_with(options)?;
+        Ok(ApksignerVerification::parse_output(
+            &String::from_utf8_lossy(&output.stdout),
+        ))
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<ApksignerVerification> {
+        self.run_async_with(&ExecOptions::new()).await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<ApksignerVerification> {
+        let output = crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(ApksignerVerification::parse_output(
+            &String::from_utf8_lossy(&output.stdout),
+        ))
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut apksigner = apksigner_tool()?;
+        apksigner.arg("verify");
+        if self.print_certs {
+            apksigner.arg("--print-certs");
+        }
+        if self.verbose {
+            apksigner.arg("-v");
+        }
+        if let Some(min_sdk_version) = self.min_sdk_version {
+            apksigner
+                .arg("--min-sdk-version")
+                .arg(min_sdk_version.to_string());
+        }
+        if let Some(max_sdk_version) = self.max_sdk_version {
+            apksigner
+                .arg("--max-sdk-version")
+                .arg(max_sdk_version.to_string());
+        }
+        apksigner.arg(&self.apk);
+        Ok(apksigner)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+}
+
+/// Parsed output of `apksigner verify --print-certs -v`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApksignerVerification {
+    /// Schemes the APK is verified with, e.g. `v2` or `v3.1`
+    pub verified_schemes: Vec<String>,
+    pub signers: Vec<ApksignerCertificate>,
+    pub warnings: Vec<String>,
+}
+
+/// Signer certificate printed by `apksigner verify --print-certs` and `apksigner
+/// lineage --print-certs`. Digests are lowercase hex strings
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApksignerCertificate {
+    /// Number of the signer starting from 1
+    pub index: u32,
+    /// Distinguished name of the certificate, e.g. `CN=Android Debug, O=Android, C=US`
+    pub dn: String,
+    pub sha256_digest: Option<String>,
+    pub sha1_digest: Option<String>,
+    pub md5_digest: Option<String>,
+    pub key_algorithm: Option<String>,
+    pub key_size: Option<u32>,
+    pub public_key_sha256_digest: Option<String>,
+}
+
+impl ApksignerVerification {
+    /// Parses verified schemes, signer certificates and warnings, skipping other lines
+    pub fn parse_output(output: &str) -> ApksignerVerification {
+        let mut verification = ApksignerVerification {
+            signers: ApksignerCertificate::parse_output(output),
+            ..Default::default()
+        };
+        for line in output.lines().map(str::trim) {
+            if let Some(scheme) = line.strip_prefix("Verified using ") {
+                if let Some((scheme, "true")) = scheme.rsplit_once(": ") {
+                    if let Some((version, _)) = scheme.split_once(" scheme") {
+                        verification.verified_schemes.push(version.to_owned());
+                    }
+                }
+            } else if let Some(warning) = line.strip_prefix("WARNING: ") {
+                verification.warnings.push(warning.to_owned());
+            }
+        }
+        verification
+    }
+}
+
+impl ApksignerCertificate {
+    /// Parses `Signer #N ...` lines into the list of certificates ordered by number
+    pub fn parse_output(output: &str) -> Vec<ApksignerCertificate> {
+        let mut certificates: Vec<ApksignerCertificate> = Vec::new();
+        for line in output.lines().map(str::trim) {
+            let Some((index, field)) = line
+                .strip_prefix("Signer #")
+                .and_then(|line| line.split_once(' '))
+            else {
+                continue;
+            };
+            let Ok(index) = index.parse::<u32>() else {
+                continue;
+            };
+            let Some((name, value)) = field.split_once(": ") else {
+                continue;
+            };
+            let position = match certificates.iter().position(|c| c.index == index) {
+                Some(position) => position,
+                None => {
+                    certificates.push(ApksignerCertificate {
+                        index,
+                        ..Default::default()
+                    });
+                    certificates.len() - 1
+                }
+            };
+            let certificate = &mut certificates[position];
+            let value = value.trim().to_owned();
+            match name {
+                "certificate DN" => certificate.dn = value,
+                "certificate SHA-256 digest" => certificate.sha256_digest = Some(value),
+                "certificate SHA-1 digest" => certificate.sha1_digest = Some(value),
+                "certificate MD5 digest" => certificate.md5_digest = Some(value),
+                "key algorithm" => certificate.key_algorithm = Some(value),
+                "key size (bits)" => certificate.key_size = value.parse().ok(),
+                "public key SHA-256 digest" => certificate.public_key_sha256_digest = Some(value),
+                _ => (),
+            }
+        }
+        certificates.sort_by_key(|certificate| certificate.index);
+        certificates
+    }
+}
@@ -0,0 +1,68 @@
+mod apksigner_lineage;
+mod apksigner_rotate;
+mod apksigner_sign;
+mod apksigner_signer;
+mod apksigner_verify;
+mod zipalign;
+
+pub use apksigner_lineage::*;
+pub use apksigner_rotate::*;
+pub use apksigner_sign::*;
+pub use apksigner_signer::*;
+pub use apksigner_verify::*;
+pub use zipalign::*;
+
+use crate::{error::*, executor::current_executor, sdk::build_tools_executable};
+use std::{path::Path, process::Command};
+
+/// `apksigner` is a script on Windows
+#[cfg(target_os = "windows")]
+const APKSIGNER: &str = "apksigner.bat";
+#[cfg(not(target_os = "windows"))]
+const APKSIGNER: &str = "apksigner";
+
+/// Signs APKs and verifies that their signatures will be verified successfully on all
+/// versions of the Android platform supported by the APK
+#[derive(Clone, Copy)]
+pub struct Apksigner;
+
+impl Apksigner {
+    /// Signs APK with the signer
+    pub fn sign(self, apk: &Path, signer: &ApksignerSigner) -> ApksignerSign {
+        ApksignerSign::new(apk, signer)
+    }
+
+    /// Checks whether APK signatures will be verified on all supported platforms
+    pub fn verify(self, apk: &Path) -> ApksignerVerify {
+        ApksignerVerify::new(apk)
+    }
+
+    /// Adds the new signer to the signing certificate lineage
+    pub fn rotate(
+        self,
+        out: &Path,
+        old_signer: &ApksignerSigner,
+        new_signer: &ApksignerSigner,
+    ) -> ApksignerRotate {
+        ApksignerRotate::new(out, old_signer, new_signer)
+    }
+
+    /// Prints or modifies the signing certificate lineage
+    pub fn lineage(self, input: &Path) -> ApksignerLineage {
+        ApksignerLineage::new(input)
+    }
+}
+
+/// Find `apksigner` in build-tools of Android SDK and initialize it
+pub fn apksigner_tool() -> Result<Command> {
+    current_executor().locate("apksigner", &|| {
+        Ok(Command::new(build_tools_executable(APKSIGNER)?))
+    })
+}
+
+/// Find `zipalign` in build-tools of Android SDK and initialize it
+pub fn zipalign_tool() -> Result<Command> {
+    current_executor().locate("zipalign", &|| {
+        Ok(Command::new(build_tools_executable(bin!("zipalign"))?))
+    })
+}
@@ -0,0 +1,213 @@
+use super::zipalign_tool;
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// # Zipalign
+/// Ensures that all uncompressed files in the archive are aligned relative to the start
+/// of the file, so they can be accessed directly via `mmap`. Align APK before signing it
+/// with APK Signature Scheme v2 or above, the signatures are broken by aligning.
+///
+/// ```no_run
+/// use android_tools::build_tools::Zipalign;
+/// use std::path::Path;
+///
+/// Zipalign::new(4, Path::new("app-unaligned.apk"), Path::new("app.apk"))
+///     .p(true)
+///     .run()
+///     .unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct Zipalign {
+    alignment: u32,
+    input: PathBuf,
+    output: PathBuf,
+    p: bool,
+    f: bool,
+    v: bool,
+    z: bool,
+}
+
+impl Zipalign {
+    /// Initialize zipalign with alignment in bytes, the input and the output archives.
+    /// APKs are aligned by 4 bytes
+    pub fn new(alignment: u32, input: &Path, output: &Path) -> Self {
+        Self {
+            alignment,
+            input: input.to_owned(),
+            output: output.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Checks alignment of the archive instead of aligning it
+    pub fn check(alignment: u32, input: &Path) -> ZipalignCheck {
+        ZipalignCheck::new(alignment, input)
+    }
+
+    /// Page-align uncompressed `.so` files
+    pub fn p(&mut self, p: bool) -> &mut Self {
+        self.p = p;
+        self
+    }
+
+    /// Overwrite existing output file
+    pub fn f(&mut self, f: bool) -> &mut Self {
+        self.f = f;
+        self
+    }
+
+    /// Verbose output
+    pub fn v(&mut self, v: bool) -> &mut Self {
+        self.v = v;
+        self
+    }
+
+    /// Recompress using Zopfli
+    pub fn z(&mut self, z: bool) -> &mut Self {
+        self.z = z;
+        self
+    }
+
+    /// Executes zipalign and returns path to the aligned archive
+    pub fn run(&self) -> Result<PathBuf> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        self.command()?.output_with(options)?;
+        Ok(self.output.clone())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<PathBuf> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(self.output.clone())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut zipalign = zipalign_tool()?;
+        if self.p {
+            zipalign.arg("-p");
+        }
+        if self.f {
+            zipalign.arg("-f");
+        }
+        if self.v {
+            zipalign.arg("-v");
+        }
+        if self.z {
+            zipalign.arg("-z");
+        }
+        zipalign.arg(self.alignment.to_string());
+        zipalign.arg(&self.input);
+        zipalign.arg(&self.output);
+        Ok(zipalign)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+}
+
+/// Checks alignment of the archive with `zipalign -c -v`. The check always runs
+/// verbose, as zipalign reports misaligned entries only then
+#[derive(Clone, Default)]
+pub struct ZipalignCheck {
+    alignment: u32,
+    input: PathBuf,
+    p: bool,
+}
+
+impl ZipalignCheck {
+    /// Initialize zipalign check with alignment in bytes and the archive
+    pub fn new(alignment: u32, input: &Path) -> Self {
+        Self {
+            alignment,
+            input: input.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Check page alignment of uncompressed `.so` files
+    pub fn p(&mut self, p: bool) -> &mut Self {
+        self.p = p;
+        self
+    }
+
+    /// Executes zipalign check and returns whether the archive is aligned
+    pub fn run(&self) -> Result<bool> {
+        self.run_with(&ExecOptions::new())
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<bool> {
+        Self::is_aligned(self.command()?.output_with(options))
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<bool> {
+        self.run_async_with(&ExecOptions::new()).await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<bool> {
+        Self::is_aligned(crate::async_command::output_err_async(self.command()?, options).await)
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        let mut zipalign = zipalign_tool()?;
+        zipalign.arg("-c");
+        if self.p {
+            zipalign.arg("-p");
+        }
+        zipalign.arg("-v");
+        zipalign.arg(self.alignment.to_string());
+        zipalign.arg(&self.input);
+        Ok(zipalign)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+
+    /// Zipalign exits with failure when the check fails, which isn't an error here.
+    /// Errors like a missing archive are printed to stderr
+    fn is_aligned(output: Result<std::process::Output>) -> Result<bool> {
+        match output {
+            Ok(_) => Ok(true),
+            Err(Error::CmdFailed(failure))
+                if failure.stdout.contains("Verification FAILED")
+                    || (failure.exit_code == Some(1) && failure.stderr.trim().is_empty()) =>
+            {
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
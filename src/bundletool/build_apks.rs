@@ -1,6 +1,7 @@
 use super::bundletool;
-use crate::error::*;
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// ## Generate a set of APKs from your app bundle
 ///
@@ -204,6 +205,35 @@ impl BuildApks {
 
     /// Runs `build_apks` commands to build apks
     pub fn run(&self) -> Result<PathBuf> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        self.command()?.output_with(options)?;
+        Ok(self.output.clone())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<PathBuf> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(self.output.clone())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
         let mut build_apks = bundletool()?;
         build_apks.arg("build-apks");
         build_apks.arg("--bundle").arg(&self.bundle);
@@ -259,7 +289,11 @@ impl BuildApks {
         if self.local_testing {
             build_apks.arg("--local-testing");
         }
-        build_apks.output_err(true)?;
-        Ok(self.output.clone())
+        Ok(build_apks)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
@@ -1,6 +1,7 @@
 use super::bundletool;
-use crate::error::*;
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// ## Build your app bundle using bundletool
 /// To build your app bundle, you use the `bundletool build-bundle` command, as shown
@@ -60,6 +61,35 @@ impl BuildBundle {
 
     /// Runs `bundletool` commands to build AAB
     pub fn run(&self) -> Result<()> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
         let mut build_bundle = bundletool()?;
         build_bundle.arg("build-bundle");
         build_bundle.arg("--modules");
@@ -77,7 +107,11 @@ impl BuildBundle {
         if let Some(metadata_file) = &self.metadata_file {
             build_bundle.arg("--metadata-file").arg(metadata_file);
         }
-        build_bundle.output_err(true)?;
-        Ok(())
+        Ok(build_bundle)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
@@ -1,6 +1,7 @@
 use super::bundletool;
-use crate::error::*;
+use crate::{command_line::CommandLine, error::*, executor::ExecOptions};
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// ## Extract device-specific APKs from an existing APK set
 ///
@@ -33,6 +34,35 @@ impl ExtractApks {
 
     /// Runs `bundletool` commands to extract apks on your device or emulator
     pub fn run(&self) -> Result<()> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<()> {
+        self.command()?.output_with(options)?;
+        Ok(())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<()> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<()> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
         let mut extract_apks = bundletool()?;
         extract_apks.arg("--apks");
         extract_apks.arg(&self.apks);
@@ -40,7 +70,11 @@ impl ExtractApks {
         extract_apks.arg(&self.device_spec);
         extract_apks.arg("--output-dir");
         extract_apks.arg(&self.output_dir);
-        extract_apks.output_err(true)?;
-        Ok(())
+        Ok(extract_apks)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
@@ -12,7 +12,7 @@ pub use get_device_spec::*;
 pub use get_size_total::*;
 pub use install_apks::*;
 
-use crate::{bundletool, error::*};
+use crate::{bundletool, error::*, executor::current_executor};
 use std::{
     path::{Path, PathBuf},
     process::Command,
@@ -84,22 +84,24 @@ impl Bundletool {
 
 /// Find `bundletool` jar file in home directory then set environment variable and initialize it
 pub fn bundletool() -> Result<Command> {
-    let mut bundletool_init = Command::new("java");
-    bundletool_init.arg("-jar");
-    if let Ok(bundletool_path) = std::env::var("BUNDLETOOL_PATH") {
-        bundletool_init.arg(bundletool_path);
-    } else {
-        let env_version =
-            std::env::var("BUNDLETOOL_VERSION").unwrap_or_else(|_| BUNDLETOOL_VERSION.to_string());
-        let bundletool_file = format!("bundletool-all-{}.jar", env_version);
-        let bundletool_file_path = dirs::home_dir()
-            .ok_or(Error::UnableToAccessHomeDirectory)?
-            .join(bundletool_file);
-        if bundletool_file_path.exists() {
-            bundletool_init.arg(bundletool_file_path);
+    current_executor().locate("bundletool", &|| {
+        let mut bundletool_init = Command::new("java");
+        bundletool_init.arg("-jar");
+        if let Ok(bundletool_path) = std::env::var("BUNDLETOOL_PATH") {
+            bundletool_init.arg(bundletool_path);
         } else {
-            return Err(Error::BundletoolNotFound);
+            let env_version = std::env::var("BUNDLETOOL_VERSION")
+                .unwrap_or_else(|_| BUNDLETOOL_VERSION.to_string());
+            let bundletool_file = format!("bundletool-all-{}.jar", env_version);
+            let bundletool_file_path = dirs::home_dir()
+                .ok_or(Error::UnableToAccessHomeDirectory)?
+                .join(bundletool_file);
+            if bundletool_file_path.exists() {
+                bundletool_init.arg(bundletool_file_path);
+            } else {
+                return Err(Error::BundletoolNotFound);
+            }
         }
-    }
-    Ok(bundletool_init)
+        Ok(bundletool_init)
+    })
 }
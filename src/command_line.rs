@@ -0,0 +1,80 @@
+//! Contains `CommandLine` type that describes a tool command without running it.
+
+use crate::error::redact_args;
+use std::{ffi::OsString, path::PathBuf, process::Command};
+
+/// Program and arguments of the command a builder executes. Returned by
+/// `command_line()` methods of the builders, e.g. to log the command or to write it
+/// into a shell script.
+///
+/// ```
+/// use android_tools::command_line::CommandLine;
+/// use std::process::Command;
+///
+/// let mut jarsigner = Command::new("jarsigner");
+/// jarsigner.args(["-storepass", "android", "my app.aab", "key0"]);
+/// let command_line = CommandLine::new(&jarsigner);
+/// assert_eq!(
+///     command_line.to_string(),
+///     "jarsigner -storepass '***' 'my app.aab' key0"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandLine {
+    /// Resolved path to the program, e.g. `$ANDROID_SDK_ROOT/build-tools/31.0.0/aapt2`
+    pub program: PathBuf,
+    pub args: Vec<OsString>,
+}
+
+impl CommandLine {
+    /// Reads program and arguments of the command
+    pub fn new(command: &Command) -> Self {
+        Self {
+            program: PathBuf::from(command.get_program()),
+            args: command.get_args().map(ToOwned::to_owned).collect(),
+        }
+    }
+
+    /// Returns arguments with passwords, like `-storepass` or `--ks-pass pass:`
+    /// values, replaced by `***`
+    pub fn redacted_args(&self) -> Vec<String> {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        redact_args(&args)
+    }
+
+    /// Renders the command for a POSIX shell. Arguments are quoted if needed and
+    /// passwords are masked
+    pub fn to_shell_string(&self) -> String {
+        std::iter::once(self.program.to_string_lossy().to_string())
+            .chain(self.redacted_args())
+            .map(|arg| shell_escape(&arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl std::fmt::Display for CommandLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_shell_string())
+    }
+}
+
+impl From<&Command> for CommandLine {
+    fn from(command: &Command) -> Self {
+        Self::new(command)
+    }
+}
+
+/// Wraps the argument into single quotes if it contains characters special for the
+/// shell
+fn shell_escape(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        return arg.to_owned();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
@@ -1,7 +1,7 @@
 mod emulator_enum;
 mod emulator_tools;
 
-use crate::{error::*, sdk_path_from_env};
+use crate::{error::*, executor::current_executor, sdk::AndroidSdk};
 use std::process::Command;
 
 pub use emulator_enum::*;
@@ -16,13 +16,17 @@ impl Emulator {
     }
 }
 
-/// Find `emulator` executable binary file in SDK and initialize it
+/// Find `emulator` executable binary file and initialize it. The `emulator` from `PATH`
+/// is used with `which` feature, then the one from the Android SDK
 pub fn emulator_tool() -> Result<Command> {
-    if let Ok(emulator_tools) = which::which(bin!("emulator")) {
-        return Ok(Command::new(emulator_tools));
-    }
-    let sdk_path = sdk_path_from_env()?;
-    let build_tools = sdk_path.join("emulator");
-    let emulator = build_tools.join(bin!("emulator"));
-    Ok(Command::new(emulator))
+    current_executor().locate("emulator", &|| {
+        #[cfg(feature = "which")]
+        if let Ok(emulator_tools) = which::which(bin!("emulator")) {
+            return Ok(Command::new(emulator_tools));
+        }
+        let emulator = AndroidSdk::from_env()?
+            .emulator_dir()
+            .ok_or(Error::CmdNotFound("emulator".to_owned()))?;
+        Ok(Command::new(emulator.join(bin!("emulator"))))
+    })
 }
@@ -1,7 +1,12 @@
 //! Contains `Error` type and `CommandExt` impl used by `android-tools-rs`.
 
+use crate::executor::{current_executor, ExecOptions};
 use displaydoc::Display;
-use std::{path::PathBuf, process::Command};
+use std::{
+    path::PathBuf,
+    process::{Command, ExitStatus},
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -20,37 +25,319 @@ pub enum Error {
     PathNotFound(PathBuf),
     /// Command {0} is not found
     CmdNotFound(String),
-    /// Command had a non-zero exit code. Stdout: {0} Stderr: {1}
-    CmdFailed(String, String),
+    /// {0}
+    CmdFailed(Box<CommandFailure>),
+    /// Command `{0}` timed out after {1:?}
+    CmdTimeout(String, Duration),
+    /// Adb server responded with failure: {0}
+    AdbServerFailed(String),
+    /// Unexpected response from adb server: {0}
+    AdbProtocol(String),
+    /// Unknown adb device state {0:?}
+    UnknownDeviceState(String),
+    /// Invalid arguments: {0}
+    InvalidArguments(String),
+    /// Failed to parse tool output: {0}
+    OutputParseFailed(String),
+    /// Unexpected command `{0}`, expected `{1}`
+    UnexpectedCommand(String, String),
+    /// Invalid APK: {0}
+    InvalidApk(String),
+    /// Invalid signing key: {0}
+    InvalidSigningKey(String),
+    /// APK signature verification failed: {0}
+    SignatureVerificationFailed(String),
+    /// Invalid binary resource: {0}
+    InvalidBinaryResource(String),
+    /// Invalid AndroidManifest.xml: {0}
+    InvalidManifest(String),
+    /// Manifest merging failed:\n{0}
+    ManifestMergeFailed(String),
+    /// Failed to compile resources:{0}
+    CompileFailed(CompileFailures),
     /// Compiled resources is not found
     CompiledResourcesNotFound,
     /// IO error
     Io(#[from] std::io::Error),
 }
 
+/// Resource files that failed to compile with their errors
+#[derive(Debug)]
+pub struct CompileFailures(pub Vec<(PathBuf, Error)>);
+
+impl std::fmt::Display for CompileFailures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (path, error) in &self.0 {
+            write!(f, "\n{}: {}", path.display(), error)?;
+        }
+        Ok(())
+    }
+}
+
 /// Extension trait for [`Command`] that helps
 /// to wrap output and print logs from command execution.
 ///
 /// [`Command`](std::process::Command)
 pub trait CommandExt {
-    /// Executes the command as a child process, then captures an output and return it.
-    /// If command termination wasn't successful wraps an output into error and return it
+    /// Executes the command with the current [`ToolExecutor`], then captures an output
+    /// and return it. If command termination wasn't successful wraps an output into
+    /// error and return it
+    ///
+    /// [`ToolExecutor`](crate::executor::ToolExecutor)
     fn output_err(self, print_logs: bool) -> Result<std::process::Output>;
+
+    /// Executes the command with given options like [`output_err`](Self::output_err).
+    /// Returns [`Error::CmdTimeout`] if the command didn't finish in time
+    fn output_with(self, options: &ExecOptions) -> Result<std::process::Output>;
 }
 
 impl CommandExt for Command {
-    fn output_err(mut self, print_logs: bool) -> Result<std::process::Output> {
-        // Enables log print during command execution
-        let output = match print_logs {
-            true => self.spawn().and_then(|p| p.wait_with_output())?,
-            false => self.output()?,
-        };
+    fn output_err(self, print_logs: bool) -> Result<std::process::Output> {
+        self.output_with(ExecOptions::new().print_logs(print_logs))
+    }
+
+    fn output_with(mut self, options: &ExecOptions) -> Result<std::process::Output> {
+        let started = Instant::now();
+        let output = current_executor().execute(&mut self, options)?;
         if !output.status.success() {
-            return Err(Error::CmdFailed(
+            return Err(CommandFailure::new(
+                &self,
+                output.status,
                 String::from_utf8_lossy(&output.stdout).to_string(),
                 String::from_utf8_lossy(&output.stderr).to_string(),
-            ));
+                started.elapsed(),
+            )
+            .into());
         }
         Ok(output)
     }
 }
+
+/// Failed execution of a tool, like aapt2, bundletool, jarsigner or adb
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandFailure {
+    /// Name of the tool, e.g. `aapt2`. For `java -jar` it's the name of the jar, e.g.
+    /// `bundletool`
+    pub tool: String,
+    /// Program and its arguments with passwords replaced by `***`
+    pub args: Vec<String>,
+    /// Exit code of the process, if it exited normally
+    pub exit_code: Option<i32>,
+    /// Signal the process was terminated with, on Unix
+    pub signal: Option<i32>,
+    pub duration: Duration,
+    pub stdout: String,
+    pub stderr: String,
+    /// Errors and warnings found in the output of the tool
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Error or warning reported by a tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// File the diagnostic refers to, e.g. `res/values/strings.xml`
+    pub file: Option<PathBuf>,
+    pub line: Option<u32>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl CommandFailure {
+    /// Collects information about the failed command and parses diagnostics from its
+    /// output
+    pub fn new(
+        command: &Command,
+        status: ExitStatus,
+        stdout: String,
+        stderr: String,
+        duration: Duration,
+    ) -> Self {
+        let args = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        let tool = tool_name(&args);
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+        let mut diagnostics = parse_diagnostics(&tool, &stderr);
+        diagnostics.extend(parse_diagnostics(&tool, &stdout));
+        Self {
+            tool,
+            args: redact_args(&args),
+            exit_code: status.code(),
+            signal,
+            duration,
+            stdout,
+            stderr,
+            diagnostics,
+        }
+    }
+}
+
+impl std::fmt::Display for CommandFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", self.tool)?;
+        match (self.exit_code, self.signal) {
+            (Some(code), _) => write!(f, "exited with code {}", code)?,
+            (None, Some(signal)) => write!(f, "was terminated by signal {}", signal)?,
+            (None, None) => write!(f, "failed")?,
+        }
+        write!(
+            f,
+            " after {:.2?}: `{}`. Stdout: {} Stderr: {}",
+            self.duration,
+            self.args.join(" "),
+            self.stdout,
+            self.stderr
+        )
+    }
+}
+
+impl From<CommandFailure> for Error {
+    fn from(failure: CommandFailure) -> Self {
+        Error::CmdFailed(Box::new(failure))
+    }
+}
+
+/// Command line flags followed by a password
+const SECRET_FLAGS: [&str; 9] = [
+    "-storepass",
+    "-keypass",
+    "-srcstorepass",
+    "-deststorepass",
+    "-srckeypass",
+    "-destkeypass",
+    "-new",
+    "--ks-pass",
+    "--key-pass",
+];
+
+/// Replaces passwords in the command line arguments with `***`. Values of
+/// `--ks-pass` and `--key-pass` that refer to a file keep the path
+pub fn redact_args<S: AsRef<str>>(args: &[S]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut secret_next = false;
+    for arg in args {
+        let arg = arg.as_ref();
+        let flag_value = arg
+            .split_once('=')
+            .filter(|(flag, _)| SECRET_FLAGS.contains(flag));
+        redacted.push(if let Some((flag, value)) = flag_value {
+            format!("{}={}", flag, redact_password(value))
+        } else if secret_next || arg.starts_with("pass:") {
+            redact_password(arg)
+        } else {
+            arg.to_owned()
+        });
+        secret_next = SECRET_FLAGS.contains(&arg);
+    }
+    redacted
+}
+
+fn redact_password(value: &str) -> String {
+    if value.starts_with("file:") {
+        value.to_owned()
+    } else if value.starts_with("pass:") {
+        "pass:***".to_owned()
+    } else {
+        "***".to_owned()
+    }
+}
+
+/// Returns name of the executable without extension, or name of the jar started with
+/// `java -jar`
+pub(crate) fn tool_name(args: &[String]) -> String {
+    let stem = |path: &str| {
+        std::path::Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let program = args
+        .first()
+        .map(|program| stem(program))
+        .unwrap_or_default();
+    if program == "java" {
+        if let Some(jar) = args.windows(2).find(|pair| pair[0] == "-jar") {
+            let jar = stem(&jar[1]);
+            // bundletool-all-1.8.2.jar
+            return match jar.strip_prefix("bundletool") {
+                Some(_) => "bundletool".to_owned(),
+                None => jar,
+            };
+        }
+    }
+    program
+}
+
+/// Parses errors and warnings printed by the tool. aapt2 diagnostics are parsed with
+/// the location, e.g. `res/values/strings.xml:3: error: message`. For other tools lines
+/// with `error:` or `warning:`, like `keytool error: message`, are reported
+pub fn parse_diagnostics(tool: &str, output: &str) -> Vec<Diagnostic> {
+    output
+        .lines()
+        .filter_map(|line| match tool {
+            "aapt2" => parse_located_diagnostic(line.trim()),
+            _ => parse_diagnostic(line.trim()),
+        })
+        .collect()
+}
+
+const SEVERITIES: [(&str, Severity); 4] = [
+    ("error", Severity::Error),
+    ("warning", Severity::Warning),
+    ("warn", Severity::Warning),
+    ("note", Severity::Note),
+];
+
+/// Parses `file:line: severity: message`, `file: severity: message` and
+/// `severity: message`
+fn parse_located_diagnostic(line: &str) -> Option<Diagnostic> {
+    for (name, severity) in SEVERITIES {
+        let prefix = format!("{}: ", name);
+        let (location, message) = if let Some(message) = line.strip_prefix(&prefix) {
+            ("", message)
+        } else if let Some(pos) = line.find(&format!(": {}", prefix)) {
+            (&line[..pos], &line[pos + prefix.len() + 2..])
+        } else {
+            continue;
+        };
+        let (file, line) = match location.rsplit_once(':') {
+            Some((file, line)) if line.parse::<u32>().is_ok() => (file, line.parse().ok()),
+            _ => (location, None),
+        };
+        return Some(Diagnostic {
+            file: Some(file)
+                .filter(|file| !file.is_empty())
+                .map(PathBuf::from),
+            line,
+            severity,
+            message: message.trim().to_owned(),
+        });
+    }
+    None
+}
+
+/// Parses lines like `keytool error: message` or `[BT:1.8.2] Error: message`
+fn parse_diagnostic(line: &str) -> Option<Diagnostic> {
+    let lowercase = line.to_ascii_lowercase();
+    SEVERITIES.iter().find_map(|(name, severity)| {
+        let pos = lowercase.find(&format!("{}: ", name))?;
+        let message = line[pos + name.len() + 1..].trim();
+        Some(Diagnostic {
+            file: None,
+            line: None,
+            severity: *severity,
+            message: message.to_owned(),
+        })
+    })
+}
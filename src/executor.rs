@@ -0,0 +1,670 @@
+//! Contains `ToolExecutor` trait used to run tool commands and its implementations.
+//!
+//! Tool commands built by `run()` methods are executed by the current executor of the
+//! thread, which runs a real process by default. Use [`with_executor`] to replace it, for
+//! example with [`ReplayExecutor`] to check arguments of the commands without Android SDK
+//! installed. Commands which output is read while they run, like `logcat`, are started
+//! by [`ToolExecutor::spawn`]. Only `aapt2 daemon`, which is written to as well, is
+//! always spawned directly. `run_async()` methods use `ToolExecutor::execute_async` of
+//! the executor that is current on the thread polling them.
+//!
+//! ```
+//! use android_tools::{aapt2::{Aapt2, SubCommand}, executor::{with_executor, ReplayExecutor}};
+//! use std::{path::Path, sync::Arc};
+//!
+//! let mut replay = ReplayExecutor::new();
+//! replay
+//!     .expect("aapt2", &["dump", "packagename", "app.apk"])
+//!     .stdout("com.example\n");
+//! let replay = Arc::new(replay);
+//! let package = with_executor(replay.clone(), || {
+//!     Aapt2.dump(SubCommand::Packagename, Path::new("app.apk")).packagename()
+//! });
+//! assert_eq!(package.unwrap(), "com.example");
+//! assert!(replay.remaining().is_empty());
+//! ```
+
+use crate::{command_line::CommandLine, error::*};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    io::{Read, Write},
+    process::{Child, ChildStdout, Command, ExitStatus, Output, Stdio},
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+/// Locates and executes tool commands
+pub trait ToolExecutor: Send + Sync {
+    /// Returns command of the tool, e.g. `aapt2`. `locate` finds the tool in `PATH` or
+    /// Android SDK
+    fn locate(&self, _tool: &str, locate: &dyn Fn() -> Result<Command>) -> Result<Command> {
+        locate()
+    }
+
+    /// Executes the command with given options and returns its output
+    fn execute(&self, command: &mut Command, options: &ExecOptions) -> Result<Output>;
+
+    /// Executes the command asynchronously. Calls [`execute`](Self::execute) by default,
+    /// which blocks the task until the command finishes
+    #[cfg(feature = "async")]
+    fn execute_async<'a>(
+        &'a self,
+        command: &'a mut Command,
+        options: &'a ExecOptions,
+    ) -> BoxFuture<'a, Result<Output>> {
+        Box::pin(async move { self.execute(command, options) })
+    }
+
+    /// Starts the command which stdout is read while it runs. Calls
+    /// [`execute`](Self::execute) by default and returns the process with its output
+    /// once the command finishes
+    fn spawn(&self, command: &mut Command) -> Result<Box<dyn ToolProcess>> {
+        let output = self.execute(command, &ExecOptions::new())?;
+        Ok(Box::new(FinishedProcess::new(output)))
+    }
+}
+
+/// Command started by [`ToolExecutor::spawn`]. Reading it reads stdout of the command
+pub trait ToolProcess: Read + Send {
+    /// Waits for the command to exit. Returns its exit status and stderr, stdout is
+    /// left to the reader
+    fn wait(&mut self) -> Result<Output>;
+
+    /// Kills the command if it's still running
+    fn kill(&mut self) -> Result<()>;
+}
+
+/// Future returned by [`ToolExecutor::execute_async`]
+#[cfg(feature = "async")]
+pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>;
+
+/// Stream of the command output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Callback that receives every line of the command output without the line ending
+pub type LineCallback = Arc<dyn Fn(OutputStream, &str) + Send + Sync>;
+
+/// Options of the command execution. Passed to `run_with()` methods of the builders
+/// or to [`CommandExt::output_with`].
+///
+/// ```no_run
+/// use android_tools::{adb::AdbPull, executor::ExecOptions};
+/// use std::{path::Path, time::Duration};
+///
+/// let stats = AdbPull::new(Path::new("/sdcard/report.txt"), Path::new("."))
+///     .run_with(ExecOptions::new().timeout(Duration::from_secs(30)));
+/// ```
+///
+/// ```no_run
+/// use android_tools::{error::CommandExt, executor::ExecOptions};
+/// use std::{process::Command, time::Duration};
+///
+/// let mut adb = Command::new("adb");
+/// adb.args(["install", "app.apk"]);
+/// let output = adb.output_with(
+///     ExecOptions::new()
+///         .timeout(Duration::from_secs(60))
+///         .on_line(|stream, line| println!("{:?}: {}", stream, line)),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct ExecOptions {
+    pub(crate) print_logs: bool,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) kill_on_timeout: bool,
+    on_line: Option<LineCallback>,
+}
+
+impl Default for ExecOptions {
+    fn default() -> Self {
+        Self {
+            print_logs: false,
+            timeout: None,
+            kill_on_timeout: true,
+            on_line: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for ExecOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecOptions")
+            .field("print_logs", &self.print_logs)
+            .field("timeout", &self.timeout)
+            .field("kill_on_timeout", &self.kill_on_timeout)
+            .field("on_line", &self.on_line.is_some())
+            .finish()
+    }
+}
+
+impl ExecOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prints output of the command while it runs. The output is still captured
+    pub fn print_logs(&mut self, print_logs: bool) -> &mut Self {
+        self.print_logs = print_logs;
+        self
+    }
+
+    /// Stops waiting for the command after given time and returns
+    /// [`Error::CmdTimeout`]
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Kills the command when the timeout elapses. Enabled by default, otherwise the
+    /// command keeps running in the background
+    pub fn kill_on_timeout(&mut self, kill_on_timeout: bool) -> &mut Self {
+        self.kill_on_timeout = kill_on_timeout;
+        self
+    }
+
+    /// Calls given function for every line printed by the command while it runs. The
+    /// output is still captured
+    pub fn on_line(
+        &mut self,
+        on_line: impl Fn(OutputStream, &str) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_line = Some(Arc::new(on_line));
+        self
+    }
+
+    /// Passes every line of the output to the line callback
+    pub(crate) fn emit(&self, stream: OutputStream, output: &[u8]) {
+        if let Some(on_line) = &self.on_line {
+            for line in String::from_utf8_lossy(output).lines() {
+                on_line(stream, line);
+            }
+        }
+    }
+}
+
+/// Splits output read in chunks of arbitrary size into lines for the line callback
+pub(crate) struct LineSplitter<'a> {
+    options: &'a ExecOptions,
+    stream: OutputStream,
+    partial: Vec<u8>,
+}
+
+impl<'a> LineSplitter<'a> {
+    pub(crate) fn new(options: &'a ExecOptions, stream: OutputStream) -> Self {
+        Self {
+            options,
+            stream,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Emits complete lines of the chunk and keeps the unfinished one
+    pub(crate) fn push(&mut self, chunk: &[u8]) {
+        if self.options.on_line.is_none() {
+            return;
+        }
+        self.partial.extend_from_slice(chunk);
+        if let Some(end) = self.partial.iter().rposition(|&byte| byte == b'\n') {
+            self.options.emit(self.stream, &self.partial[..=end]);
+            self.partial.drain(..=end);
+        }
+    }
+
+    /// Emits the last line that has no line ending
+    pub(crate) fn finish(self) {
+        self.options.emit(self.stream, &self.partial);
+    }
+}
+
+/// Executor that runs commands as child processes. Used by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessExecutor;
+
+impl ToolExecutor for ProcessExecutor {
+    fn execute(&self, command: &mut Command, options: &ExecOptions) -> Result<Output> {
+        // Output is always captured to report diagnostics of failed commands, and
+        // printed by the readers if needed
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        let started = Instant::now();
+        let mut child = command.spawn()?;
+        let stdout = child
+            .stdout
+            .take()
+            .map(|stdout| read_lines(stdout, OutputStream::Stdout, options));
+        let stderr = child
+            .stderr
+            .take()
+            .map(|stderr| read_lines(stderr, OutputStream::Stderr, options));
+        let status = match options.timeout {
+            Some(timeout) => loop {
+                if let Some(status) = child.try_wait()? {
+                    break status;
+                }
+                if started.elapsed() >= timeout {
+                    if options.kill_on_timeout {
+                        child.kill()?;
+                        child.wait()?;
+                    }
+                    // Readers are left to finish when the pipes are closed, as children
+                    // of the command may still hold them
+                    let command_line = CommandLine::new(command).to_string();
+                    return Err(Error::CmdTimeout(command_line, timeout));
+                }
+                std::thread::sleep(Duration::from_millis(10).min(timeout));
+            },
+            None => child.wait()?,
+        };
+        let join = |reader: Option<JoinHandle<std::io::Result<Vec<u8>>>>| match reader {
+            Some(reader) => reader.join().expect("output reader panicked"),
+            None => Ok(Vec::new()),
+        };
+        Ok(Output {
+            status,
+            stdout: join(stdout)?,
+            stderr: join(stderr)?,
+        })
+    }
+
+    #[cfg(feature = "async")]
+    fn execute_async<'a>(
+        &'a self,
+        command: &'a mut Command,
+        options: &'a ExecOptions,
+    ) -> BoxFuture<'a, Result<Output>> {
+        Box::pin(crate::async_command::execute_process(command, options))
+    }
+
+    fn spawn(&self, command: &mut Command) -> Result<Box<dyn ToolProcess>> {
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        // Stderr is drained while stdout is read, so the command doesn't block on a
+        // full pipe
+        let mut child_stderr = child.stderr.take().expect("stderr is piped");
+        let stderr = std::thread::spawn(move || {
+            let mut stderr = Vec::new();
+            child_stderr.read_to_end(&mut stderr).map(|_| stderr)
+        });
+        Ok(Box::new(ChildProcess {
+            child,
+            stdout,
+            stderr: Some(stderr),
+        }))
+    }
+}
+
+/// Running child process returned by [`ProcessExecutor::spawn`]
+struct ChildProcess {
+    child: Child,
+    stdout: ChildStdout,
+    stderr: Option<JoinHandle<std::io::Result<Vec<u8>>>>,
+}
+
+impl Read for ChildProcess {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.stdout.read(buf)
+    }
+}
+
+impl ToolProcess for ChildProcess {
+    fn wait(&mut self) -> Result<Output> {
+        let status = self.child.wait()?;
+        let stderr = match self.stderr.take() {
+            Some(stderr) => stderr.join().expect("output reader panicked")?,
+            None => Vec::new(),
+        };
+        Ok(Output {
+            status,
+            stdout: Vec::new(),
+            stderr,
+        })
+    }
+
+    fn kill(&mut self) -> Result<()> {
+        if self.child.try_wait()?.is_none() {
+            self.child.kill()?;
+            self.child.wait()?;
+        }
+        Ok(())
+    }
+}
+
+/// Process of the command that has already finished, e.g. replayed
+struct FinishedProcess {
+    stdout: std::io::Cursor<Vec<u8>>,
+    output: Output,
+}
+
+impl FinishedProcess {
+    fn new(mut output: Output) -> Self {
+        Self {
+            stdout: std::io::Cursor::new(std::mem::take(&mut output.stdout)),
+            output,
+        }
+    }
+}
+
+impl Read for FinishedProcess {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.stdout.read(buf)
+    }
+}
+
+impl ToolProcess for FinishedProcess {
+    fn wait(&mut self) -> Result<Output> {
+        Ok(self.output.clone())
+    }
+
+    fn kill(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads output of the command on a separate thread, passing every line to the line
+/// callback. The output is printed as soon as it's read if needed, so prompts and
+/// progress without a line ending are shown too
+fn read_lines(
+    output: impl Read + Send + 'static,
+    stream: OutputStream,
+    options: &ExecOptions,
+) -> JoinHandle<std::io::Result<Vec<u8>>> {
+    let options = options.clone();
+    std::thread::spawn(move || {
+        let mut output = output;
+        let mut captured = Vec::new();
+        let mut lines = LineSplitter::new(&options, stream);
+        let mut buffer = [0; 8192];
+        loop {
+            let read = match output.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            let chunk = &buffer[..read];
+            if options.print_logs {
+                match stream {
+                    OutputStream::Stdout => {
+                        let mut stdout = std::io::stdout().lock();
+                        stdout.write_all(chunk)?;
+                        stdout.flush()?;
+                    }
+                    OutputStream::Stderr => std::io::stderr().write_all(chunk)?,
+                }
+            }
+            lines.push(chunk);
+            captured.extend_from_slice(chunk);
+        }
+        lines.finish();
+        Ok(captured)
+    })
+}
+
+thread_local! {
+    static EXECUTOR: RefCell<Arc<dyn ToolExecutor>> = RefCell::new(Arc::new(ProcessExecutor));
+}
+
+/// Returns executor of the current thread
+pub fn current_executor() -> Arc<dyn ToolExecutor> {
+    EXECUTOR.with(|executor| executor.borrow().clone())
+}
+
+/// Runs the closure with given executor used by the current thread. The previous
+/// executor is restored afterwards, even if the closure panics
+pub fn with_executor<T>(executor: Arc<dyn ToolExecutor>, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Arc<dyn ToolExecutor>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            if let Some(previous) = self.0.take() {
+                EXECUTOR.with(|executor| *executor.borrow_mut() = previous);
+            }
+        }
+    }
+    let previous = EXECUTOR.with(|current| current.replace(executor));
+    let _restore = Restore(Some(previous));
+    f()
+}
+
+/// Tool invocation with its output
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Invocation {
+    /// Name of the tool, e.g. `aapt2` or `bundletool`
+    pub tool: String,
+    /// Arguments of the tool without the program. For `java -jar` it's the arguments
+    /// after the jar
+    pub args: Vec<String>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    pub exit_code: i32,
+}
+
+impl Invocation {
+    /// Initialize successful invocation of the tool with given arguments
+    pub fn new<S: AsRef<str>>(tool: &str, args: &[S]) -> Self {
+        Self {
+            tool: tool.to_owned(),
+            args: args.iter().map(|arg| arg.as_ref().to_owned()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Reads tool name and arguments of the command
+    pub fn from_command(command: &Command) -> Self {
+        let argv = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        let tool = tool_name(&argv);
+        let java = std::path::Path::new(&argv[0]).file_stem() == Some("java".as_ref());
+        // Arguments of `java -jar bundletool.jar` start after the jar
+        let skip = match argv.iter().position(|arg| arg == "-jar") {
+            Some(jar) if java => jar + 2,
+            _ => 1,
+        };
+        Self::new(&tool, &argv[skip.min(argv.len())..])
+    }
+
+    /// Output printed to stdout
+    pub fn stdout(&mut self, stdout: &str) -> &mut Self {
+        self.stdout = stdout.as_bytes().to_vec();
+        self
+    }
+
+    /// Output printed to stderr
+    pub fn stderr(&mut self, stderr: &str) -> &mut Self {
+        self.stderr = stderr.as_bytes().to_vec();
+        self
+    }
+
+    /// Exit code of the tool. Defaults to 0
+    pub fn exit_code(&mut self, exit_code: i32) -> &mut Self {
+        self.exit_code = exit_code;
+        self
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            status: exit_status(self.exit_code),
+            stdout: self.stdout.clone(),
+            stderr: self.stderr.clone(),
+        }
+    }
+}
+
+/// Executor that checks commands against expected invocations in order and returns
+/// their canned output instead of running the tools. Tools are not looked up in
+/// Android SDK
+#[derive(Debug, Default)]
+pub struct ReplayExecutor {
+    expected: Mutex<VecDeque<Invocation>>,
+}
+
+impl ReplayExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds expected invocation of the tool with given arguments. Returns the invocation
+    /// to set up its output
+    pub fn expect<S: AsRef<str>>(&mut self, tool: &str, args: &[S]) -> &mut Invocation {
+        let expected = self.expected.get_mut().unwrap();
+        expected.push_back(Invocation::new(tool, args));
+        expected.back_mut().unwrap()
+    }
+
+    /// Adds expected invocations, e.g. recorded by [`RecordingExecutor`]
+    pub fn expect_all(&mut self, invocations: Vec<Invocation>) -> &mut Self {
+        self.expected.get_mut().unwrap().extend(invocations);
+        self
+    }
+
+    /// Returns expected invocations that weren't executed yet
+    pub fn remaining(&self) -> Vec<Invocation> {
+        self.expected.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+impl ToolExecutor for ReplayExecutor {
+    fn locate(&self, tool: &str, _locate: &dyn Fn() -> Result<Command>) -> Result<Command> {
+        Ok(Command::new(tool))
+    }
+
+    fn execute(&self, command: &mut Command, options: &ExecOptions) -> Result<Output> {
+        let actual = Invocation::from_command(command);
+        let describe =
+            |invocation: &Invocation| format!("{} {}", invocation.tool, invocation.args.join(" "));
+        let mut expected = self.expected.lock().unwrap();
+        match expected.front() {
+            Some(next) if next.tool == actual.tool && next.args == actual.args => {
+                let output = expected.pop_front().unwrap().output();
+                options.emit(OutputStream::Stdout, &output.stdout);
+                options.emit(OutputStream::Stderr, &output.stderr);
+                Ok(output)
+            }
+            Some(next) => Err(Error::UnexpectedCommand(describe(&actual), describe(next))),
+            None => Err(Error::UnexpectedCommand(
+                describe(&actual),
+                "no more commands".to_owned(),
+            )),
+        }
+    }
+}
+
+/// Executor that records executed commands with their output. Recorded invocations can
+/// be replayed by [`ReplayExecutor`]
+pub struct RecordingExecutor {
+    inner: Arc<dyn ToolExecutor>,
+    invocations: Arc<Mutex<Vec<Invocation>>>,
+}
+
+impl RecordingExecutor {
+    /// Initialize recording of commands executed by given executor
+    pub fn new(inner: Arc<dyn ToolExecutor>) -> Self {
+        Self {
+            inner,
+            invocations: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Returns recorded invocations
+    pub fn invocations(&self) -> Vec<Invocation> {
+        self.invocations.lock().unwrap().clone()
+    }
+
+    fn record(&self, command: &Command, output: &Output) {
+        let mut invocation = Invocation::from_command(command);
+        invocation.stdout = output.stdout.clone();
+        invocation.stderr = output.stderr.clone();
+        invocation.exit_code = output.status.code().unwrap_or(-1);
+        self.invocations.lock().unwrap().push(invocation);
+    }
+}
+
+impl Default for RecordingExecutor {
+    fn default() -> Self {
+        Self::new(Arc::new(ProcessExecutor))
+    }
+}
+
+impl ToolExecutor for RecordingExecutor {
+    fn locate(&self, tool: &str, locate: &dyn Fn() -> Result<Command>) -> Result<Command> {
+        self.inner.locate(tool, locate)
+    }
+
+    fn execute(&self, command: &mut Command, options: &ExecOptions) -> Result<Output> {
+        let output = self.inner.execute(command, options)?;
+        self.record(command, &output);
+        Ok(output)
+    }
+
+    #[cfg(feature = "async")]
+    fn execute_async<'a>(
+        &'a self,
+        command: &'a mut Command,
+        options: &'a ExecOptions,
+    ) -> BoxFuture<'a, Result<Output>> {
+        Box::pin(async move {
+            let output = self.inner.execute_async(command, options).await?;
+            self.record(command, &output);
+            Ok(output)
+        })
+    }
+
+    fn spawn(&self, command: &mut Command) -> Result<Box<dyn ToolProcess>> {
+        let process = self.inner.spawn(command)?;
+        Ok(Box::new(RecordedProcess {
+            process,
+            invocation: Invocation::from_command(command),
+            invocations: self.invocations.clone(),
+        }))
+    }
+}
+
+/// Spawned process which stdout is recorded as it's read. The invocation is recorded
+/// when the process exits
+struct RecordedProcess {
+    process: Box<dyn ToolProcess>,
+    invocation: Invocation,
+    invocations: Arc<Mutex<Vec<Invocation>>>,
+}
+
+impl Read for RecordedProcess {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.process.read(buf)?;
+        self.invocation.stdout.extend_from_slice(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl ToolProcess for RecordedProcess {
+    fn wait(&mut self) -> Result<Output> {
+        let output = self.process.wait()?;
+        let mut invocation = self.invocation.clone();
+        invocation.stderr = output.stderr.clone();
+        invocation.exit_code = output.status.code().unwrap_or(-1);
+        self.invocations.lock().unwrap().push(invocation);
+        Ok(output)
+    }
+
+    fn kill(&mut self) -> Result<()> {
+        self.process.kill()
+    }
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    std::os::unix::process::ExitStatusExt::from_raw(code << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    std::os::windows::process::ExitStatusExt::from_raw(code as u32)
+}
@@ -1,4 +1,6 @@
-use crate::error::*;
+use crate::{
+    command_line::CommandLine, error::*, executor::current_executor, executor::ExecOptions,
+};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -369,6 +371,35 @@ impl JarSigner {
 
     /// Runs jarsigner commands and signa JAR file with arguments
     pub fn run(&self) -> Result<PathBuf> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        self.command()?.output_with(options)?;
+        Ok(self.jar_file.clone())
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<PathBuf> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<PathBuf> {
+        crate::async_command::output_err_async(self.command()?, options).await?;
+        Ok(self.jar_file.clone())
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
         let mut jarsigner = jarsigner_tool()?;
         if self.verify {
             jarsigner.arg("-verify");
@@ -468,21 +499,27 @@ impl JarSigner {
         if self.help {
             jarsigner.arg("-help");
         }
-        jarsigner.output_err(true)?;
-        Ok(self.jar_file.clone())
+        Ok(jarsigner)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
     }
 }
 
 /// Signs and verifies `.aab` and Java Archive (JAR) files
 fn jarsigner_tool() -> Result<Command> {
-    if let Ok(jarsigner) = which::which(bin!("jarsigner")) {
-        return Ok(Command::new(jarsigner));
-    }
-    if let Ok(java) = std::env::var("JAVA_HOME") {
-        let keytool = PathBuf::from(java).join("bin").join(bin!("jarsigner.exe"));
-        if keytool.exists() {
-            return Ok(Command::new(keytool));
-        }
-    }
-    Err(Error::CmdNotFound("jarsigner".to_string()))
+    current_executor().locate("jarsigner", &|| {
+        if let Ok(jarsigner) = which::which(bin!("jarsigner")) {
+            return Ok(Command::new(jarsigner));
+        }
+        if let Ok(java) = std::env::var("JAVA_HOME") {
+            let keytool = PathBuf::from(java).join("bin").join(bin!("jarsigner.exe"));
+            if keytool.exists() {
+                return Ok(Command::new(keytool));
+            }
+        }
+        Err(Error::CmdNotFound("jarsigner".to_string()))
+    })
 }
@@ -1,4 +1,8 @@
-use crate::error::*;
+use crate::{
+    command_line::CommandLine,
+    error::*,
+    executor::{current_executor, ExecOptions},
+};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -223,8 +227,8 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully qualified class
-    /// name with an optional configure argument. For example, if MyProvider is a legacy provider
-    /// loaded via reflection,
+    ///   name with an optional configure argument. For example, if MyProvider is a legacy provider
+    ///   loaded via reflection,
     ///
     /// ```sh
     /// keytool -providerclass com.example.MyProvider ...
@@ -273,7 +277,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully
-    /// qualified class name with an optional configure argument.
+    ///   qualified class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     /// * {-protected}: Password provided through a protected mechanism
@@ -339,7 +343,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [providerarg arg]}: Add security provider by fully qualified
-    /// class name with an optional configure argument.
+    ///   class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     /// * {-protected}: Password provided through a protected mechanism
@@ -369,7 +373,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully qualified
-    /// class name with an optional configure argument.
+    ///   class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     ///
@@ -404,7 +408,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully qualified
-    /// class name with an optional configure argument.
+    ///   class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     /// * {-protected}: Password provided through a protected mechanism
@@ -436,7 +440,7 @@ impl Keytool {
     /// * [-destkeypass arg]: Destination key password
     /// * {-noprompt}: Do not prompt
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully qualified
-    /// class name with an optional configure argument
+    ///   class name with an optional configure argument
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     ///
@@ -500,7 +504,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully qualified
-    /// class name with an optional configure argument.
+    ///   class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     /// * {-protected }: Password provided through a protected mechanism
@@ -571,7 +575,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg] }: Add security provider by fully qualified
-    /// class name with an optional configure argument.
+    ///   class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     /// * {-protected}: Password provided through a protected mechanism
@@ -649,7 +653,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully
-    /// qualified class name with an optional configure argument.
+    ///   qualified class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output]
     ///
@@ -668,7 +672,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully
-    /// qualified class name with an optional configure argument.
+    ///   qualified class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     ///
@@ -692,7 +696,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully qualified
-    /// class name with an optional configure argument.
+    ///   class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     /// * {-protected}: Password provided through a protected mechanism
@@ -712,7 +716,7 @@ impl Keytool {
     /// * {-storetype type}: Keystore type
     /// * {-providername name}: Provider name
     /// * {-providerclass class [-providerarg arg]}: Add security provider by fully qualifie
-    /// class name with an optional configure argument.
+    ///   class name with an optional configure argument.
     /// * {-providerpath list}: Provider classpath
     /// * {-v}: Verbose output
     /// * {-protected}: Password provided through a protected mechanism
@@ -786,6 +790,47 @@ impl Keytool {
 
     /// Runs keytool commands
     pub fn run(&self) -> Result<Option<Key>> {
+        self.run_with(ExecOptions::new().print_logs(true))
+    }
+
+    /// Runs the command with given execution options, e.g. a timeout
+    pub fn run_with(&self, options: &ExecOptions) -> Result<Option<Key>> {
+        let (keytool, key) = self.command_with_key()?;
+        keytool.output_with(options)?;
+        Ok(key)
+    }
+
+    /// Executes the command asynchronously. The child process is killed when the
+    /// future is dropped
+    #[cfg(feature = "async")]
+    pub async fn run_async(&self) -> Result<Option<Key>> {
+        self.run_async_with(ExecOptions::new().print_logs(true))
+            .await
+    }
+
+    /// Executes the command asynchronously with given execution options, e.g. a
+    /// timeout. The child process is killed when the future is dropped or the
+    /// timeout elapses
+    #[cfg(feature = "async")]
+    pub async fn run_async_with(&self, options: &ExecOptions) -> Result<Option<Key>> {
+        let (keytool, key) = self.command_with_key()?;
+        crate::async_command::output_err_async(keytool, options).await?;
+        Ok(key)
+    }
+
+    /// Builds the command `run` executes, e.g. to execute it with
+    /// [`CommandExt::output_with`](crate::error::CommandExt::output_with)
+    pub fn command(&self) -> Result<Command> {
+        Ok(self.command_with_key()?.0)
+    }
+
+    /// Returns program and arguments the command is run with, without running it
+    pub fn command_line(&self) -> Result<CommandLine> {
+        Ok(CommandLine::new(&self.command()?))
+    }
+
+    /// Builds the command and the key it generates
+    fn command_with_key(&self) -> Result<(Command, Option<Key>)> {
         let mut key = Some(Key::new_default()?);
         let mut keytool = keytool()?;
         if self.v {
@@ -927,8 +972,7 @@ impl Keytool {
                     .join(","),
             );
         }
-        keytool.output_err(true)?;
-        Ok(key)
+        Ok((keytool, key))
     }
 }
 
@@ -939,16 +983,18 @@ impl Keytool {
 /// The `keytool` command also enables users to cache the public keys (in the form of
 /// certificates) of their communicating peers
 pub fn keytool() -> Result<Command> {
-    if let Ok(keytool) = which::which(bin!("keytool")) {
-        return Ok(Command::new(keytool));
-    }
-    if let Ok(java) = std::env::var("JAVA_HOME") {
-        let keytool = PathBuf::from(java).join("bin").join(bin!("keytool"));
-        if keytool.exists() {
+    current_executor().locate("keytool", &|| {
+        if let Ok(keytool) = which::which(bin!("keytool")) {
             return Ok(Command::new(keytool));
         }
-    }
-    Err(Error::CmdNotFound("keytool".to_string()))
+        if let Ok(java) = std::env::var("JAVA_HOME") {
+            let keytool = PathBuf::from(java).join("bin").join(bin!("keytool"));
+            if keytool.exists() {
+                return Ok(Command::new(keytool));
+            }
+        }
+        Err(Error::CmdNotFound("keytool".to_string()))
+    })
 }
 
 #[derive(Clone)]
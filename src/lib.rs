@@ -13,35 +13,38 @@ macro_rules! bin {
     }};
 }
 
+pub mod arsc;
+#[cfg(feature = "async")]
+pub mod async_command;
+pub mod axml;
+pub mod command_line;
 pub mod error;
+pub mod executor;
+pub mod sdk;
+pub mod zip_align;
 
 #[cfg(feature = "aapt2")]
 pub mod aapt2;
 pub mod adb;
+#[cfg(feature = "apksig")]
+pub mod apksig;
+#[cfg(feature = "build-tools")]
+pub mod build_tools;
 #[cfg(feature = "bundletool")]
 pub mod bundletool;
 #[cfg(feature = "emulator")]
 pub mod emulator;
 #[cfg(feature = "java-tools")]
 pub mod java_tools;
+#[cfg(feature = "manifest")]
+pub mod manifest;
+#[cfg(feature = "proto")]
+pub mod proto;
 
-/// Return SDK path from found environment variable
+/// Return SDK path from found environment variable, `local.properties` or default
+/// installation path. See [`AndroidSdk::from_env`](sdk::AndroidSdk::from_env)
 pub fn sdk_path_from_env() -> crate::error::Result<PathBuf> {
-    let sdk_path = {
-        let sdk_path = std::env::var("ANDROID_SDK_ROOT")
-            .ok()
-            .or_else(|| std::env::var("ANDROID_SDK_PATH").ok())
-            .or_else(|| std::env::var("ANDROID_HOME").ok());
-        if let Some(path) = sdk_path {
-            std::path::PathBuf::from(path)
-        } else {
-            sdk_install_path()?
-        }
-    };
-    if !sdk_path.exists() {
-        return Err(Error::AndroidSdkNotFound)?;
-    }
-    Ok(sdk_path)
+    Ok(sdk::AndroidSdk::from_env()?.path().to_owned())
 }
 
 /// Default installation path
@@ -65,13 +68,15 @@ pub fn sdk_install_path() -> crate::error::Result<PathBuf> {
     Ok(sdk_path)
 }
 
+/// Returns the highest version among subdirectory names, compared as versions
 pub fn find_max_version(target_dir: &std::path::Path) -> crate::error::Result<String> {
-    let max_version = std::fs::read_dir(&target_dir)?
+    let max_version = std::fs::read_dir(target_dir)?
         .filter_map(|path| path.ok())
         .filter(|path| path.path().is_dir())
         .filter_map(|path| path.file_name().into_string().ok())
-        .filter(|name| name.chars().next().unwrap().is_ascii_digit())
-        .max()
+        .filter_map(|name| Some((name.parse::<sdk::Version>().ok()?, name)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, name)| name)
         .ok_or(Error::AndroidToolNotFound)?;
     Ok(max_version)
 }
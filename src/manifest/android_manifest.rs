@@ -0,0 +1,159 @@
+use super::Application;
+use crate::error::*;
+use quick_xml::se::Serializer;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Android namespace URI bound to the `android` prefix
+pub const ANDROID_NAMESPACE: &str = "http://schemas.android.com/apk/res/android";
+
+/// Root `<manifest>` element of `AndroidManifest.xml`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "manifest")]
+pub struct AndroidManifest {
+    #[serde(rename = "@xmlns:android", default = "default_namespace")]
+    pub ns_android: String,
+    /// Package name, e.g. `com.example.app`
+    #[serde(rename = "@package")]
+    pub package: String,
+    #[serde(
+        rename(serialize = "@android:versionCode", deserialize = "@versionCode"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub version_code: Option<u32>,
+    #[serde(
+        rename(serialize = "@android:versionName", deserialize = "@versionName"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub version_name: Option<String>,
+    #[serde(rename = "uses-sdk", skip_serializing_if = "Option::is_none")]
+    pub uses_sdk: Option<UsesSdk>,
+    #[serde(rename = "uses-permission", default)]
+    pub uses_permission: Vec<UsesPermission>,
+    #[serde(rename = "uses-feature", default)]
+    pub uses_feature: Vec<UsesFeature>,
+    #[serde(default)]
+    pub application: Application,
+}
+
+impl Default for AndroidManifest {
+    fn default() -> Self {
+        Self {
+            ns_android: default_namespace(),
+            package: String::new(),
+            version_code: None,
+            version_name: None,
+            uses_sdk: None,
+            uses_permission: Vec::new(),
+            uses_feature: Vec::new(),
+            application: Application::default(),
+        }
+    }
+}
+
+fn default_namespace() -> String {
+    ANDROID_NAMESPACE.to_owned()
+}
+
+impl AndroidManifest {
+    /// Parses manifest from XML. Attributes are matched by local name and elements and
+    /// attributes that aren't modeled are skipped
+    pub fn from_xml(xml: &str) -> Result<Self> {
+        quick_xml::de::from_str(xml).map_err(|err| Error::InvalidManifest(err.to_string()))
+    }
+
+    /// Reads and parses `AndroidManifest.xml`
+    pub fn from_file(path: &Path) -> Result<Self> {
+        Self::from_xml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Writes manifest as indented XML with the XML declaration
+    pub fn to_xml(&self) -> Result<String> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        let mut serializer = Serializer::new(&mut xml);
+        serializer.indent(' ', 4);
+        self.serialize(serializer)
+            .map_err(|err| Error::InvalidManifest(err.to_string()))?;
+        xml.push('\n');
+        Ok(xml)
+    }
+
+    /// Writes manifest to the file, e.g. `build/AndroidManifest.xml`
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_xml()?)?;
+        Ok(())
+    }
+}
+
+/// `<uses-sdk>` element
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsesSdk {
+    #[serde(
+        rename(serialize = "@android:minSdkVersion", deserialize = "@minSdkVersion"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_sdk_version: Option<u32>,
+    #[serde(
+        rename(
+            serialize = "@android:targetSdkVersion",
+            deserialize = "@targetSdkVersion"
+        ),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub target_sdk_version: Option<u32>,
+    #[serde(
+        rename(serialize = "@android:maxSdkVersion", deserialize = "@maxSdkVersion"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_sdk_version: Option<u32>,
+}
+
+/// `<uses-permission>` element
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsesPermission {
+    /// Permission name, e.g. `android.permission.INTERNET`
+    #[serde(rename(serialize = "@android:name", deserialize = "@name"))]
+    pub name: String,
+    #[serde(
+        rename(serialize = "@android:maxSdkVersion", deserialize = "@maxSdkVersion"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_sdk_version: Option<u32>,
+}
+
+impl UsesPermission {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+/// `<uses-feature>` element. Declares either hardware or software feature by name or
+/// OpenGL ES version
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsesFeature {
+    /// Feature name, e.g. `android.hardware.vulkan.level`
+    #[serde(
+        rename(serialize = "@android:name", deserialize = "@name"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub name: Option<String>,
+    #[serde(
+        rename(serialize = "@android:required", deserialize = "@required"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub required: Option<bool>,
+    #[serde(
+        rename(serialize = "@android:version", deserialize = "@version"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub version: Option<u32>,
+    /// OpenGL ES version, e.g. `0x00030002` for 3.2
+    #[serde(
+        rename(serialize = "@android:glEsVersion", deserialize = "@glEsVersion"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gl_es_version: Option<String>,
+}
@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+
+/// `<application>` element with app components
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Application {
+    /// Class name of the `Application` subclass
+    #[serde(
+        rename(serialize = "@android:name", deserialize = "@name"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub name: Option<String>,
+    /// Label, e.g. `@string/app_name`
+    #[serde(
+        rename(serialize = "@android:label", deserialize = "@label"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub label: Option<String>,
+    #[serde(
+        rename(serialize = "@android:icon", deserialize = "@icon"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub icon: Option<String>,
+    #[serde(
+        rename(serialize = "@android:roundIcon", deserialize = "@roundIcon"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub round_icon: Option<String>,
+    #[serde(
+        rename(serialize = "@android:theme", deserialize = "@theme"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub theme: Option<String>,
+    #[serde(
+        rename(serialize = "@android:debuggable", deserialize = "@debuggable"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub debuggable: Option<bool>,
+    /// `false` for apps without DEX code, e.g. using only `NativeActivity`
+    #[serde(
+        rename(serialize = "@android:hasCode", deserialize = "@hasCode"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub has_code: Option<bool>,
+    #[serde(
+        rename(serialize = "@android:allowBackup", deserialize = "@allowBackup"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub allow_backup: Option<bool>,
+    #[serde(
+        rename(
+            serialize = "@android:extractNativeLibs",
+            deserialize = "@extractNativeLibs"
+        ),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub extract_native_libs: Option<bool>,
+    #[serde(
+        rename(
+            serialize = "@android:usesCleartextTraffic",
+            deserialize = "@usesCleartextTraffic"
+        ),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub uses_cleartext_traffic: Option<bool>,
+    #[serde(rename = "meta-data", default)]
+    pub meta_data: Vec<MetaData>,
+    #[serde(rename = "activity", default)]
+    pub activities: Vec<Activity>,
+    #[serde(rename = "service", default)]
+    pub services: Vec<Service>,
+    #[serde(rename = "receiver", default)]
+    pub receivers: Vec<Receiver>,
+    #[serde(rename = "provider", default)]
+    pub providers: Vec<Provider>,
+}
+
+/// `<activity>` element
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Activity {
+    /// Class name, e.g. `android.app.NativeActivity`
+    #[serde(rename(serialize = "@android:name", deserialize = "@name"))]
+    pub name: String,
+    #[serde(
+        rename(serialize = "@android:label", deserialize = "@label"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub label: Option<String>,
+    #[serde(
+        rename(serialize = "@android:exported", deserialize = "@exported"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exported: Option<bool>,
+    #[serde(
+        rename(serialize = "@android:theme", deserialize = "@theme"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub theme: Option<String>,
+    /// Launch mode, e.g. `singleTask`
+    #[serde(
+        rename(serialize = "@android:launchMode", deserialize = "@launchMode"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub launch_mode: Option<String>,
+    /// Changes handled by the activity, e.g. `orientation|keyboardHidden|screenSize`
+    #[serde(
+        rename(serialize = "@android:configChanges", deserialize = "@configChanges"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub config_changes: Option<String>,
+    #[serde(
+        rename(
+            serialize = "@android:screenOrientation",
+            deserialize = "@screenOrientation"
+        ),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub screen_orientation: Option<String>,
+    #[serde(rename = "intent-filter", default)]
+    pub intent_filters: Vec<IntentFilter>,
+    #[serde(rename = "meta-data", default)]
+    pub meta_data: Vec<MetaData>,
+}
+
+impl Activity {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+/// `<service>` element
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Service {
+    #[serde(rename(serialize = "@android:name", deserialize = "@name"))]
+    pub name: String,
+    #[serde(
+        rename(serialize = "@android:enabled", deserialize = "@enabled"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub enabled: Option<bool>,
+    #[serde(
+        rename(serialize = "@android:exported", deserialize = "@exported"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exported: Option<bool>,
+    /// Permission required to start or bind to the service
+    #[serde(
+        rename(serialize = "@android:permission", deserialize = "@permission"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub permission: Option<String>,
+    /// Foreground service types, e.g. `location|mediaPlayback`
+    #[serde(
+        rename(
+            serialize = "@android:foregroundServiceType",
+            deserialize = "@foregroundServiceType"
+        ),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub foreground_service_type: Option<String>,
+    #[serde(rename = "intent-filter", default)]
+    pub intent_filters: Vec<IntentFilter>,
+    #[serde(rename = "meta-data", default)]
+    pub meta_data: Vec<MetaData>,
+}
+
+/// `<receiver>` element
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receiver {
+    #[serde(rename(serialize = "@android:name", deserialize = "@name"))]
+    pub name: String,
+    #[serde(
+        rename(serialize = "@android:enabled", deserialize = "@enabled"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub enabled: Option<bool>,
+    #[serde(
+        rename(serialize = "@android:exported", deserialize = "@exported"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exported: Option<bool>,
+    /// Permission broadcasters must hold to send messages to the receiver
+    #[serde(
+        rename(serialize = "@android:permission", deserialize = "@permission"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub permission: Option<String>,
+    #[serde(rename = "intent-filter", default)]
+    pub intent_filters: Vec<IntentFilter>,
+    #[serde(rename = "meta-data", default)]
+    pub meta_data: Vec<MetaData>,
+}
+
+/// `<provider>` element
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provider {
+    #[serde(rename(serialize = "@android:name", deserialize = "@name"))]
+    pub name: String,
+    /// Semicolon-separated authorities, e.g. `com.example.app.fileprovider`
+    #[serde(rename(serialize = "@android:authorities", deserialize = "@authorities"))]
+    pub authorities: String,
+    #[serde(
+        rename(serialize = "@android:enabled", deserialize = "@enabled"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub enabled: Option<bool>,
+    #[serde(
+        rename(serialize = "@android:exported", deserialize = "@exported"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exported: Option<bool>,
+    #[serde(
+        rename(
+            serialize = "@android:grantUriPermissions",
+            deserialize = "@grantUriPermissions"
+        ),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub grant_uri_permissions: Option<bool>,
+    #[serde(rename = "meta-data", default)]
+    pub meta_data: Vec<MetaData>,
+}
+
+/// `<intent-filter>` element
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntentFilter {
+    #[serde(rename = "action", default)]
+    pub actions: Vec<Action>,
+    #[serde(rename = "category", default)]
+    pub categories: Vec<Category>,
+    #[serde(rename = "data", default)]
+    pub data: Vec<Data>,
+}
+
+impl IntentFilter {
+    /// Filter of the launcher activity: `MAIN` action and `LAUNCHER` category
+    pub fn launcher() -> Self {
+        Self {
+            actions: vec![Action {
+                name: "android.intent.action.MAIN".to_owned(),
+            }],
+            categories: vec![Category {
+                name: "android.intent.category.LAUNCHER".to_owned(),
+            }],
+            data: Vec::new(),
+        }
+    }
+}
+
+/// `<action>` element of the intent filter
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Action {
+    #[serde(rename(serialize = "@android:name", deserialize = "@name"))]
+    pub name: String,
+}
+
+/// `<category>` element of the intent filter
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Category {
+    #[serde(rename(serialize = "@android:name", deserialize = "@name"))]
+    pub name: String,
+}
+
+/// `<data>` element of the intent filter
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Data {
+    #[serde(
+        rename(serialize = "@android:scheme", deserialize = "@scheme"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub scheme: Option<String>,
+    #[serde(
+        rename(serialize = "@android:host", deserialize = "@host"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub host: Option<String>,
+    #[serde(
+        rename(serialize = "@android:port", deserialize = "@port"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub port: Option<String>,
+    #[serde(
+        rename(serialize = "@android:path", deserialize = "@path"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub path: Option<String>,
+    #[serde(
+        rename(serialize = "@android:pathPrefix", deserialize = "@pathPrefix"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub path_prefix: Option<String>,
+    #[serde(
+        rename(serialize = "@android:pathPattern", deserialize = "@pathPattern"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub path_pattern: Option<String>,
+    #[serde(
+        rename(serialize = "@android:mimeType", deserialize = "@mimeType"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mime_type: Option<String>,
+}
+
+/// `<meta-data>` element with either value or resource
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetaData {
+    #[serde(rename(serialize = "@android:name", deserialize = "@name"))]
+    pub name: String,
+    #[serde(
+        rename(serialize = "@android:value", deserialize = "@value"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub value: Option<String>,
+    #[serde(
+        rename(serialize = "@android:resource", deserialize = "@resource"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub resource: Option<String>,
+}
+
+impl MetaData {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            value: Some(value.to_owned()),
+            resource: None,
+        }
+    }
+}
@@ -0,0 +1,563 @@
+use crate::error::*;
+use quick_xml::{
+    events::{BytesDecl, BytesEnd, BytesStart, Event},
+    Reader, Writer,
+};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Tools namespace URI bound to the `tools` prefix
+pub const TOOLS_NAMESPACE: &str = "http://schemas.android.com/tools";
+
+const TOOLS_NODE: &str = "tools:node";
+const TOOLS_REPLACE: &str = "tools:replace";
+const TOOLS_REMOVE: &str = "tools:remove";
+const TOOLS_OVERRIDE_LIBRARY: &str = "tools:overrideLibrary";
+
+/// Merges the main `AndroidManifest.xml` with overlays and library manifests like the
+/// Android Gradle Plugin does. Elements are matched by tag and `android:name`, and
+/// merging is controlled with `tools:node`, `tools:replace` and `tools:remove`.
+/// Namespaces are expected to be bound to the `android` and `tools` prefixes.
+///
+/// ```no_run
+/// use android_tools::{aapt2::Aapt2Link, manifest::ManifestMerger};
+/// use std::path::Path;
+///
+/// let merged = ManifestMerger::new(Path::new("src/AndroidManifest.xml"))
+///     .library(Path::new("libs/billing/AndroidManifest.xml"))
+///     .placeholder("applicationId", "com.example.app")
+///     .run()
+///     .unwrap();
+/// let manifest_path = Path::new("build/AndroidManifest.xml");
+/// merged.to_file(manifest_path).unwrap();
+/// Aapt2Link::new(&[], Path::new("build/app.apk"), manifest_path)
+///     .run()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct ManifestMerger {
+    main: PathBuf,
+    overlays: Vec<PathBuf>,
+    libraries: Vec<PathBuf>,
+    placeholders: BTreeMap<String, String>,
+}
+
+impl ManifestMerger {
+    pub fn new(main: &Path) -> Self {
+        Self {
+            main: main.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds overlay, e.g. manifest of the build type. Overlays have higher priority than
+    /// the main manifest, the first added overlay has the highest priority. Class names
+    /// starting with `.` are expanded with the package of the main manifest
+    pub fn overlay(&mut self, overlay: &Path) -> &mut Self {
+        self.overlays.push(overlay.to_owned());
+        self
+    }
+
+    /// Adds library manifest. Libraries have lower priority than the main manifest, the
+    /// first added library has the highest priority. Class names starting with `.` are
+    /// expanded with the package of the library
+    pub fn library(&mut self, library: &Path) -> &mut Self {
+        self.libraries.push(library.to_owned());
+        self
+    }
+
+    /// Sets value of the `${name}` placeholder. `${applicationId}` defaults to the
+    /// package of the merged manifest
+    pub fn placeholder(&mut self, name: &str, value: &str) -> &mut Self {
+        self.placeholders.insert(name.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Merges manifests. Fails with [`Error::ManifestMergeFailed`] listing conflicts
+    /// that aren't resolved with `tools:` attributes
+    pub fn run(&self) -> Result<MergedManifest> {
+        let mut log = Vec::new();
+        let mut merged = Element::read(&self.main)?;
+        let package = merged.attribute("package").unwrap_or_default().to_owned();
+        merged.expand_class_names(&package);
+        for overlay in self.overlays.iter().rev() {
+            let mut higher = Element::read(overlay)?;
+            higher.expand_class_names(&package);
+            Merge::new(overlay, None, &mut log).element(&mut higher, merged, "manifest");
+            merged = higher;
+        }
+        for library in &self.libraries {
+            let mut lower = Element::read(library)?;
+            let package = lower.attribute("package").unwrap_or_default().to_owned();
+            lower.expand_class_names(&package);
+            Merge::new(library, Some(package), &mut log).element(&mut merged, lower, "manifest");
+        }
+        merged.remove_tools();
+        let mut placeholders = self.placeholders.clone();
+        if let Some(package) = merged.attribute("package") {
+            placeholders
+                .entry("applicationId".to_owned())
+                .or_insert_with(|| package.to_owned());
+        }
+        let mut merge = Merge::new(&self.main, None, &mut log);
+        merge.substitute(&mut merged, &placeholders, "manifest");
+        let errors: Vec<_> = log
+            .iter()
+            .filter(|record| record.severity == Severity::Error)
+            .map(ToString::to_string)
+            .collect();
+        if !errors.is_empty() {
+            return Err(Error::ManifestMergeFailed(errors.join("\n")));
+        }
+        Ok(MergedManifest {
+            xml: merged.to_xml()?,
+            log,
+        })
+    }
+}
+
+/// Result of [`ManifestMerger`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedManifest {
+    /// Merged `AndroidManifest.xml`
+    pub xml: String,
+    /// Decisions made while merging, e.g. added, removed or replaced elements
+    pub log: Vec<MergeRecord>,
+}
+
+impl MergedManifest {
+    /// Writes merged manifest to the file to pass it to
+    /// [`Aapt2Link`](crate::aapt2::Aapt2Link)
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, &self.xml)?;
+        Ok(())
+    }
+}
+
+/// Entry of the merge log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeRecord {
+    pub severity: Severity,
+    /// Path of the element, e.g. `manifest/application/activity#com.example.Main`
+    pub element: String,
+    /// Manifest the merged element or attribute comes from
+    pub source: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for MergeRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        };
+        write!(
+            f,
+            "{}: {}: {}: {}",
+            self.source.display(),
+            severity,
+            self.element,
+            self.message
+        )
+    }
+}
+
+/// Merging of the lower priority manifest into the higher priority one
+struct Merge<'a> {
+    source: &'a Path,
+    /// Package of the library, if the lower priority manifest is a library
+    library: Option<String>,
+    log: &'a mut Vec<MergeRecord>,
+}
+
+impl<'a> Merge<'a> {
+    fn new(source: &'a Path, library: Option<String>, log: &'a mut Vec<MergeRecord>) -> Self {
+        Self {
+            source,
+            library,
+            log,
+        }
+    }
+
+    fn record(&mut self, severity: Severity, element: &str, message: String) {
+        self.log.push(MergeRecord {
+            severity,
+            element: element.to_owned(),
+            source: self.source.to_owned(),
+            message,
+        });
+    }
+
+    fn element(&mut self, higher: &mut Element, lower: Element, path: &str) {
+        let node = higher.attribute(TOOLS_NODE).unwrap_or("merge").to_owned();
+        match node.as_str() {
+            "replace" => {
+                self.record(Severity::Note, path, "replaced by tools:node".to_owned());
+                return;
+            }
+            "strict" => {
+                if higher.without_tools() != lower.without_tools() {
+                    self.record(
+                        Severity::Error,
+                        path,
+                        "element is different from the declaration with tools:node=\"strict\""
+                            .to_owned(),
+                    );
+                }
+                return;
+            }
+            _ => {}
+        }
+        self.attributes(higher, &lower, path);
+        if node == "merge-only-attributes" {
+            return;
+        }
+        for child in lower.children {
+            let child_path = format!("{}/{}", path, child.describe());
+            let remove_all = higher.children.iter().any(|existing| {
+                existing.name == child.name && existing.attribute(TOOLS_NODE) == Some("removeAll")
+            });
+            if remove_all {
+                let message = "removed by tools:node=\"removeAll\"".to_owned();
+                self.record(Severity::Note, &child_path, message);
+                continue;
+            }
+            // `uses-sdk` of the library is only checked against the app, whose
+            // `minSdkVersion` is 1 without the element
+            if self.library.is_some() && child.name == "uses-sdk" {
+                let app = higher
+                    .children
+                    .iter()
+                    .find(|existing| existing.name == "uses-sdk");
+                let app = app.cloned().unwrap_or_else(|| Element::new("uses-sdk"));
+                self.min_sdk_version(&app, &child, &child_path);
+                continue;
+            }
+            let key = child.key();
+            let existing = higher.children.iter_mut().find(|existing| {
+                existing.name == child.name
+                    && match &key {
+                        Some(key) => existing.key().as_ref() == Some(key),
+                        None => existing.without_tools() == child.without_tools(),
+                    }
+            });
+            match existing {
+                Some(existing) if existing.attribute(TOOLS_NODE) == Some("remove") => {
+                    let message = "removed by tools:node=\"remove\"".to_owned();
+                    self.record(Severity::Note, &child_path, message);
+                }
+                Some(existing) if key.is_some() => self.element(existing, child, &child_path),
+                Some(_) => {}
+                None => {
+                    self.record(Severity::Note, &child_path, "added".to_owned());
+                    higher.children.push(child);
+                }
+            }
+        }
+    }
+
+    fn attributes(&mut self, higher: &mut Element, lower: &Element, path: &str) {
+        let replace = higher.list(TOOLS_REPLACE);
+        let remove = higher.list(TOOLS_REMOVE);
+        for (name, value) in &lower.attributes {
+            let is_namespace = name.starts_with("xmlns:");
+            if name.starts_with("tools:") {
+                // Annotations of the main manifest still apply to libraries merged later
+                if self.library.is_none() {
+                    Self::tools_attribute(higher, name, value);
+                }
+                continue;
+            }
+            if remove.contains(name) {
+                let message = format!("attribute {} removed by tools:remove", name);
+                self.record(Severity::Note, path, message);
+                continue;
+            }
+            // Package and version of the library don't apply to the app
+            if self.library.is_some() && higher.name == "manifest" && !is_namespace {
+                continue;
+            }
+            match higher.attribute(name) {
+                None => higher.attributes.push((name.clone(), value.clone())),
+                Some(existing) if existing == value || is_namespace => {}
+                Some(_) if replace.contains(name) => {
+                    let message = format!(
+                        "attribute {} value=({}) replaced by tools:replace",
+                        name, value
+                    );
+                    self.record(Severity::Note, path, message);
+                }
+                Some(existing) => {
+                    let message = format!(
+                        "attribute {} value=({}) is also present with value=({}). Add \
+                         tools:replace=\"{}\" to <{}> element to override it",
+                        name, existing, value, name, higher.name
+                    );
+                    self.record(Severity::Error, path, message);
+                }
+            }
+        }
+    }
+
+    /// Carries `tools:` annotation into the higher priority element. Lists, like
+    /// `tools:replace`, are joined, otherwise the higher priority value is kept
+    fn tools_attribute(higher: &mut Element, name: &str, value: &str) {
+        let is_list = [TOOLS_REPLACE, TOOLS_REMOVE, TOOLS_OVERRIDE_LIBRARY].contains(&name);
+        let existing = higher
+            .attributes
+            .iter_mut()
+            .find(|(attribute, _)| attribute == name);
+        match existing {
+            None => higher.attributes.push((name.to_owned(), value.to_owned())),
+            Some((_, existing)) if is_list => {
+                for item in value.split(',').map(str::trim) {
+                    if !existing.split(',').any(|listed| listed.trim() == item) {
+                        existing.push(',');
+                        existing.push_str(item);
+                    }
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Libraries can't require higher `minSdkVersion` than the app unless listed in
+    /// `tools:overrideLibrary`. A library built for a preview codename, e.g.
+    /// `UpsideDownCake`, requires the app to use the same codename
+    fn min_sdk_version(&mut self, higher: &Element, lower: &Element, path: &str) {
+        let version = |element: &Element| {
+            element
+                .attribute("android:minSdkVersion")
+                .unwrap_or("1")
+                .to_owned()
+        };
+        let (app, library) = (version(higher), version(lower));
+        let overridden = self
+            .library
+            .as_ref()
+            .is_some_and(|package| higher.list(TOOLS_OVERRIDE_LIBRARY).contains(package));
+        if overridden {
+            return;
+        }
+        if app.contains("${") || library.contains("${") {
+            let message = format!(
+                "minSdkVersion {} of the app can't be checked against version {} declared in \
+                 library",
+                app, library
+            );
+            self.record(Severity::Warning, path, message);
+            return;
+        }
+        let relation = match (app.parse::<u32>(), library.parse::<u32>()) {
+            (Ok(app), Ok(library)) if library > app => "smaller than",
+            (Ok(_), Ok(_)) => return,
+            // Preview codename is newer than any released API level
+            (Err(_), Ok(_)) => return,
+            _ if app == library => return,
+            _ => "different than",
+        };
+        let message = format!(
+            "minSdkVersion {} cannot be {} version {} declared in library",
+            app, relation, library
+        );
+        self.record(Severity::Error, path, message);
+    }
+
+    fn substitute(
+        &mut self,
+        element: &mut Element,
+        placeholders: &BTreeMap<String, String>,
+        path: &str,
+    ) {
+        for (name, value) in &mut element.attributes {
+            // Substituted values aren't searched for placeholders again
+            let mut from = 0;
+            while let Some(start) = value[from..].find("${").map(|start| from + start) {
+                let Some(end) = value[start..].find('}').map(|end| start + end) else {
+                    break;
+                };
+                let placeholder = value[start + 2..end].to_owned();
+                match placeholders.get(&placeholder) {
+                    Some(substitution) => {
+                        value.replace_range(start..=end, substitution);
+                        from = start + substitution.len();
+                    }
+                    None => {
+                        let message = format!(
+                            "attribute {} requires a value for placeholder ${{{}}}",
+                            name, placeholder
+                        );
+                        self.record(Severity::Error, path, message);
+                        break;
+                    }
+                }
+            }
+        }
+        for child in &mut element.children {
+            let child_path = format!("{}/{}", path, child.describe());
+            self.substitute(child, placeholders, &child_path);
+        }
+    }
+}
+
+/// Element of the text XML with qualified names of attributes
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        let xml = std::fs::read_to_string(path)?;
+        Self::from_xml(&xml)
+            .map_err(|err| Error::InvalidManifest(format!("{}: {}", path.display(), err)))
+    }
+
+    fn from_xml(xml: &str) -> std::result::Result<Self, String> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+        let mut stack: Vec<Element> = Vec::new();
+        loop {
+            let element = match reader.read_event().map_err(|err| err.to_string())? {
+                Event::Start(start) => {
+                    stack.push(Self::from_start(&start)?);
+                    continue;
+                }
+                Event::Empty(start) => Self::from_start(&start)?,
+                Event::End(_) => stack.pop().ok_or("unexpected end of element")?,
+                Event::Eof => return Err("missing root element".to_owned()),
+                _ => continue,
+            };
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => return Ok(element),
+            }
+        }
+    }
+
+    fn from_start(start: &BytesStart) -> std::result::Result<Self, String> {
+        let mut attributes = Vec::new();
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|err| err.to_string())?;
+            let name = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+            let value = attribute.unescape_value().map_err(|err| err.to_string())?;
+            attributes.push((name, value.into_owned()));
+        }
+        Ok(Self {
+            name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+            attributes,
+            children: Vec::new(),
+        })
+    }
+
+    fn to_xml(&self) -> Result<String> {
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 4);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+        self.write(&mut writer)?;
+        let mut xml = String::from_utf8_lossy(&writer.into_inner()).into_owned();
+        xml.push('\n');
+        Ok(xml)
+    }
+
+    fn write(&self, writer: &mut Writer<Vec<u8>>) -> Result<()> {
+        let start = BytesStart::new(self.name.as_str()).with_attributes(
+            self.attributes
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        if self.children.is_empty() {
+            writer.write_event(Event::Empty(start))?;
+            return Ok(());
+        }
+        writer.write_event(Event::Start(start))?;
+        for child in &self.children {
+            child.write(writer)?;
+        }
+        writer.write_event(Event::End(BytesEnd::new(self.name.as_str())))?;
+        Ok(())
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Comma-separated values of the attribute, e.g. of `tools:replace`
+    fn list(&self, name: &str) -> Vec<String> {
+        self.attribute(name)
+            .map(|list| list.split(',').map(|item| item.trim().to_owned()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Key the element is matched by. Elements without a key, like `<intent-filter>`,
+    /// are matched if they are equal
+    fn key(&self) -> Option<String> {
+        match self.name.as_str() {
+            "manifest" | "application" | "uses-sdk" | "supports-screens" => Some(String::new()),
+            "uses-feature" => self
+                .attribute("android:name")
+                .or_else(|| self.attribute("android:glEsVersion"))
+                .map(str::to_owned),
+            _ => self.attribute("android:name").map(str::to_owned),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self.key() {
+            Some(key) if !key.is_empty() => format!("{}#{}", self.name, key),
+            _ => self.name.clone(),
+        }
+    }
+
+    fn without_tools(&self) -> Self {
+        let mut element = self.clone();
+        element.remove_tools();
+        element
+    }
+
+    /// Removes elements marked with `tools:node="remove"` or `"removeAll"` and
+    /// `tools:` attributes
+    fn remove_tools(&mut self) {
+        self.children
+            .retain(|child| !matches!(child.attribute(TOOLS_NODE), Some("remove" | "removeAll")));
+        self.attributes.retain(|(name, value)| {
+            let tools_namespace = name.starts_with("xmlns:") && value == TOOLS_NAMESPACE;
+            !name.starts_with("tools:") && !tools_namespace
+        });
+        self.children.iter_mut().for_each(Self::remove_tools);
+    }
+
+    /// Expands class names of components relative to the package, e.g. `.Main`
+    fn expand_class_names(&mut self, package: &str) {
+        let component = matches!(
+            self.name.as_str(),
+            "application" | "activity" | "activity-alias" | "service" | "receiver" | "provider"
+        );
+        if component {
+            for (name, value) in &mut self.attributes {
+                if name == "android:name" && value.starts_with('.') {
+                    value.insert_str(0, package);
+                }
+            }
+        }
+        for child in &mut self.children {
+            child.expand_class_names(package);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Typed model of `AndroidManifest.xml` that is written to and parsed from XML, and
+//! [`ManifestMerger`] of app, overlay and library manifests.
+//!
+//! ```
+//! use android_tools::manifest::{Activity, AndroidManifest, IntentFilter, MetaData, UsesSdk};
+//!
+//! let mut manifest = AndroidManifest {
+//!     package: "com.example.app".to_owned(),
+//!     version_code: Some(1),
+//!     version_name: Some("0.1.0".to_owned()),
+//!     uses_sdk: Some(UsesSdk {
+//!         min_sdk_version: Some(21),
+//!         target_sdk_version: Some(33),
+//!         ..Default::default()
+//!     }),
+//!     ..Default::default()
+//! };
+//! manifest.application.has_code = Some(false);
+//! manifest.application.activities.push(Activity {
+//!     exported: Some(true),
+//!     intent_filters: vec![IntentFilter::launcher()],
+//!     meta_data: vec![MetaData::new("android.app.lib_name", "example")],
+//!     ..Activity::new("android.app.NativeActivity")
+//! });
+//! let xml = manifest.to_xml().unwrap();
+//! assert_eq!(AndroidManifest::from_xml(&xml).unwrap(), manifest);
+//! ```
+
+mod android_manifest;
+mod application;
+mod merger;
+
+pub use android_manifest::*;
+pub use application::*;
+pub use merger::*;
@@ -0,0 +1,61 @@
+/// Configuration of the resource value, `aapt.pb.Configuration`. Enum fields keep the
+/// raw values of `Configuration.proto` enums, where `0` is unset
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct Configuration {
+    #[prost(uint32, tag = "1")]
+    pub mcc: u32,
+    #[prost(uint32, tag = "2")]
+    pub mnc: u32,
+    /// BCP-47 locale tag, e.g. `fr-FR`
+    #[prost(string, tag = "3")]
+    pub locale: String,
+    /// `1` is LTR, `2` is RTL
+    #[prost(int32, tag = "4")]
+    pub layout_direction: i32,
+    #[prost(uint32, tag = "5")]
+    pub screen_width: u32,
+    #[prost(uint32, tag = "6")]
+    pub screen_height: u32,
+    #[prost(uint32, tag = "7")]
+    pub screen_width_dp: u32,
+    #[prost(uint32, tag = "8")]
+    pub screen_height_dp: u32,
+    #[prost(uint32, tag = "9")]
+    pub smallest_screen_width_dp: u32,
+    /// `1` small, `2` normal, `3` large, `4` xlarge
+    #[prost(int32, tag = "10")]
+    pub screen_layout_size: i32,
+    #[prost(int32, tag = "11")]
+    pub screen_layout_long: i32,
+    #[prost(int32, tag = "12")]
+    pub screen_round: i32,
+    #[prost(int32, tag = "13")]
+    pub wide_color_gamut: i32,
+    #[prost(int32, tag = "14")]
+    pub hdr: i32,
+    /// `1` port, `2` land, `3` square
+    #[prost(int32, tag = "15")]
+    pub orientation: i32,
+    #[prost(int32, tag = "16")]
+    pub ui_mode_type: i32,
+    /// `1` night, `2` notnight
+    #[prost(int32, tag = "17")]
+    pub ui_mode_night: i32,
+    /// Density in dpi
+    #[prost(uint32, tag = "18")]
+    pub density: u32,
+    #[prost(int32, tag = "19")]
+    pub touchscreen: i32,
+    #[prost(int32, tag = "20")]
+    pub keys_hidden: i32,
+    #[prost(int32, tag = "21")]
+    pub keyboard: i32,
+    #[prost(int32, tag = "22")]
+    pub nav_hidden: i32,
+    #[prost(int32, tag = "23")]
+    pub navigation: i32,
+    #[prost(uint32, tag = "24")]
+    pub sdk_version: u32,
+    #[prost(string, tag = "25")]
+    pub product: String,
+}
@@ -0,0 +1,51 @@
+//! Protobuf models of aapt2 resources: `Resources.proto` and `Configuration.proto`.
+//!
+//! Reads and writes `resources.pb` and proto `AndroidManifest.xml` produced by
+//! [`Aapt2Link::proto_format`](crate::aapt2::Aapt2Link::proto_format) and
+//! [`Aapt2Convert`](crate::aapt2::Aapt2Convert), e.g. to modify bundle module before
+//! [`BuildBundle::run`](crate::bundletool::BuildBundle::run). Fields added in newer
+//! aapt2 versions, like macros and feature flags, are not modeled and are dropped when
+//! the message is written back.
+//!
+//! ```no_run
+//! use android_tools::axml::ATTR_VERSION_CODE;
+//! use android_tools::proto::{
+//!     primitive::OneofValue, read_message, write_message, Item, XmlAttribute, XmlNode,
+//! };
+//! use std::path::Path;
+//!
+//! let path = Path::new("base/manifest/AndroidManifest.xml");
+//! let mut manifest: XmlNode = read_message(path).unwrap();
+//! manifest.element_mut().unwrap().set_attribute(XmlAttribute::android(
+//!     "versionCode",
+//!     ATTR_VERSION_CODE,
+//!     "2",
+//!     Item::primitive(OneofValue::IntDecimalValue(2)),
+//! ));
+//! write_message(path, &manifest).unwrap();
+//! ```
+
+mod configuration;
+mod resources;
+mod xml;
+
+pub use configuration::*;
+pub use prost::Message;
+pub use resources::*;
+pub use xml::*;
+
+use crate::error::*;
+use std::path::Path;
+
+/// Decodes the message, e.g. [`ResourceTable`] from `resources.pb`
+pub fn read_message<M: Message + Default>(path: &Path) -> Result<M> {
+    M::decode(std::fs::read(path)?.as_slice()).map_err(|err| {
+        Error::InvalidBinaryResource(format!("failed to decode {:?}: {}", path, err))
+    })
+}
+
+/// Encodes the message into the file
+pub fn write_message<M: Message>(path: &Path, message: &M) -> Result<()> {
+    std::fs::write(path, message.encode_to_vec())?;
+    Ok(())
+}
@@ -0,0 +1,600 @@
+use super::Configuration;
+
+/// Serialized `ResStringPool`, `aapt.pb.StringPool`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StringPool {
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: Vec<u8>,
+}
+
+/// Position in the source file
+#[derive(Clone, Copy, PartialEq, Eq, Hash, prost::Message)]
+pub struct SourcePosition {
+    #[prost(uint32, tag = "1")]
+    pub line_number: u32,
+    #[prost(uint32, tag = "2")]
+    pub column_number: u32,
+}
+
+/// Source file of the value. The path is an index in the table's source pool
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Source {
+    #[prost(uint32, tag = "1")]
+    pub path_idx: u32,
+    #[prost(message, optional, tag = "2")]
+    pub position: Option<SourcePosition>,
+}
+
+/// Tool that built the table, e.g. `Android Asset Packaging Tool (aapt)`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ToolFingerprint {
+    #[prost(string, tag = "1")]
+    pub tool: String,
+    #[prost(string, tag = "2")]
+    pub version: String,
+}
+
+/// Package ID assigned to a shared library at runtime
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct DynamicRefTable {
+    #[prost(message, optional, tag = "1")]
+    pub package_id: Option<PackageId>,
+    #[prost(string, tag = "2")]
+    pub package_name: String,
+}
+
+/// Resource table of the module, `resources.pb`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResourceTable {
+    /// Pool of source file paths
+    #[prost(message, optional, tag = "1")]
+    pub source_pool: Option<StringPool>,
+    #[prost(message, repeated, tag = "2")]
+    pub package: Vec<Package>,
+    #[prost(message, repeated, tag = "3")]
+    pub overlayable: Vec<Overlayable>,
+    #[prost(message, repeated, tag = "4")]
+    pub tool_fingerprint: Vec<ToolFingerprint>,
+    #[prost(message, repeated, tag = "5")]
+    pub dynamic_ref_table: Vec<DynamicRefTable>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, prost::Message)]
+pub struct PackageId {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Package {
+    #[prost(message, optional, tag = "1")]
+    pub package_id: Option<PackageId>,
+    #[prost(string, tag = "2")]
+    pub package_name: String,
+    #[prost(message, repeated, tag = "3")]
+    pub r#type: Vec<Type>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, prost::Message)]
+pub struct TypeId {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+
+/// Resource type with its entries, e.g. `string`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Type {
+    #[prost(message, optional, tag = "1")]
+    pub type_id: Option<TypeId>,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(message, repeated, tag = "3")]
+    pub entry: Vec<Entry>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Visibility {
+    #[prost(enumeration = "visibility::Level", tag = "1")]
+    pub level: i32,
+    #[prost(message, optional, tag = "2")]
+    pub source: Option<Source>,
+    #[prost(string, tag = "3")]
+    pub comment: String,
+    #[prost(bool, tag = "4")]
+    pub staged_api: bool,
+}
+
+pub mod visibility {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Level {
+        Unknown = 0,
+        Private = 1,
+        Public = 2,
+    }
+}
+
+/// Allows overlays to define new resources of the type
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AllowNew {
+    #[prost(message, optional, tag = "1")]
+    pub source: Option<Source>,
+    #[prost(string, tag = "2")]
+    pub comment: String,
+}
+
+/// Set of resources that runtime resource overlays may overlay
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Overlayable {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(message, optional, tag = "2")]
+    pub source: Option<Source>,
+    #[prost(string, tag = "3")]
+    pub actor: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OverlayableItem {
+    #[prost(message, optional, tag = "1")]
+    pub source: Option<Source>,
+    #[prost(string, tag = "2")]
+    pub comment: String,
+    /// Values of `OverlayableItem.Policy`
+    #[prost(int32, repeated, tag = "3")]
+    pub policy: Vec<i32>,
+    /// Index of the [`Overlayable`] in the table
+    #[prost(uint32, tag = "4")]
+    pub overlayable_idx: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StagedId {
+    #[prost(message, optional, tag = "1")]
+    pub source: Option<Source>,
+    #[prost(uint32, tag = "2")]
+    pub staged_id: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, prost::Message)]
+pub struct EntryId {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+
+/// Resource with its values for every configuration, e.g. `app_name`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Entry {
+    #[prost(message, optional, tag = "1")]
+    pub entry_id: Option<EntryId>,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(message, optional, tag = "3")]
+    pub visibility: Option<Visibility>,
+    #[prost(message, optional, tag = "4")]
+    pub allow_new: Option<AllowNew>,
+    #[prost(message, optional, tag = "5")]
+    pub overlayable_item: Option<OverlayableItem>,
+    #[prost(message, repeated, tag = "6")]
+    pub config_value: Vec<ConfigValue>,
+    #[prost(message, optional, tag = "7")]
+    pub staged_id: Option<StagedId>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ConfigValue {
+    #[prost(message, optional, tag = "1")]
+    pub config: Option<Configuration>,
+    #[prost(message, optional, tag = "2")]
+    pub value: Option<Value>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Value {
+    #[prost(message, optional, tag = "1")]
+    pub source: Option<Source>,
+    #[prost(string, tag = "2")]
+    pub comment: String,
+    #[prost(bool, tag = "3")]
+    pub weak: bool,
+    #[prost(oneof = "value::Value", tags = "4, 5")]
+    pub value: Option<value::Value>,
+}
+
+pub mod value {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Value {
+        #[prost(message, tag = "4")]
+        Item(super::Item),
+        #[prost(message, tag = "5")]
+        CompoundValue(super::CompoundValue),
+    }
+}
+
+/// Single value, like string, reference or primitive
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Item {
+    #[prost(oneof = "item::Value", tags = "1, 2, 3, 4, 5, 6, 7")]
+    pub value: Option<item::Value>,
+}
+
+pub mod item {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Value {
+        #[prost(message, tag = "1")]
+        Ref(super::Reference),
+        #[prost(message, tag = "2")]
+        Str(super::StringValue),
+        #[prost(message, tag = "3")]
+        RawStr(super::RawString),
+        #[prost(message, tag = "4")]
+        StyledStr(super::StyledString),
+        #[prost(message, tag = "5")]
+        File(super::FileReference),
+        #[prost(message, tag = "6")]
+        Id(super::Id),
+        #[prost(message, tag = "7")]
+        Prim(super::Primitive),
+    }
+}
+
+/// Value made of other values, like style or array
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CompoundValue {
+    #[prost(oneof = "compound_value::Value", tags = "1, 2, 3, 4, 5")]
+    pub value: Option<compound_value::Value>,
+}
+
+pub mod compound_value {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Value {
+        #[prost(message, tag = "1")]
+        Attr(super::Attribute),
+        #[prost(message, tag = "2")]
+        Style(super::Style),
+        #[prost(message, tag = "3")]
+        Styleable(super::Styleable),
+        #[prost(message, tag = "4")]
+        Array(super::Array),
+        #[prost(message, tag = "5")]
+        Plural(super::Plural),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, prost::Message)]
+pub struct Boolean {
+    #[prost(bool, tag = "1")]
+    pub value: bool,
+}
+
+/// Reference to resource or theme attribute
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Reference {
+    #[prost(enumeration = "reference::Type", tag = "1")]
+    pub r#type: i32,
+    /// Resource ID, `0` if unresolved
+    #[prost(uint32, tag = "2")]
+    pub id: u32,
+    /// Name of the resource, e.g. `string/app_name`
+    #[prost(string, tag = "3")]
+    pub name: String,
+    #[prost(bool, tag = "4")]
+    pub private: bool,
+    #[prost(message, optional, tag = "5")]
+    pub is_dynamic: Option<Boolean>,
+    #[prost(uint32, tag = "6")]
+    pub type_flags: u32,
+    #[prost(bool, tag = "7")]
+    pub allow_raw: bool,
+}
+
+pub mod reference {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Type {
+        Reference = 0,
+        Attribute = 1,
+    }
+}
+
+/// Placeholder value of `id` resources
+#[derive(Clone, Copy, PartialEq, Eq, Hash, prost::Message)]
+pub struct Id {}
+
+/// String value, `aapt.pb.String`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StringValue {
+    #[prost(string, tag = "1")]
+    pub value: String,
+}
+
+/// String value as it's written in the source
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RawString {
+    #[prost(string, tag = "1")]
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StyledString {
+    #[prost(string, tag = "1")]
+    pub value: String,
+    #[prost(message, repeated, tag = "2")]
+    pub span: Vec<styled_string::Span>,
+}
+
+pub mod styled_string {
+    /// Styled range of the string, e.g. `<b>`
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Span {
+        #[prost(string, tag = "1")]
+        pub tag: String,
+        #[prost(uint32, tag = "2")]
+        pub first_char: u32,
+        #[prost(uint32, tag = "3")]
+        pub last_char: u32,
+    }
+}
+
+/// Reference to file of the module, e.g. `res/drawable/icon.png`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FileReference {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(enumeration = "file_reference::Type", tag = "2")]
+    pub r#type: i32,
+}
+
+pub mod file_reference {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Type {
+        Unknown = 0,
+        Png = 1,
+        BinaryXml = 2,
+        ProtoXml = 3,
+    }
+}
+
+/// Primitive value, like number, boolean or color
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Primitive {
+    #[prost(
+        oneof = "primitive::OneofValue",
+        tags = "1, 2, 3, 13, 14, 6, 7, 8, 9, 10, 11, 12, 4, 5"
+    )]
+    pub oneof_value: Option<primitive::OneofValue>,
+}
+
+pub mod primitive {
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, prost::Message)]
+    pub struct NullType {}
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, prost::Message)]
+    pub struct EmptyType {}
+
+    #[derive(Clone, Copy, PartialEq, prost::Oneof)]
+    pub enum OneofValue {
+        #[prost(message, tag = "1")]
+        NullValue(NullType),
+        #[prost(message, tag = "2")]
+        EmptyValue(EmptyType),
+        #[prost(float, tag = "3")]
+        FloatValue(f32),
+        /// Dimension in the complex format of `Res_value`
+        #[prost(uint32, tag = "13")]
+        DimensionValue(u32),
+        /// Fraction in the complex format of `Res_value`
+        #[prost(uint32, tag = "14")]
+        FractionValue(u32),
+        #[prost(int32, tag = "6")]
+        IntDecimalValue(i32),
+        #[prost(uint32, tag = "7")]
+        IntHexadecimalValue(u32),
+        #[prost(bool, tag = "8")]
+        BooleanValue(bool),
+        #[prost(uint32, tag = "9")]
+        ColorArgb8Value(u32),
+        #[prost(uint32, tag = "10")]
+        ColorRgb8Value(u32),
+        #[prost(uint32, tag = "11")]
+        ColorArgb4Value(u32),
+        #[prost(uint32, tag = "12")]
+        ColorRgb4Value(u32),
+        #[prost(float, tag = "4")]
+        DimensionValueDeprecated(f32),
+        #[prost(float, tag = "5")]
+        FractionValueDeprecated(f32),
+    }
+}
+
+/// Definition of attribute, `<attr>`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Attribute {
+    /// Bitmask of allowed formats, e.g. `0x02` for string
+    #[prost(uint32, tag = "1")]
+    pub format_flags: u32,
+    #[prost(int32, tag = "2")]
+    pub min_int: i32,
+    #[prost(int32, tag = "3")]
+    pub max_int: i32,
+    #[prost(message, repeated, tag = "4")]
+    pub symbol: Vec<attribute::Symbol>,
+}
+
+pub mod attribute {
+    /// Enum or flag value of the attribute
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Symbol {
+        #[prost(message, optional, tag = "1")]
+        pub source: Option<super::Source>,
+        #[prost(string, tag = "2")]
+        pub comment: String,
+        #[prost(message, optional, tag = "3")]
+        pub name: Option<super::Reference>,
+        #[prost(uint32, tag = "4")]
+        pub value: u32,
+        #[prost(uint32, tag = "5")]
+        pub r#type: u32,
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Style {
+    #[prost(message, optional, tag = "1")]
+    pub parent: Option<Reference>,
+    #[prost(message, optional, tag = "2")]
+    pub parent_source: Option<Source>,
+    #[prost(message, repeated, tag = "3")]
+    pub entry: Vec<style::Entry>,
+}
+
+pub mod style {
+    /// Attribute of the style with its value
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Entry {
+        #[prost(message, optional, tag = "1")]
+        pub source: Option<super::Source>,
+        #[prost(string, tag = "2")]
+        pub comment: String,
+        #[prost(message, optional, tag = "3")]
+        pub key: Option<super::Reference>,
+        #[prost(message, optional, tag = "4")]
+        pub item: Option<super::Item>,
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Styleable {
+    #[prost(message, repeated, tag = "1")]
+    pub entry: Vec<styleable::Entry>,
+}
+
+pub mod styleable {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Entry {
+        #[prost(message, optional, tag = "1")]
+        pub source: Option<super::Source>,
+        #[prost(string, tag = "2")]
+        pub comment: String,
+        #[prost(message, optional, tag = "3")]
+        pub attr: Option<super::Reference>,
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Array {
+    #[prost(message, repeated, tag = "1")]
+    pub element: Vec<array::Element>,
+}
+
+pub mod array {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Element {
+        #[prost(message, optional, tag = "1")]
+        pub source: Option<super::Source>,
+        #[prost(string, tag = "2")]
+        pub comment: String,
+        #[prost(message, optional, tag = "3")]
+        pub item: Option<super::Item>,
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Plural {
+    #[prost(message, repeated, tag = "1")]
+    pub entry: Vec<plural::Entry>,
+}
+
+pub mod plural {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Entry {
+        #[prost(message, optional, tag = "1")]
+        pub source: Option<super::Source>,
+        #[prost(string, tag = "2")]
+        pub comment: String,
+        #[prost(enumeration = "Arity", tag = "3")]
+        pub arity: i32,
+        #[prost(message, optional, tag = "4")]
+        pub item: Option<super::Item>,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum Arity {
+        Zero = 0,
+        One = 1,
+        Two = 2,
+        Few = 3,
+        Many = 4,
+        Other = 5,
+    }
+}
+
+impl ResourceTable {
+    /// Returns the package by name, e.g. `com.example`
+    pub fn package(&self, name: &str) -> Option<&Package> {
+        self.package
+            .iter()
+            .find(|package| package.package_name == name)
+    }
+
+    /// Returns the mutable package by name
+    pub fn package_mut(&mut self, name: &str) -> Option<&mut Package> {
+        self.package
+            .iter_mut()
+            .find(|package| package.package_name == name)
+    }
+}
+
+impl Package {
+    /// Returns the entry by type and name, e.g. `("string", "app_name")`
+    pub fn entry(&self, type_name: &str, name: &str) -> Option<&Entry> {
+        self.r#type
+            .iter()
+            .find(|resource_type| resource_type.name == type_name)?
+            .entry
+            .iter()
+            .find(|entry| entry.name == name)
+    }
+
+    /// Returns the mutable entry by type and name
+    pub fn entry_mut(&mut self, type_name: &str, name: &str) -> Option<&mut Entry> {
+        self.r#type
+            .iter_mut()
+            .find(|resource_type| resource_type.name == type_name)?
+            .entry
+            .iter_mut()
+            .find(|entry| entry.name == name)
+    }
+}
+
+impl Item {
+    /// Creates string item
+    pub fn string(value: &str) -> Self {
+        Self {
+            value: Some(item::Value::Str(StringValue {
+                value: value.to_owned(),
+            })),
+        }
+    }
+
+    /// Creates primitive item, e.g. `Item::primitive(OneofValue::IntDecimalValue(1))`
+    pub fn primitive(value: primitive::OneofValue) -> Self {
+        Self {
+            value: Some(item::Value::Prim(Primitive {
+                oneof_value: Some(value),
+            })),
+        }
+    }
+
+    /// Creates reference to resource by ID
+    pub fn reference(id: u32) -> Self {
+        Self {
+            value: Some(item::Value::Ref(Reference {
+                id,
+                ..Default::default()
+            })),
+        }
+    }
+}
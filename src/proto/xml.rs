@@ -0,0 +1,143 @@
+use super::{Item, SourcePosition};
+use crate::axml::ANDROID_NS;
+
+/// Node of proto XML, e.g. `manifest/AndroidManifest.xml` of the bundle module
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct XmlNode {
+    #[prost(oneof = "xml_node::Node", tags = "1, 2")]
+    pub node: Option<xml_node::Node>,
+    #[prost(message, optional, tag = "3")]
+    pub source: Option<SourcePosition>,
+}
+
+pub mod xml_node {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Node {
+        #[prost(message, tag = "1")]
+        Element(super::XmlElement),
+        #[prost(string, tag = "2")]
+        Text(String),
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct XmlElement {
+    /// Namespaces declared on the element
+    #[prost(message, repeated, tag = "1")]
+    pub namespace_declaration: Vec<XmlNamespace>,
+    #[prost(string, tag = "2")]
+    pub namespace_uri: String,
+    #[prost(string, tag = "3")]
+    pub name: String,
+    #[prost(message, repeated, tag = "4")]
+    pub attribute: Vec<XmlAttribute>,
+    #[prost(message, repeated, tag = "5")]
+    pub child: Vec<XmlNode>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct XmlNamespace {
+    #[prost(string, tag = "1")]
+    pub prefix: String,
+    #[prost(string, tag = "2")]
+    pub uri: String,
+    #[prost(message, optional, tag = "3")]
+    pub source: Option<SourcePosition>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct XmlAttribute {
+    #[prost(string, tag = "1")]
+    pub namespace_uri: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    /// Value as written in the source
+    #[prost(string, tag = "3")]
+    pub value: String,
+    #[prost(message, optional, tag = "4")]
+    pub source: Option<SourcePosition>,
+    /// Resource ID of the attribute, e.g. `0x0101021b` for `android:versionCode`
+    #[prost(uint32, tag = "5")]
+    pub resource_id: u32,
+    /// Compiled value the platform uses, if the attribute has a resource ID
+    #[prost(message, optional, tag = "6")]
+    pub compiled_item: Option<Item>,
+}
+
+impl XmlNode {
+    /// Returns the root element of the document
+    pub fn element(&self) -> Option<&XmlElement> {
+        match &self.node {
+            Some(xml_node::Node::Element(element)) => Some(element),
+            _ => None,
+        }
+    }
+
+    /// Returns the mutable root element of the document
+    pub fn element_mut(&mut self) -> Option<&mut XmlElement> {
+        match &mut self.node {
+            Some(xml_node::Node::Element(element)) => Some(element),
+            _ => None,
+        }
+    }
+}
+
+impl XmlElement {
+    /// Returns the attribute by name in any namespace
+    pub fn attribute(&self, name: &str) -> Option<&XmlAttribute> {
+        self.attribute
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+
+    /// Returns the mutable attribute by name in any namespace
+    pub fn attribute_mut(&mut self, name: &str) -> Option<&mut XmlAttribute> {
+        self.attribute
+            .iter_mut()
+            .find(|attribute| attribute.name == name)
+    }
+
+    /// Adds the attribute or replaces the attribute with the same namespace and name
+    pub fn set_attribute(&mut self, attribute: XmlAttribute) -> &mut Self {
+        match self.attribute.iter_mut().find(|existing| {
+            existing.namespace_uri == attribute.namespace_uri && existing.name == attribute.name
+        }) {
+            Some(existing) => *existing = attribute,
+            None => self.attribute.push(attribute),
+        }
+        self
+    }
+
+    /// Returns child elements
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.child.iter().filter_map(XmlNode::element)
+    }
+
+    /// Returns the first child element with given name
+    pub fn child_element(&self, name: &str) -> Option<&XmlElement> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// Returns the first mutable child element with given name
+    pub fn child_element_mut(&mut self, name: &str) -> Option<&mut XmlElement> {
+        self.child
+            .iter_mut()
+            .filter_map(XmlNode::element_mut)
+            .find(|element| element.name == name)
+    }
+}
+
+impl XmlAttribute {
+    /// Creates attribute in the `android` namespace with the source value and the
+    /// compiled item, e.g. `"1"` and `Item::primitive(OneofValue::IntDecimalValue(1))`
+    pub fn android(name: &str, resource_id: u32, value: &str, compiled_item: Item) -> Self {
+        Self {
+            namespace_uri: ANDROID_NS.to_owned(),
+            name: name.to_owned(),
+            value: value.to_owned(),
+            resource_id,
+            compiled_item: Some(compiled_item),
+            ..Default::default()
+        }
+    }
+}
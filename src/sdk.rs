@@ -0,0 +1,392 @@
+//! Contains `AndroidSdk` type that locates Android SDK and its installed packages.
+
+use crate::{error::*, sdk_install_path};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Environment variable that pins the build-tools version used by aapt2, e.g. `30.0.3`
+/// or `>=30.0.0`
+pub const BUILD_TOOLS_VERSION_ENV: &str = "ANDROID_BUILD_TOOLS_VERSION";
+
+/// Android SDK installation.
+///
+/// ```no_run
+/// use android_tools::sdk::{AndroidSdk, VersionReq};
+///
+/// let sdk = AndroidSdk::from_env().unwrap();
+/// let build_tools = sdk.build_tools_dir(&">=30.0.0".parse().unwrap()).unwrap();
+/// let platform = sdk.platform_dir(&VersionReq::Latest).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AndroidSdk {
+    path: PathBuf,
+}
+
+/// Version of an SDK package, e.g. `30.0.3`, `33.0.0-rc1` or `25.2.9519653`. Numbers of
+/// pre-releases are compared numerically, so `rc01` equals `rc1`
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// Pre-release suffix, e.g. `rc1`
+    pub pre: Option<String>,
+}
+
+/// Version of a package to select when several versions are installed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum VersionReq {
+    /// The highest installed version
+    #[default]
+    Latest,
+    /// Exactly given version
+    Exact(Version),
+    /// The highest installed version that is not lower than given version
+    AtLeast(Version),
+}
+
+/// Installed SDK platform, e.g. `platforms/android-30`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform {
+    /// Name of the platform directory without `android-` prefix, e.g. `30` or
+    /// `TiramisuPrivacySandbox`
+    pub name: String,
+    /// API level if the platform is named by it
+    pub api_level: Option<u32>,
+    pub path: PathBuf,
+}
+
+impl AndroidSdk {
+    /// Uses SDK at given path
+    pub fn from_path(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Err(Error::AndroidSdkNotFound);
+        }
+        Ok(Self {
+            path: path.to_owned(),
+        })
+    }
+
+    /// Finds SDK from `ANDROID_SDK_ROOT`, `ANDROID_SDK_PATH` or `ANDROID_HOME` environment
+    /// variables, then from `sdk.dir` of `local.properties` in the current directory,
+    /// then at the default installation path
+    pub fn from_env() -> Result<Self> {
+        let env_path = ["ANDROID_SDK_ROOT", "ANDROID_SDK_PATH", "ANDROID_HOME"]
+            .iter()
+            .find_map(std::env::var_os);
+        if let Some(path) = env_path {
+            return Self::from_path(Path::new(&path));
+        }
+        if let Some(path) = std::env::current_dir()
+            .ok()
+            .and_then(|dir| local_properties_sdk_dir(&dir))
+        {
+            return Self::from_path(&path);
+        }
+        Self::from_path(&sdk_install_path()?)
+    }
+
+    /// Finds SDK from `sdk.dir` of `local.properties` in given project directory
+    pub fn from_local_properties(project_dir: &Path) -> Result<Self> {
+        let path = local_properties_sdk_dir(project_dir).ok_or(Error::AndroidSdkNotFound)?;
+        Self::from_path(&path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns installed build-tools versions from the lowest to the highest
+    pub fn build_tools(&self) -> Vec<Version> {
+        let mut versions = versions_in(&self.path.join("build-tools"))
+            .into_iter()
+            .map(|(version, _)| version)
+            .collect::<Vec<_>>();
+        versions.sort();
+        versions
+    }
+
+    /// Returns build-tools directory of the selected version
+    pub fn build_tools_dir(&self, req: &VersionReq) -> Result<PathBuf> {
+        select(versions_in(&self.path.join("build-tools")), req)
+    }
+
+    /// Returns installed platforms ordered by API level. Platforms without API level,
+    /// like previews, go last
+    pub fn platforms(&self) -> Vec<Platform> {
+        let mut platforms = dirs_in(&self.path.join("platforms"))
+            .into_iter()
+            .filter_map(|(name, path)| {
+                let name = name.strip_prefix("android-")?.to_owned();
+                Some(Platform {
+                    api_level: name.parse().ok(),
+                    name,
+                    path,
+                })
+            })
+            .collect::<Vec<_>>();
+        platforms.sort_by_key(|platform| (platform.api_level.is_none(), platform.api_level));
+        platforms
+    }
+
+    /// Returns directory of the selected platform, e.g. `platforms/android-30`. Only
+    /// platforms with API level are selected, the requested version is compared with the
+    /// API level
+    pub fn platform_dir(&self, req: &VersionReq) -> Result<PathBuf> {
+        let platforms = self
+            .platforms()
+            .into_iter()
+            .filter_map(|platform| {
+                let api_level = platform.api_level?;
+                Some((Version::new(api_level, 0, 0), platform.path))
+            })
+            .collect();
+        select(platforms, req)
+    }
+
+    /// Returns installed NDK versions from the lowest to the highest. The legacy
+    /// `ndk-bundle` is included with the version from its `source.properties`
+    pub fn ndks(&self) -> Vec<(Version, PathBuf)> {
+        let mut ndks = versions_in(&self.path.join("ndk"));
+        let ndk_bundle = self.path.join("ndk-bundle");
+        if let Some(version) = ndk_revision(&ndk_bundle) {
+            ndks.push((version, ndk_bundle));
+        }
+        ndks.sort();
+        ndks
+    }
+
+    /// Returns directory of the selected NDK
+    pub fn ndk_dir(&self, req: &VersionReq) -> Result<PathBuf> {
+        select(self.ndks(), req).map_err(|_| Error::AndroidToolNotFound)
+    }
+
+    /// Returns `emulator` directory if the emulator is installed
+    pub fn emulator_dir(&self) -> Option<PathBuf> {
+        Some(self.path.join("emulator")).filter(|dir| dir.exists())
+    }
+
+    /// Returns `platform-tools` directory with adb if it's installed
+    pub fn platform_tools_dir(&self) -> Option<PathBuf> {
+        Some(self.path.join("platform-tools")).filter(|dir| dir.exists())
+    }
+}
+
+impl Version {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: None,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    /// Parses `30`, `30.0`, `30.0.3` or `33.0.0-rc1`. `33.0.0 rc1` is accepted too, as
+    /// it's written in `source.properties`
+    fn from_str(version: &str) -> Result<Self> {
+        let invalid = || Error::InvalidArguments(format!("invalid version {:?}", version));
+        let version = version.trim();
+        let (numbers, pre) = match version.split_once(['-', ' ']) {
+            Some((numbers, pre)) => (numbers, Some(pre.trim().to_owned())),
+            None => (version, None),
+        };
+        let mut numbers = numbers.split('.').map(|number| number.parse::<u32>());
+        let major = numbers.next().ok_or_else(invalid)?.map_err(|_| invalid())?;
+        let mut next = || numbers.next().transpose().map_err(|_| invalid());
+        let minor = next()?.unwrap_or(0);
+        let patch = next()?.unwrap_or(0);
+        if next()?.is_some() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre,
+        })
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Version {
+    /// Compares numbers, then pre-release versions are lower than the release. Numbers
+    /// of pre-releases are compared numerically
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => std::cmp::Ordering::Equal,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (Some(pre), Some(other)) => pre_release_key(pre).cmp(&pre_release_key(other)),
+            })
+    }
+}
+
+/// Splits pre-release suffix into the name and the trailing number, so `rc2` is lower
+/// than `rc10`
+fn pre_release_key(pre: &str) -> (&str, Option<u64>) {
+    let name = pre.trim_end_matches(|c: char| c.is_ascii_digit());
+    (name, pre[name.len()..].parse().ok())
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl std::hash::Hash for Version {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (self.major, self.minor, self.patch).hash(state);
+        self.pre.as_deref().map(pre_release_key).hash(state);
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl VersionReq {
+    pub fn matches(&self, version: &Version) -> bool {
+        match self {
+            Self::Latest => true,
+            Self::Exact(exact) => version == exact,
+            Self::AtLeast(min) => version >= min,
+        }
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = Error;
+
+    /// Parses `latest`, `30.0.3` or `>=30.0.0`
+    fn from_str(req: &str) -> Result<Self> {
+        let req = req.trim();
+        if req.is_empty() || req == "latest" {
+            Ok(Self::Latest)
+        } else if let Some(min) = req.strip_prefix(">=") {
+            Ok(Self::AtLeast(min.parse()?))
+        } else {
+            Ok(Self::Exact(req.parse()?))
+        }
+    }
+}
+
+impl std::fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Latest => write!(f, "latest"),
+            Self::Exact(version) => write!(f, "{}", version),
+            Self::AtLeast(version) => write!(f, ">={}", version),
+        }
+    }
+}
+
+/// Returns the build-tools version requirement from `ANDROID_BUILD_TOOLS_VERSION`
+pub fn build_tools_version_from_env() -> Result<Option<VersionReq>> {
+    std::env::var(BUILD_TOOLS_VERSION_ENV)
+        .ok()
+        .map(|req| req.parse())
+        .transpose()
+}
+
+/// Finds executable of build-tools, e.g. `aapt2` or `zipalign`. With `which` feature
+/// the executable from `PATH` is used unless `ANDROID_BUILD_TOOLS_VERSION` selects
+/// build-tools version. Otherwise the executable is taken from the latest installed
+/// build-tools
+pub fn build_tools_executable(file_name: &str) -> Result<PathBuf> {
+    let version = build_tools_version_from_env()?;
+    #[cfg(feature = "which")]
+    if version.is_none() {
+        if let Ok(executable) = which::which(file_name) {
+            return Ok(executable);
+        }
+    }
+    let build_tools = AndroidSdk::from_env()?.build_tools_dir(&version.unwrap_or_default())?;
+    Ok(build_tools.join(file_name))
+}
+
+/// Returns the highest version matching the requirement
+fn select(versions: Vec<(Version, PathBuf)>, req: &VersionReq) -> Result<PathBuf> {
+    versions
+        .into_iter()
+        .filter(|(version, _)| req.matches(version))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, path)| path)
+        .ok_or(Error::AndroidToolNotFound)
+}
+
+fn dirs_in(dir: &Path) -> Vec<(String, PathBuf)> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| Some((entry.file_name().into_string().ok()?, entry.path())))
+        .collect()
+}
+
+/// Returns subdirectories named by versions
+fn versions_in(dir: &Path) -> Vec<(Version, PathBuf)> {
+    dirs_in(dir)
+        .into_iter()
+        .filter_map(|(name, path)| Some((name.parse().ok()?, path)))
+        .collect()
+}
+
+/// Reads `Pkg.Revision` from `source.properties` of the package
+fn ndk_revision(ndk_dir: &Path) -> Option<Version> {
+    let properties = std::fs::read_to_string(ndk_dir.join("source.properties")).ok()?;
+    property(&properties, "Pkg.Revision")?.parse().ok()
+}
+
+/// Reads `sdk.dir` from `local.properties` of the project
+fn local_properties_sdk_dir(project_dir: &Path) -> Option<PathBuf> {
+    let properties = std::fs::read_to_string(project_dir.join("local.properties")).ok()?;
+    property(&properties, "sdk.dir").map(PathBuf::from)
+}
+
+/// Returns value of the key in Java properties file. Escaped characters, like `\:` in
+/// Windows paths, are unescaped
+fn property(properties: &str, key: &str) -> Option<String> {
+    properties.lines().find_map(|line| {
+        let line = line.trim_start();
+        if line.starts_with('#') || line.starts_with('!') {
+            return None;
+        }
+        let (name, value) = line.split_once(['=', ':'])?;
+        if name.trim() != key {
+            return None;
+        }
+        let mut unescaped = String::new();
+        let mut chars = value.trim().chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => unescaped.extend(chars.next()),
+                c => unescaped.push(c),
+            }
+        }
+        Some(unescaped)
+    })
+}
@@ -0,0 +1,335 @@
+//! Contains `ZipAlign` type that aligns APK entries without running `zipalign`.
+
+use crate::error::*;
+use std::{ops::Range, path::Path};
+
+/// Alignment of uncompressed entries zipalign uses for APKs
+pub const DEFAULT_ALIGNMENT: u32 = 4;
+/// Page size of most devices
+pub const PAGE_SIZE_4K: u32 = 4096;
+/// Page size required to load native libraries on devices with 16 KB pages
+pub const PAGE_SIZE_16K: u32 = 16384;
+
+/// ID of the extra field Android tools use to pad local file headers
+const ALIGNMENT_EXTRA_ID: u16 = 0xd935;
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x0807_4b50;
+const LOCAL_HEADER_SIZE: usize = 30;
+const CENTRAL_HEADER_SIZE: usize = 46;
+const EOCD_SIZE: usize = 22;
+const STORED: u16 = 0;
+
+/// # ZipAlign
+/// Rewrites ZIP archive, so data of every uncompressed (STORED) entry starts at the
+/// aligned offset, like `zipalign -P 16 4` does. Uncompressed native libraries,
+/// `lib/**/*.so`, are aligned to the page size, so they can be mapped directly from the
+/// APK.
+///
+/// Local file headers are padded with the `0xd935` extra field, the same way
+/// `zipalign` and `apksigner` do. The APK Signing Block is dropped, as aligning breaks
+/// v2+ signatures, so align APK before signing it.
+///
+/// ```no_run
+/// use android_tools::zip_align::ZipAlign;
+/// use std::path::Path;
+///
+/// // E.g. the APK linked by `Aapt2Link::run`
+/// let apk = Path::new("app-unaligned.apk");
+/// ZipAlign::new().align(apk, Path::new("app.apk")).unwrap();
+/// assert!(ZipAlign::new().check_alignment(Path::new("app.apk")).unwrap().is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ZipAlign {
+    alignment: u32,
+    page_size: Option<u32>,
+}
+
+/// Uncompressed entry whose data isn't aligned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MisalignedEntry {
+    pub name: String,
+    /// Offset of the entry data in the archive
+    pub data_offset: u64,
+    /// Required alignment of the entry data
+    pub alignment: u32,
+}
+
+impl Default for ZipAlign {
+    fn default() -> Self {
+        Self {
+            alignment: DEFAULT_ALIGNMENT,
+            page_size: Some(PAGE_SIZE_16K),
+        }
+    }
+}
+
+impl ZipAlign {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Alignment of uncompressed entries in bytes [default: 4]
+    pub fn alignment(&mut self, alignment: u32) -> &mut Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Alignment of uncompressed `.so` files in `lib` directory. `None` aligns them like
+    /// other entries [default: 16 KB]
+    pub fn page_size(&mut self, page_size: Option<u32>) -> &mut Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Aligns the input archive and writes it to the output path. Output path may be the
+    /// same as the input path
+    pub fn align(&self, input: &Path, output: &Path) -> Result<()> {
+        let aligned = self.align_bytes(&std::fs::read(input)?)?;
+        std::fs::write(output, aligned)?;
+        Ok(())
+    }
+
+    /// Returns aligned copy of the archive
+    pub fn align_bytes(&self, zip: &[u8]) -> Result<Vec<u8>> {
+        self.validate()?;
+        let archive = Archive::parse(zip)?;
+        let mut aligned = Vec::with_capacity(zip.len() + archive.entries.len() * 8);
+        let mut central_directory = Vec::with_capacity(archive.central_directory.len());
+        for entry in &archive.entries {
+            let local_offset = aligned.len();
+            let header = &zip[entry.local_header.clone()];
+            let (name, extra) = header[LOCAL_HEADER_SIZE..].split_at(entry.local_name_len);
+            let mut extra = strip_alignment_field(extra);
+            if entry.method == STORED {
+                let alignment = self.entry_alignment(&entry.name) as usize;
+                let data_offset = local_offset + LOCAL_HEADER_SIZE + name.len() + extra.len();
+                // The field consists of ID, size, alignment and zero padding
+                let padding = (alignment - (data_offset + 6) % alignment) % alignment;
+                extra.extend_from_slice(&ALIGNMENT_EXTRA_ID.to_le_bytes());
+                extra.extend_from_slice(&(2 + padding as u16).to_le_bytes());
+                extra.extend_from_slice(&(alignment as u16).to_le_bytes());
+                extra.resize(extra.len() + padding, 0);
+            }
+            let extra_len = u16::try_from(extra.len()).map_err(|_| {
+                Error::InvalidApk(format!("extra field of {} is too long", entry.name))
+            })?;
+            aligned.extend_from_slice(&header[..28]);
+            aligned.extend_from_slice(&extra_len.to_le_bytes());
+            aligned.extend_from_slice(name);
+            aligned.extend_from_slice(&extra);
+            aligned.extend_from_slice(&zip[entry.data.clone()]);
+
+            let mut central_header = zip[entry.central_header.clone()].to_vec();
+            let local_offset = u32::try_from(local_offset)
+                .map_err(|_| Error::InvalidApk("ZIP64 isn't supported".to_owned()))?;
+            central_header[42..46].copy_from_slice(&local_offset.to_le_bytes());
+            central_directory.extend_from_slice(&central_header);
+        }
+        let cd_offset = u32::try_from(aligned.len())
+            .map_err(|_| Error::InvalidApk("ZIP64 isn't supported".to_owned()))?;
+        aligned.extend_from_slice(&central_directory);
+        let mut eocd = zip[archive.eocd_offset..].to_vec();
+        eocd[12..16].copy_from_slice(&(central_directory.len() as u32).to_le_bytes());
+        eocd[16..20].copy_from_slice(&cd_offset.to_le_bytes());
+        aligned.extend_from_slice(&eocd);
+        Ok(aligned)
+    }
+
+    /// Returns uncompressed entries of the archive that aren't aligned
+    pub fn check_alignment(&self, zip: &Path) -> Result<Vec<MisalignedEntry>> {
+        self.check_alignment_bytes(&std::fs::read(zip)?)
+    }
+
+    /// Returns uncompressed entries of the archive contents that aren't aligned
+    pub fn check_alignment_bytes(&self, zip: &[u8]) -> Result<Vec<MisalignedEntry>> {
+        self.validate()?;
+        let archive = Archive::parse(zip)?;
+        Ok(archive
+            .entries
+            .into_iter()
+            .filter(|entry| entry.method == STORED)
+            .filter_map(|entry| {
+                let alignment = self.entry_alignment(&entry.name);
+                if entry.data.start % alignment as usize == 0 {
+                    return None;
+                }
+                Some(MisalignedEntry {
+                    name: entry.name,
+                    data_offset: entry.data.start as u64,
+                    alignment,
+                })
+            })
+            .collect())
+    }
+
+    fn entry_alignment(&self, name: &str) -> u32 {
+        match self.page_size {
+            Some(page_size) if name.starts_with("lib/") && name.ends_with(".so") => page_size,
+            _ => self.alignment,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let valid = |alignment: u32| alignment.is_power_of_two() && alignment <= u16::MAX as u32;
+        if !valid(self.alignment) || !self.page_size.is_none_or(valid) {
+            return Err(Error::InvalidArguments(
+                "alignment must be a power of two up to 32 KB".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Entries of ZIP archive in the central directory order
+struct Archive {
+    entries: Vec<Entry>,
+    central_directory: Range<usize>,
+    eocd_offset: usize,
+}
+
+struct Entry {
+    name: String,
+    /// Length of the name in the local file header
+    local_name_len: usize,
+    method: u16,
+    /// Local file header including the name and the extra field
+    local_header: Range<usize>,
+    /// Entry data including the data descriptor
+    data: Range<usize>,
+    central_header: Range<usize>,
+}
+
+impl Archive {
+    fn parse(zip: &[u8]) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidApk(reason.to_owned());
+        let eocd_offset =
+            find_eocd(zip).ok_or_else(|| invalid("End of Central Directory not found"))?;
+        let entries_count = read_u16(zip, eocd_offset + 10)? as usize;
+        let cd_size = read_u32(zip, eocd_offset + 12)? as usize;
+        let cd_offset = read_u32(zip, eocd_offset + 16)? as usize;
+        if cd_offset + cd_size != eocd_offset {
+            return Err(invalid(
+                "unexpected central directory offset, ZIP64 isn't supported",
+            ));
+        }
+        let mut entries = Vec::with_capacity(entries_count);
+        let mut offset = cd_offset;
+        for _ in 0..entries_count {
+            if read_u32(zip, offset)? != CENTRAL_HEADER_SIGNATURE {
+                return Err(invalid("malformed central directory"));
+            }
+            let flags = read_u16(zip, offset + 8)?;
+            let method = read_u16(zip, offset + 10)?;
+            let compressed_size = read_u32(zip, offset + 20)? as usize;
+            let name_len = read_u16(zip, offset + 28)? as usize;
+            let central_header_len = CENTRAL_HEADER_SIZE
+                + name_len
+                + read_u16(zip, offset + 30)? as usize
+                + read_u16(zip, offset + 32)? as usize;
+            let local_offset = read_u32(zip, offset + 42)? as usize;
+            let raw_name = zip
+                .get(offset + CENTRAL_HEADER_SIZE..offset + CENTRAL_HEADER_SIZE + name_len)
+                .ok_or_else(|| invalid("malformed central directory"))?;
+            let name = String::from_utf8_lossy(raw_name).into_owned();
+            if compressed_size == u32::MAX as usize || local_offset == u32::MAX as usize {
+                return Err(invalid("ZIP64 isn't supported"));
+            }
+            if read_u32(zip, local_offset)? != LOCAL_HEADER_SIGNATURE {
+                return Err(Error::InvalidApk(format!(
+                    "local header of {} not found",
+                    name
+                )));
+            }
+            let local_name_len = read_u16(zip, local_offset + 26)? as usize;
+            let data_start = local_offset
+                + LOCAL_HEADER_SIZE
+                + local_name_len
+                + read_u16(zip, local_offset + 28)? as usize;
+            let local_name = local_offset + LOCAL_HEADER_SIZE;
+            if zip.get(local_name..local_name + local_name_len) != Some(raw_name) {
+                return Err(Error::InvalidApk(format!(
+                    "local header name of {} doesn't match the central directory",
+                    name
+                )));
+            }
+            let mut data_end = data_start + compressed_size;
+            if flags & 0x08 != 0 {
+                // Data descriptor with optional signature, CRC-32 and sizes
+                data_end += if read_u32(zip, data_end)? == DATA_DESCRIPTOR_SIGNATURE {
+                    16
+                } else {
+                    12
+                };
+            }
+            if data_end > cd_offset {
+                return Err(Error::InvalidApk(format!("data of {} is truncated", name)));
+            }
+            entries.push(Entry {
+                name,
+                local_name_len,
+                method,
+                local_header: local_offset..data_start,
+                data: data_start..data_end,
+                central_header: offset..offset + central_header_len,
+            });
+            offset += central_header_len;
+        }
+        Ok(Self {
+            entries,
+            central_directory: cd_offset..eocd_offset,
+            eocd_offset,
+        })
+    }
+}
+
+fn find_eocd(zip: &[u8]) -> Option<usize> {
+    let max_comment = zip.len().checked_sub(EOCD_SIZE)?.min(u16::MAX as usize);
+    (0..=max_comment)
+        .map(|comment| zip.len() - EOCD_SIZE - comment)
+        .find(|&offset| {
+            read_u32(zip, offset).ok() == Some(EOCD_SIGNATURE)
+                && read_u16(zip, offset + 20).ok() == Some((zip.len() - offset - EOCD_SIZE) as u16)
+        })
+}
+
+/// Removes padding fields left by the previous alignment. Malformed extra field is kept
+/// as it is
+fn strip_alignment_field(extra: &[u8]) -> Vec<u8> {
+    let mut stripped = Vec::with_capacity(extra.len());
+    let mut rest = extra;
+    while rest.len() >= 4 {
+        let id = u16::from_le_bytes([rest[0], rest[1]]);
+        let len = 4 + u16::from_le_bytes([rest[2], rest[3]]) as usize;
+        if len > rest.len() {
+            return extra.to_vec();
+        }
+        if id != ALIGNMENT_EXTRA_ID {
+            stripped.extend_from_slice(&rest[..len]);
+        }
+        rest = &rest[len..];
+    }
+    if !rest.is_empty() {
+        // Zero padding some tools write without a field header
+        if rest.iter().all(|byte| *byte == 0) {
+            return stripped;
+        }
+        return extra.to_vec();
+    }
+    stripped
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|value| u16::from_le_bytes(value.try_into().unwrap()))
+        .ok_or_else(|| Error::InvalidApk("archive is truncated".to_owned()))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|value| u32::from_le_bytes(value.try_into().unwrap()))
+        .ok_or_else(|| Error::InvalidApk("archive is truncated".to_owned()))
+}
@@ -0,0 +1,69 @@
+#![cfg(unix)]
+
+mod common;
+
+use android_tools::{aapt2::Aapt2DaemonSession, executor::with_executor};
+use common::FakeTools;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Fake `aapt2 daemon` that fails every invocation containing `broken.xml`
+const FAKE_AAPT2: &str = r#"#!/bin/sh
+echo Ready
+args=""
+while IFS= read -r line; do
+  if [ -n "$line" ]; then
+    args="$args $line"
+    continue
+  fi
+  case "$args" in
+    " quit") exit 0 ;;
+    " hang") echo Done >&2; exec sleep 30 ;;
+    *broken.xml*) echo "res/values/broken.xml:3: error: invalid resource." >&2; echo Error >&2 ;;
+    *) echo "compiled$args" ;;
+  esac
+  echo Done >&2
+  args=""
+done
+"#;
+
+/// Starts the session with the fake aapt2
+fn start_fake_daemon() -> Aapt2DaemonSession {
+    let tools = Arc::new(FakeTools::new());
+    tools.add("aapt2", FAKE_AAPT2);
+    with_executor(tools, Aapt2DaemonSession::start).unwrap()
+}
+
+#[test]
+/// Session keeps a single `aapt2 daemon` process alive and reports result of every
+/// invocation separately
+fn test_aapt2_daemon_session() {
+    let mut daemon = start_fake_daemon();
+    let result = daemon
+        .invoke(["compile", "res/values/strings.xml", "-o", "compiled"])
+        .unwrap();
+    assert!(result.success);
+    assert!(result.diagnostics.is_empty());
+    let result = daemon
+        .invoke(["compile", "res/values/broken.xml", "-o", "compiled"])
+        .unwrap();
+    assert!(!result.success);
+    assert_eq!(
+        result.diagnostics,
+        vec!["res/values/broken.xml:3: error: invalid resource.".to_owned()]
+    );
+    assert!(daemon.invoke(["compile", "multi\nline"]).is_err());
+    daemon.shutdown().unwrap();
+}
+
+#[test]
+/// Daemon that doesn't quit when asked is killed instead of blocking the drop
+fn test_aapt2_daemon_drop_kills_hung_daemon() {
+    let mut daemon = start_fake_daemon();
+    assert!(daemon.invoke(["hang"]).unwrap().success);
+    let started = Instant::now();
+    drop(daemon);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
@@ -0,0 +1,58 @@
+use android_tools::aapt2::{Badging, ManifestChange, ResourceChange, ResourceDiff};
+use std::path::PathBuf;
+
+#[test]
+/// `aapt2 diff` prints every difference between two APKs prefixed with the path of the APK
+/// it was found in. Changed values span several lines
+fn test_parse_aapt2_diff() {
+    let apks = [PathBuf::from("old.apk"), PathBuf::from("new.apk")];
+    let output = "old.apk: missing com.example:string/removed
+new.apk: new entry com.example:string/title
+new.apk: new config com.example:string/app_name config=fr
+new.apk: value com.example:string/app_name config= does not match:
+\"Example\"
+ vs 
+\"Example app\"
+new.apk: com.example:string/hello has different visibility (PUBLIC vs PRIVATE)
+";
+    let diff = ResourceDiff::parse(output, &apks);
+    assert!(!diff.is_identical());
+    assert_eq!(
+        diff.removed,
+        vec![ResourceChange {
+            name: "com.example:string/removed".to_owned(),
+            config: None,
+        }]
+    );
+    assert_eq!(diff.added.len(), 2);
+    assert_eq!(diff.added[1].config.as_deref(), Some("fr"));
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].name, "com.example:string/app_name");
+    assert_eq!(diff.changed[0].config, "");
+    assert_eq!(diff.changed[0].old_value, "\"Example\"");
+    assert_eq!(diff.changed[0].new_value, "\"Example app\"");
+    assert_eq!(diff.other.len(), 1);
+    assert!(ResourceDiff::parse("", &apks).is_identical());
+
+    let old = Badging {
+        package: "com.example".to_owned(),
+        version_code: Some(1),
+        uses_permissions: vec!["android.permission.INTERNET".to_owned()],
+        ..Default::default()
+    };
+    let new = Badging {
+        version_code: Some(2),
+        uses_permissions: vec!["android.permission.CAMERA".to_owned()],
+        ..old.clone()
+    };
+    let changes = ManifestChange::compare(&old, &new);
+    assert_eq!(
+        changes[0],
+        ManifestChange {
+            attribute: "versionCode".to_owned(),
+            old_value: Some("1".to_owned()),
+            new_value: Some("2".to_owned()),
+        }
+    );
+    assert_eq!(changes.len(), 3);
+}
@@ -0,0 +1,105 @@
+use android_tools::aapt2::{
+    parse_configurations, parse_permissions, Badging, ResourceId, ResourceTable, Visibility,
+};
+
+#[test]
+/// `aapt2 dump badging` prints information extracted from the APK's manifest, one
+/// `key: name='value'` or `key:'value'` record per line
+fn test_parse_aapt2_dump_badging() {
+    let output = "package: name='com.example.app' versionCode='3' versionName='1.0 beta' platformBuildVersionName='11' compileSdkVersion='30'
+sdkVersion:'21'
+targetSdkVersion:'30'
+uses-permission: name='android.permission.INTERNET'
+uses-permission: name='android.permission.WRITE_EXTERNAL_STORAGE' maxSdkVersion='18'
+application-label:'Example'
+application: label='Example' icon='res/mipmap-hdpi-v4/ic_launcher.png'
+launchable-activity: name='android.app.NativeActivity'  label='' icon=''
+feature-group: label=''
+  uses-feature: name='android.hardware.touchscreen'
+supports-screens: 'small' 'normal' 'large' 'xlarge'
+locales: '--_--' 'fr'
+densities: '160' '240' '65534'
+native-code: 'arm64-v8a' 'x86_64'
+";
+    let badging = Badging::parse(output).unwrap();
+    assert_eq!(
+        badging,
+        Badging {
+            package: "com.example.app".to_owned(),
+            version_code: Some(3),
+            version_name: Some("1.0 beta".to_owned()),
+            sdk_version: Some(21),
+            target_sdk_version: Some(30),
+            launchable_activity: Some("android.app.NativeActivity".to_owned()),
+            native_code: vec!["arm64-v8a".to_owned(), "x86_64".to_owned()],
+            locales: vec!["--_--".to_owned(), "fr".to_owned()],
+            densities: vec![160, 240, 65534],
+            uses_permissions: vec![
+                "android.permission.INTERNET".to_owned(),
+                "android.permission.WRITE_EXTERNAL_STORAGE".to_owned(),
+            ],
+        }
+    );
+    assert!(Badging::parse("sdkVersion:'21'").is_err());
+
+    let permissions = parse_permissions(
+        "package: com.example.app
+permission: com.example.app.permission.C2D_MESSAGE
+uses-permission: name='android.permission.INTERNET'
+",
+    );
+    assert_eq!(permissions, vec!["android.permission.INTERNET".to_owned()]);
+}
+
+#[test]
+/// `aapt2 dump resources` prints every package of the resource table with its types,
+/// resources and their values for each configuration
+fn test_parse_aapt2_dump_resources() {
+    let output = "Binary APK
+Package name=com.example.app id=7f
+  type mipmap id=01 entryCount=1
+    resource 0x7f010000 mipmap/ic_launcher
+      (hdpi-v4) (file) res/mipmap-hdpi-v4/ic_launcher.png type=PNG
+      (xhdpi-v4) (file) res/mipmap-xhdpi-v4/ic_launcher.png type=PNG
+  type string id=02 entryCount=2
+    resource 0x7f020000 string/app_name PUBLIC
+      () \"Example\"
+      (fr) \"Exemple\"
+    resource 0x7f020001 string/hello
+      () \"Hello\"
+  type style id=03 entryCount=1
+    resource 0x7f030000 style/AppTheme
+      () (style) size=1 parent=@android:style/Theme
+        android:attr/windowBackground=#ffffffff
+";
+    let table = ResourceTable::parse(output).unwrap();
+    assert_eq!(table.packages.len(), 1);
+    assert_eq!(table.packages[0].name, "com.example.app");
+    assert_eq!(table.packages[0].id, Some(0x7f));
+    assert_eq!(table.packages[0].types.len(), 3);
+
+    let app_name = table.entry("@string/app_name").unwrap();
+    assert_eq!(app_name.id, ResourceId(0x7f020000));
+    assert_eq!(app_name.id.type_id(), 0x02);
+    assert_eq!(app_name.visibility, Visibility::Public);
+    assert_eq!(app_name.values[1].config, "fr");
+    assert_eq!(app_name.values[1].value, "\"Exemple\"");
+
+    let hello = table.entry_by_id("0x7f020001".parse().unwrap()).unwrap();
+    assert_eq!(hello.name, "hello");
+    assert_eq!(hello.visibility, Visibility::Default);
+
+    let theme = table.entry("style/AppTheme").unwrap();
+    assert!(theme.values[0]
+        .value
+        .ends_with("\nandroid:attr/windowBackground=#ffffffff"));
+
+    assert_eq!(
+        table.configurations(),
+        vec!["", "fr", "hdpi-v4", "xhdpi-v4"]
+    );
+    assert_eq!(
+        parse_configurations("\nfr\nhdpi-v4\n"),
+        vec!["fr".to_owned(), "hdpi-v4".to_owned()]
+    );
+}
@@ -0,0 +1,143 @@
+#![cfg(unix)]
+
+mod common;
+
+use android_tools::{
+    aapt2::{flat_file_name, Aapt2, Aapt2Compile},
+    error::Error,
+    executor::with_executor,
+};
+use common::FakeTools;
+use std::{path::Path, sync::Arc};
+
+/// Fake `aapt2 compile <file> -o <dir>` that writes the source into the compiled file
+const FAKE_AAPT2: &str = r#"#!/bin/sh
+[ "$1" = compile ] || exit 1
+if grep -q broken "$2"; then
+  echo "$2:1: error: invalid resource." >&2
+  exit 1
+fi
+dir=$(basename "$(dirname "$2")")
+file=$(basename "$2")
+case "$dir" in
+  values*) name="${dir}_${file%.xml}.arsc.flat" ;;
+  *) name="${dir}_${file}.flat" ;;
+esac
+cp "$2" "$4/$name"
+"#;
+
+#[test]
+fn test_flat_file_name() {
+    assert_eq!(
+        flat_file_name("values-en/strings.xml"),
+        "values-en_strings.arsc.flat"
+    );
+    assert_eq!(
+        flat_file_name("drawable/icon.9.png"),
+        "drawable_icon.9.png.flat"
+    );
+    assert_eq!(flat_file_name("layout/main.xml"), "layout_main.xml.flat");
+}
+
+/// Runs the test with the fake aapt2. The test gets a temporary directory for its files
+fn with_fake_aapt2(test: impl FnOnce(&Path)) {
+    let tools = Arc::new(FakeTools::new());
+    tools.add("aapt2", FAKE_AAPT2);
+    with_executor(tools.clone(), || test(tools.path()));
+}
+
+#[test]
+/// Second run compiles only changed files and removes compiled files of deleted sources
+fn test_aapt2_incremental_compile() {
+    with_fake_aapt2(|dir| {
+        let res = dir.join("res");
+        let compiled_res = dir.join("compiled_res");
+        std::fs::create_dir_all(res.join("values")).unwrap();
+        std::fs::create_dir_all(res.join("layout")).unwrap();
+        std::fs::write(res.join("values/strings.xml"), "<resources/>").unwrap();
+        std::fs::write(res.join("layout/main.xml"), "<LinearLayout/>").unwrap();
+
+        let compile = Aapt2.compile_changed(&res, &compiled_res);
+        let report = compile.run().unwrap();
+        assert_eq!(report.compiled.len(), 2);
+        assert!(report.unchanged.is_empty());
+        assert!(compiled_res.join("values_strings.arsc.flat").exists());
+        assert!(compiled_res.join("layout_main.xml.flat").exists());
+
+        std::fs::write(res.join("values/strings.xml"), "<resources></resources>").unwrap();
+        std::fs::remove_file(res.join("layout/main.xml")).unwrap();
+        let report = compile.run().unwrap();
+        assert_eq!(report.compiled, vec![res.join("values/strings.xml")]);
+        assert_eq!(
+            report.removed,
+            vec![compiled_res.join("layout_main.xml.flat")]
+        );
+        assert_eq!(
+            report.flat_files,
+            vec![compiled_res.join("values_strings.arsc.flat")]
+        );
+
+        let report = compile.run().unwrap();
+        assert!(report.compiled.is_empty());
+        assert_eq!(report.unchanged, vec![res.join("values/strings.xml")]);
+    });
+}
+
+#[test]
+/// Changed options recompile all resources and still remove compiled files of deleted
+/// sources
+fn test_aapt2_incremental_compile_options_changed() {
+    with_fake_aapt2(|dir| {
+        let res = dir.join("res");
+        let compiled_res = dir.join("compiled_res");
+        std::fs::create_dir_all(res.join("values")).unwrap();
+        std::fs::create_dir_all(res.join("layout")).unwrap();
+        std::fs::write(res.join("values/strings.xml"), "<resources/>").unwrap();
+        std::fs::write(res.join("layout/main.xml"), "<LinearLayout/>").unwrap();
+        Aapt2.compile_changed(&res, &compiled_res).run().unwrap();
+
+        std::fs::remove_file(res.join("layout/main.xml")).unwrap();
+        let report = Aapt2
+            .compile_changed(&res, &compiled_res)
+            .options(Aapt2Compile::new(&res, &compiled_res).no_crunch(true))
+            .run()
+            .unwrap();
+        assert_eq!(report.compiled, vec![res.join("values/strings.xml")]);
+        assert_eq!(
+            report.removed,
+            vec![compiled_res.join("layout_main.xml.flat")]
+        );
+        assert!(!compiled_res.join("layout_main.xml.flat").exists());
+    });
+}
+
+#[test]
+/// Every failed resource is reported with diagnostics of its own compile
+fn test_aapt2_incremental_compile_failures() {
+    with_fake_aapt2(|dir| {
+        let res = dir.join("res");
+        let compiled_res = dir.join("compiled_res");
+        std::fs::create_dir_all(res.join("values")).unwrap();
+        std::fs::create_dir_all(res.join("layout")).unwrap();
+        std::fs::write(res.join("values/strings.xml"), "<resources/>").unwrap();
+        std::fs::write(res.join("layout/first.xml"), "broken").unwrap();
+        std::fs::write(res.join("layout/second.xml"), "broken").unwrap();
+
+        let Err(Error::CompileFailed(failures)) = Aapt2.compile_changed(&res, &compiled_res).run()
+        else {
+            panic!("compile should fail");
+        };
+        let failed: Vec<_> = failures.0.iter().map(|(path, _)| path.clone()).collect();
+        assert_eq!(
+            failed,
+            [res.join("layout/first.xml"), res.join("layout/second.xml")]
+        );
+        for (path, error) in &failures.0 {
+            let Error::CmdFailed(failure) = error else {
+                panic!("unexpected error: {}", error);
+            };
+            assert_eq!(failure.diagnostics[0].file.as_deref(), Some(path.as_path()));
+        }
+        assert!(compiled_res.join("values_strings.arsc.flat").exists());
+    });
+}
@@ -0,0 +1,85 @@
+use android_tools::adb::{AdbClient, AdbTarget};
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+};
+
+/// Reads one length-prefixed request sent by the client
+fn read_request(stream: &mut TcpStream) -> String {
+    let mut length = [0; 4];
+    stream.read_exact(&mut length).unwrap();
+    let length = usize::from_str_radix(std::str::from_utf8(&length).unwrap(), 16).unwrap();
+    let mut request = vec![0; length];
+    stream.read_exact(&mut request).unwrap();
+    String::from_utf8(request).unwrap()
+}
+
+/// Starts fake adb server that serves given number of connections
+fn fake_adb_server(connections: usize) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(connections) {
+            let mut stream = stream.unwrap();
+            match read_request(&mut stream).as_str() {
+                "host:version" => stream.write_all(b"OKAY00040029").unwrap(),
+                "host:devices-l" => {
+                    let devices = "emulator-5554 device product:sdk_gphone64 model:sdk_gphone64 device:emu64x transport_id:1\n";
+                    write!(stream, "OKAY{:04x}{}", devices.len(), devices).unwrap();
+                }
+                "host:transport:emulator-5554" => {
+                    stream.write_all(b"OKAY").unwrap();
+                    assert_eq!(read_request(&mut stream), "shell:getprop ro.product.model");
+                    stream.write_all(b"OKAYsdk_gphone64\n").unwrap();
+                }
+                _ => {
+                    let message = "no devices/emulators found";
+                    write!(stream, "FAIL{:04x}{}", message.len(), message).unwrap();
+                }
+            }
+        }
+    });
+    address
+}
+
+#[test]
+/// The adb server host protocol sends every request as a 4-digit hexadecimal length
+/// followed by the payload. The server answers with `OKAY` or `FAIL` and a
+/// length-prefixed message
+fn test_adb_client_host_requests() {
+    let address = fake_adb_server(4);
+    let mut client = AdbClient::new();
+    client.address(address);
+
+    assert_eq!(client.version().unwrap(), 0x29);
+    assert_eq!(client.devices().unwrap()[0].serial, "emulator-5554");
+    let mut target = AdbTarget::new();
+    target.serial("emulator-5554");
+    assert_eq!(
+        client.shell(&target, "getprop ro.product.model").unwrap(),
+        "sdk_gphone64\n"
+    );
+    let error = client.shell(&AdbTarget::new(), "ls").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Adb server responded with failure: no devices/emulators found"
+    );
+}
+
+#[test]
+/// Host and port of the target select the adb server the command is sent to
+fn test_adb_client_target_server() {
+    let address = fake_adb_server(1);
+    let mut target = AdbTarget::new();
+    target
+        .serial("emulator-5554")
+        .host("127.0.0.1")
+        .port(address.port());
+    // The client address is only used when the target doesn't select the server
+    assert_eq!(
+        AdbClient::new()
+            .shell(&target, "getprop ro.product.model")
+            .unwrap(),
+        "sdk_gphone64\n"
+    );
+}
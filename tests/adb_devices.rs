@@ -0,0 +1,55 @@
+use android_tools::adb::{Device, DeviceState};
+
+#[test]
+/// `adb devices -l` prints a header followed by one line per attached device with its
+/// serial, state and `key:value` qualifiers
+fn test_parse_adb_devices_list() {
+    let output = "* daemon not running; starting now at tcp:5037
+* daemon started successfully
+List of devices attached
+emulator-5554          device product:sdk_gphone64_x86_64 model:sdk_gphone64_x86_64 device:emu64x transport_id:1
+R58M42XXXXX            unauthorized usb:1-1 transport_id:2
+
+";
+    let devices = Device::parse_list(output).unwrap();
+    assert_eq!(devices.len(), 2);
+    assert_eq!(
+        devices[0],
+        Device {
+            serial: "emulator-5554".to_owned(),
+            state: DeviceState::Device,
+            product: Some("sdk_gphone64_x86_64".to_owned()),
+            model: Some("sdk_gphone64_x86_64".to_owned()),
+            device: Some("emu64x".to_owned()),
+            transport_id: Some(1),
+        }
+    );
+    assert_eq!(devices[1].state, DeviceState::Unauthorized);
+    assert_eq!(devices[1].model, None);
+    assert_eq!(devices[1].transport_id, Some(2));
+}
+
+#[test]
+/// `no permissions` state consists of several words, unknown states are errors
+fn test_parse_adb_devices_states() {
+    let output = "List of devices attached
+0123456789             bootloader
+4c2a3b1d               no permissions (user in plugdev group; are your udev rules wrong?); see [http://developer.android.com/tools/device.html] usb:1-2 transport_id:5
+5d3b4c2e               no permissions usb:1-3
+";
+    let devices = Device::parse_list(output).unwrap();
+    assert_eq!(devices.len(), 3);
+    assert_eq!(devices[0].state, DeviceState::Bootloader);
+    assert_eq!(devices[1].state, DeviceState::NoPermissions);
+    assert_eq!(devices[1].transport_id, Some(5));
+    assert_eq!(devices[2].state, DeviceState::NoPermissions);
+    assert_eq!(devices[2].state.to_string(), "no permissions");
+
+    let error = Device::parse_list("emulator-5556 future-state transport_id:7").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unknown adb device state \"future-state\""
+    );
+    let error = Device::parse_list("0123456789").unwrap_err();
+    assert_eq!(error.to_string(), "Unknown adb device state \"\"");
+}
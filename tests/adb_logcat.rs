@@ -0,0 +1,111 @@
+mod common;
+
+use android_tools::{
+    adb::{AdbLogcat, LogEntry, LogPriority, LogcatFormat},
+    error::Error,
+    executor::{with_executor, RecordingExecutor, ReplayExecutor},
+};
+use std::sync::Arc;
+
+#[test]
+/// Logcat prints every message with its time, process and thread ids, priority and tag.
+/// The `epoch` and `uid` format modifiers change the time and add the UID column
+fn test_parse_logcat_entries() {
+    let entry = LogEntry::parse(
+        "10-17 11:31:02.123  1234  5678 E AndroidRuntime: FATAL EXCEPTION: main",
+        LogcatFormat::Threadtime,
+    )
+    .unwrap();
+    assert_eq!(
+        entry,
+        LogEntry {
+            timestamp: "10-17 11:31:02.123".to_owned(),
+            uid: None,
+            pid: 1234,
+            tid: 5678,
+            level: LogPriority::Error,
+            tag: "AndroidRuntime".to_owned(),
+            message: "FATAL EXCEPTION: main".to_owned(),
+        }
+    );
+
+    let entry = LogEntry::parse(
+        "1697541062.123  1234  1234 I ActivityManager : Start proc",
+        LogcatFormat::Epoch,
+    )
+    .unwrap();
+    assert_eq!(entry.timestamp, "1697541062.123");
+    assert_eq!(entry.tag, "ActivityManager");
+    assert_eq!(entry.message, "Start proc");
+
+    let entry = LogEntry::parse(
+        "10-17 11:31:02.123 u0_a123  4321  4330 W chromium: ",
+        LogcatFormat::Uid,
+    )
+    .unwrap();
+    assert_eq!(entry.uid.as_deref(), Some("u0_a123"));
+    assert_eq!(entry.level, LogPriority::Warn);
+    assert_eq!(entry.message, "");
+
+    assert!(LogEntry::parse("--------- beginning of main", LogcatFormat::Threadtime).is_none());
+}
+
+#[test]
+#[cfg(unix)]
+/// Logcat that prints a lot to stderr doesn't block, and its stderr is reported when it
+/// fails
+fn test_logcat_stderr() {
+    use common::FakeTools;
+
+    let tools = Arc::new(FakeTools::new());
+    tools.add(
+        "adb",
+        "#!/bin/sh\nhead -c 200000 /dev/zero | tr '\\0' w >&2\n\
+         echo '10-17 11:31:02.123  1234  5678 I Tag: message'\n\
+         echo 'error: device offline' >&2\nexit 1\n",
+    );
+
+    let mut stream = with_executor(tools, || AdbLogcat::new().d(true).run().unwrap());
+    assert_eq!(stream.next().unwrap().unwrap().message, "message");
+    let Some(Err(Error::CmdFailed(failure))) = stream.next() else {
+        panic!("logcat should fail");
+    };
+    assert!(failure.stderr.ends_with("error: device offline\n"));
+    assert!(stream.next().is_none());
+}
+
+#[test]
+/// Logcat is started by the current executor, so its output can be replayed and recorded
+fn test_logcat_replay() {
+    let mut replay = ReplayExecutor::new();
+    replay
+        .expect("adb", &["logcat", "-v", "threadtime", "-d", "*:S"])
+        .stdout(
+            "--------- beginning of main\n\
+             10-17 11:31:02.123  1234  5678 I Tag: first\n\
+             10-17 11:31:02.124  1234  5678 W Tag: second\n",
+        )
+        .stderr("error: device offline\n")
+        .exit_code(1);
+    let recording = Arc::new(RecordingExecutor::new(Arc::new(replay)));
+    let entries = with_executor(recording.clone(), || {
+        AdbLogcat::new()
+            .d(true)
+            .silence_others(true)
+            .run()
+            .unwrap()
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].as_ref().unwrap().message, "first");
+    assert_eq!(entries[1].as_ref().unwrap().level, LogPriority::Warn);
+    let Err(Error::CmdFailed(failure)) = &entries[2] else {
+        panic!("logcat should fail");
+    };
+    assert_eq!(failure.stderr, "error: device offline\n");
+
+    let invocations = recording.invocations();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(invocations[0].exit_code, 1);
+    assert!(String::from_utf8_lossy(&invocations[0].stdout).ends_with("W Tag: second\n"));
+}
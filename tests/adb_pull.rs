@@ -0,0 +1,38 @@
+use android_tools::adb::PullStats;
+use std::{path::PathBuf, time::Duration};
+
+#[test]
+/// `adb pull` prints a summary line with the number of files, transferred bytes and
+/// throughput for every pulled path
+fn test_parse_adb_pull_stats() {
+    let output = "[ 50%] /sdcard/tombstones/tombstone_00
+/sdcard/report.txt: 1 file pulled, 0 skipped. 4.2 MB/s (52430 bytes in 0.012s)
+/sdcard/tombstones/: 3 files pulled, 1 skipped. 31.5 MB/s (1048576 bytes in 0.032s)
+";
+    let stats = PullStats::parse_output(output);
+    assert_eq!(stats.len(), 2);
+    assert_eq!(
+        stats[0],
+        PullStats {
+            remote: Some(PathBuf::from("/sdcard/report.txt")),
+            files_pulled: 1,
+            files_skipped: 0,
+            bytes: 52430,
+            duration: Duration::from_millis(12),
+            throughput: 4.2,
+        }
+    );
+    assert_eq!(stats[1].files_pulled, 3);
+    assert_eq!(stats[1].files_skipped, 1);
+    assert_eq!(stats[1].bytes, 1048576);
+    assert_eq!(stats[1].remote, Some(PathBuf::from("/sdcard/tombstones/")));
+
+    // Durations that don't fit into `Duration` skip the stats instead of panicking
+    for duration in ["inf", "NaN", "-0.5"] {
+        let line = format!(
+            "/sdcard/a.txt: 1 file pulled, 0 skipped. 0.0 MB/s (10 bytes in {}s)",
+            duration
+        );
+        assert_eq!(PullStats::parse(&line), None);
+    }
+}
@@ -0,0 +1,38 @@
+#![cfg(unix)]
+
+mod common;
+
+use android_tools::{
+    adb::{adb_tool, AdbPull},
+    error::Error,
+};
+use common::EnvGuard;
+use std::path::Path;
+
+#[test]
+/// adb is resolved from `ADB_PATH`, then from `platform-tools` of the SDK
+fn test_adb_tool() {
+    let mut env = EnvGuard::lock();
+    let dir = tempfile::tempdir().unwrap();
+    let sdk = dir.path().join("sdk");
+    std::fs::create_dir_all(sdk.join("platform-tools")).unwrap();
+    env.set_var("PATH", dir.path());
+    env.set_var("ANDROID_SDK_ROOT", &sdk);
+    env.remove_var("ADB_PATH");
+
+    let Err(Error::CmdNotFound(tool)) = adb_tool() else {
+        panic!("adb should not be found");
+    };
+    assert_eq!(tool, "adb");
+
+    let adb = sdk.join("platform-tools").join("adb");
+    std::fs::write(&adb, "").unwrap();
+    assert_eq!(adb_tool().unwrap().get_program(), adb);
+    let command_line = AdbPull::new(Path::new("/sdcard/a.txt"), Path::new("a.txt"))
+        .command_line()
+        .unwrap();
+    assert_eq!(command_line.program, adb);
+
+    env.set_var("ADB_PATH", "/opt/adb");
+    assert_eq!(adb_tool().unwrap().get_program(), "/opt/adb");
+}
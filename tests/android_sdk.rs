@@ -0,0 +1,116 @@
+use android_tools::{
+    find_max_version,
+    sdk::{AndroidSdk, Version, VersionReq},
+};
+use std::path::Path;
+
+fn create_sdk(sdk: &Path) {
+    for dir in [
+        "build-tools/9.0.0",
+        "build-tools/30.0.3",
+        "build-tools/31.0.0",
+        "build-tools/33.0.0-rc1",
+        "platforms/android-9",
+        "platforms/android-30",
+        "platforms/android-TiramisuPrivacySandbox",
+        "ndk/23.1.7779620",
+        "ndk-bundle",
+        "platform-tools",
+    ] {
+        std::fs::create_dir_all(sdk.join(dir)).unwrap();
+    }
+    std::fs::write(
+        sdk.join("ndk-bundle").join("source.properties"),
+        "Pkg.Desc = Android NDK\nPkg.Revision = 21.4.7075529\n",
+    )
+    .unwrap();
+}
+
+#[test]
+/// Versions are ordered numerically, so `30.0.3` is newer than `9.0.0`
+fn test_android_sdk_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    create_sdk(dir.path());
+    let sdk = AndroidSdk::from_path(dir.path()).unwrap();
+
+    let build_tools = sdk.build_tools();
+    assert_eq!(build_tools.first().unwrap().to_string(), "9.0.0");
+    assert_eq!(build_tools.last().unwrap().to_string(), "33.0.0-rc1");
+    assert_eq!(
+        sdk.build_tools_dir(&VersionReq::Latest).unwrap(),
+        dir.path().join("build-tools").join("33.0.0-rc1")
+    );
+    assert_eq!(
+        sdk.build_tools_dir(&"30.0.3".parse().unwrap()).unwrap(),
+        dir.path().join("build-tools").join("30.0.3")
+    );
+    assert_eq!(
+        sdk.build_tools_dir(&">=30".parse().unwrap()).unwrap(),
+        dir.path().join("build-tools").join("33.0.0-rc1")
+    );
+    assert!(sdk.build_tools_dir(&">=34".parse().unwrap()).is_err());
+    assert_eq!(
+        find_max_version(&dir.path().join("build-tools")).unwrap(),
+        "33.0.0-rc1"
+    );
+
+    let platforms = sdk.platforms();
+    let names = platforms
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["9", "30", "TiramisuPrivacySandbox"]);
+    assert_eq!(
+        sdk.platform_dir(&VersionReq::Latest).unwrap(),
+        dir.path().join("platforms").join("android-30")
+    );
+
+    let ndks = sdk.ndks();
+    assert_eq!(ndks.len(), 2);
+    assert_eq!(ndks[0].0, "21.4.7075529".parse::<Version>().unwrap());
+    assert_eq!(ndks[0].1, dir.path().join("ndk-bundle"));
+    assert!(sdk.emulator_dir().is_none());
+    assert_eq!(
+        sdk.platform_tools_dir().unwrap(),
+        dir.path().join("platform-tools")
+    );
+}
+
+#[test]
+fn test_version_parse() {
+    let rc = "33.0.0-rc1".parse::<Version>().unwrap();
+    let release = "33.0.0".parse::<Version>().unwrap();
+    assert!(rc < release);
+    let rc2 = "33.0.0-rc2".parse::<Version>().unwrap();
+    let rc10 = "33.0.0-rc10".parse::<Version>().unwrap();
+    assert!(rc < rc2 && rc2 < rc10 && rc10 < release);
+    // Equality and hash agree with the order
+    let rc01 = "33.0.0-rc01".parse::<Version>().unwrap();
+    assert_eq!(rc01, rc);
+    assert_eq!(std::collections::HashSet::from([rc01, rc.clone()]).len(), 1);
+    assert_ne!(rc, rc2);
+    assert_eq!("33".parse::<Version>().unwrap(), release);
+    assert!("r21".parse::<Version>().is_err());
+    assert!("1.2.3.4".parse::<Version>().is_err());
+    assert_eq!(
+        ">=30.0.1".parse::<VersionReq>().unwrap(),
+        VersionReq::AtLeast(Version::new(30, 0, 1))
+    );
+    assert_eq!("latest".parse::<VersionReq>().unwrap(), VersionReq::Latest);
+}
+
+#[test]
+fn test_android_sdk_local_properties() {
+    let dir = tempfile::tempdir().unwrap();
+    let sdk_dir = dir.path().join("my sdk");
+    create_sdk(&sdk_dir);
+    let escaped = sdk_dir.to_str().unwrap().replace(':', "\\:");
+    std::fs::write(
+        dir.path().join("local.properties"),
+        format!("# Location of the SDK\nsdk.dir={}\n", escaped),
+    )
+    .unwrap();
+    let sdk = AndroidSdk::from_local_properties(dir.path()).unwrap();
+    assert_eq!(sdk.path(), sdk_dir);
+    assert!(AndroidSdk::from_local_properties(&sdk_dir).is_err());
+}
@@ -0,0 +1,204 @@
+#![cfg(feature = "apksig")]
+
+use android_tools::{
+    apksig::{ApkSigner, ApkVerifier, SignatureScheme, SigningCertificateLineage, SigningKey},
+    error::Error,
+};
+use std::{
+    io::{Cursor, Write},
+    path::Path,
+    process::Command,
+};
+
+/// Creates minimal unsigned APK
+fn unsigned_apk() -> Vec<u8> {
+    let mut apk = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default();
+    apk.start_file("AndroidManifest.xml", options).unwrap();
+    apk.write_all(b"<manifest package=\"com.example\"/>")
+        .unwrap();
+    apk.start_file("classes.dex", options).unwrap();
+    apk.write_all(&[0; 4096]).unwrap();
+    apk.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_sign_and_verify_apk() {
+    let key = SigningKey::generate(1024, "CN=Test,O=Example").unwrap();
+    let apk = unsigned_apk();
+    assert_eq!(ApkVerifier::verify(&apk).unwrap().schemes, []);
+
+    let signed = ApkSigner::new(Path::new(""), Path::new(""), &key)
+        .sign(&apk)
+        .unwrap();
+    let verification = ApkVerifier::verify(&signed).unwrap();
+    assert_eq!(
+        verification.schemes,
+        [SignatureScheme::V2, SignatureScheme::V3]
+    );
+    assert!(verification.is_verified());
+    assert_eq!(
+        verification.v2_signers[0].certificates[0],
+        key.certificate()
+    );
+    let v3_signer = &verification.v3_signers[0];
+    assert_eq!(
+        v3_signer.certificate_subject().unwrap(),
+        "CN=Test,O=Example"
+    );
+    assert_eq!(v3_signer.min_sdk_version, Some(28));
+    assert!(v3_signer.lineage.is_none());
+
+    // Signed APK is still a valid ZIP archive
+    let mut archive = zip::ZipArchive::new(Cursor::new(&signed)).unwrap();
+    assert_eq!(archive.by_name("classes.dex").unwrap().size(), 4096);
+
+    // Signing again replaces the signing block
+    let other_key = SigningKey::generate(1024, "CN=Other").unwrap();
+    let resigned = ApkSigner::new(Path::new(""), Path::new(""), &other_key)
+        .v3_signing_enabled(false)
+        .sign(&signed)
+        .unwrap();
+    let verification = ApkVerifier::verify(&resigned).unwrap();
+    assert_eq!(verification.schemes, [SignatureScheme::V2]);
+    assert_eq!(
+        verification.v2_signers[0].certificates[0],
+        other_key.certificate()
+    );
+
+    // Any modified byte of the contents breaks the signature
+    let mut tampered = signed.clone();
+    tampered[40] ^= 0xff;
+    assert!(matches!(
+        ApkVerifier::verify(&tampered),
+        Err(Error::SignatureVerificationFailed(_))
+    ));
+}
+
+#[test]
+/// v3 signature is made with the new key and proves rotation from the old key, while
+/// v2 is signed with the old key for devices before Android 9
+fn test_sign_apk_with_key_rotation() {
+    let old_key = SigningKey::generate(1024, "CN=Old").unwrap();
+    let new_key = SigningKey::generate(1024, "CN=New").unwrap();
+    let mut lineage = SigningCertificateLineage::new(&old_key);
+    lineage.rotate(&old_key, &new_key).unwrap();
+    assert!(lineage.rotate(&old_key, &new_key).is_err());
+    assert_eq!(
+        SigningCertificateLineage::from_bytes(&lineage.to_bytes()).unwrap(),
+        lineage
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let apk = dir.path().join("app.apk");
+    std::fs::write(&apk, unsigned_apk()).unwrap();
+    ApkSigner::new(&apk, &apk, &new_key)
+        .v2_key(&old_key)
+        .lineage(&lineage)
+        .run()
+        .unwrap();
+    let verification = ApkVerifier::new(&apk).run().unwrap();
+    assert_eq!(
+        verification.v2_signers[0].certificate_subject().unwrap(),
+        "CN=Old"
+    );
+    let v3_signer = &verification.v3_signers[0];
+    assert_eq!(v3_signer.certificate_subject().unwrap(), "CN=New");
+    let nodes = v3_signer.lineage.as_ref().unwrap().nodes();
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[0].certificate, old_key.certificate());
+
+    // Lineage must end with the signing key
+    assert!(ApkSigner::new(&apk, &apk, &old_key)
+        .lineage(&lineage)
+        .run()
+        .is_err());
+}
+
+#[test]
+/// APKs signed with other algorithms, see `tests/resources/apksig/generate.py`. ECDSA
+/// signatures aren't verified, but contents digest of the signer still is
+fn test_verify_signature_algorithms() {
+    let resources = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/apksig");
+    let verify = |name: &str| ApkVerifier::new(&resources.join(name)).run().unwrap();
+
+    let verification = verify("rsa4096_v2_v3.apk");
+    assert_eq!(
+        verification.schemes,
+        [SignatureScheme::V2, SignatureScheme::V3]
+    );
+    assert!(verification.is_verified());
+    let v3_signer = &verification.v3_signers[0];
+    assert_eq!(v3_signer.signature_algorithms, [0x0104]);
+    assert_eq!(v3_signer.certificate_subject().unwrap(), "CN=RSA 4096");
+
+    let verification = verify("rsa_pss_v2.apk");
+    assert_eq!(verification.schemes, [SignatureScheme::V2]);
+    assert!(verification.is_verified());
+    assert_eq!(verification.v2_signers[0].signature_algorithms, [0x0102]);
+
+    let verification = verify("ec_p256_v2_v3.apk");
+    assert_eq!(
+        verification.schemes,
+        [SignatureScheme::V2, SignatureScheme::V3]
+    );
+    assert!(!verification.is_verified());
+    let v2_signer = &verification.v2_signers[0];
+    assert!(!v2_signer.verified);
+    assert_eq!(v2_signer.signature_algorithms, [0x0201]);
+    assert_eq!(v2_signer.certificate_subject().unwrap(), "CN=EC P-256");
+
+    let mut tampered = std::fs::read(resources.join("ec_p256_v2_v3.apk")).unwrap();
+    tampered[40] ^= 0xff;
+    assert!(matches!(
+        ApkVerifier::verify(&tampered),
+        Err(Error::SignatureVerificationFailed(_))
+    ));
+}
+
+#[test]
+fn test_signing_key_from_keystores() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = SigningKey::generate(1024, "CN=Test").unwrap();
+    let p12 = dir.path().join("release.p12");
+    std::fs::write(&p12, key.to_pkcs12("key0", "android").unwrap()).unwrap();
+    let loaded = SigningKey::from_pkcs12(&p12, "android", Some("key0")).unwrap();
+    assert_eq!(loaded.certificate(), key.certificate());
+    assert!(SigningKey::from_pkcs12(&p12, "wrong", None).is_err());
+
+    let jks = dir.path().join("release.jks");
+    let keytool = Command::new("keytool")
+        .args([
+            "-genkeypair",
+            "-storetype",
+            "JKS",
+            "-keyalg",
+            "RSA",
+            "-keysize",
+            "1024",
+        ])
+        .args(["-alias", "key0", "-dname", "CN=Keytool", "-validity", "1"])
+        .args([
+            "-storepass",
+            "storepass",
+            "-keypass",
+            "keypass",
+            "-keystore",
+        ])
+        .arg(&jks)
+        .output();
+    if !keytool.is_ok_and(|output| output.status.success()) {
+        // Java isn't installed
+        return;
+    }
+    let loaded = SigningKey::from_jks(&jks, "storepass", Some("key0"), "keypass").unwrap();
+    let signed = ApkSigner::new(Path::new(""), Path::new(""), &loaded)
+        .sign(&unsigned_apk())
+        .unwrap();
+    let verification = ApkVerifier::verify(&signed).unwrap();
+    assert_eq!(
+        verification.v3_signers[0].certificate_subject().unwrap(),
+        "CN=Keytool"
+    );
+    assert!(SigningKey::from_jks(&jks, "storepass", None, "wrong").is_err());
+}
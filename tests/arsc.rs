@@ -0,0 +1,91 @@
+use android_tools::{
+    arsc::{ResTable, ResTableConfig, ResTableEntryValue, DENSITY_XHDPI},
+    axml::ResValue,
+};
+use std::path::Path;
+
+#[test]
+/// Table laid out like aapt2 writes it, see `tests/resources/arsc/generate.py`
+fn test_resolve_resources() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/arsc/resources.arsc");
+    let table = ResTable::from_file(&path).unwrap();
+    let package = table.package(0x7f).unwrap();
+    assert_eq!(package.name, "com.example");
+    let types: Vec<_> = package
+        .types
+        .iter()
+        .map(|resource_type| resource_type.name.as_str())
+        .collect();
+    assert_eq!(types, ["color", "drawable", "string", "style"]);
+    assert_eq!(package.types[2].configs.len(), 2);
+
+    let app_name = table.id("@string/app_name").unwrap();
+    assert_eq!(app_name, 0x7f03_0000);
+    assert_eq!(table.name(0x7f03_0001).unwrap(), "com.example:string/brand");
+    assert_eq!(table.entries(app_name).len(), 2);
+    let value = |name: &str, device: &str| {
+        table
+            .resolve_name(name, &device.parse().unwrap())
+            .map(|entry| entry.value.clone())
+    };
+    let string = |value: &str| Some(ResTableEntryValue::Simple(ResValue::String(value.into())));
+    assert_eq!(value("@string/app_name", ""), string("My App"));
+    assert_eq!(value("@string/app_name", "fr-xhdpi"), string("Mon App"));
+    assert_eq!(value("string/app_name", "de-rDE"), string("My App"));
+    // Missing in the sparse `fr` type, so the default value is used
+    assert_eq!(
+        value("string/brand", "fr"),
+        Some(ResTableEntryValue::Simple(ResValue::Reference(app_name)))
+    );
+    assert_eq!(
+        table
+            .resolve_value(0x7f03_0001, &"fr".parse().unwrap())
+            .cloned(),
+        string("Mon App")
+    );
+    // References to the framework aren't resolved
+    let accent = table.id("color/accent").unwrap();
+    assert_eq!(
+        table.resolve_value(accent, &ResTableConfig::default()),
+        Some(&ResTableEntryValue::Simple(ResValue::Reference(
+            0x0106_000c
+        )))
+    );
+
+    assert_eq!(
+        value("drawable/icon", "xhdpi"),
+        string("res/drawable-xhdpi-v4/icon.png")
+    );
+    // Higher density is scaled down
+    assert_eq!(
+        value("drawable/icon", "mdpi"),
+        string("res/drawable-hdpi-v4/icon.png")
+    );
+    assert_eq!(
+        value("drawable/icon", "xxxhdpi"),
+        string("res/drawable-xxhdpi-v4/icon.png")
+    );
+    assert_eq!(
+        value("com.example:style/AppTheme", ""),
+        Some(ResTableEntryValue::Complex {
+            parent: 0x0103_0224,
+            values: vec![(0x0101_0098, ResValue::ColorArgb8(0xff00_00ff))],
+        })
+    );
+    assert!(value("string/missing", "").is_none());
+}
+
+#[test]
+fn test_config_qualifiers() {
+    let config: ResTableConfig = "b+sr+Latn-sw600dp-land-night-xhdpi-v21".parse().unwrap();
+    assert_eq!(config.language, "sr");
+    assert_eq!(config.script, "Latn");
+    assert_eq!(config.density, DENSITY_XHDPI);
+    assert_eq!(config.sdk_version, 21);
+    assert_eq!(config.to_string(), "b+sr+Latn-sw600dp-land-night-xhdpi-v21");
+    let config: ResTableConfig = "fr-rCA-hdpi".parse().unwrap();
+    assert_eq!(config.region, "CA");
+    assert_eq!(config.to_string(), "fr-rCA-hdpi");
+    assert_eq!(ResTableConfig::default().to_string(), "");
+    assert!("unknown-qualifier".parse::<ResTableConfig>().is_err());
+}
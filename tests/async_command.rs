@@ -0,0 +1,124 @@
+#![cfg(all(feature = "async", unix))]
+
+mod common;
+
+use android_tools::{
+    aapt2::{Aapt2, SubCommand},
+    async_command::output_err_async,
+    error::Error,
+    executor::{with_executor, ExecOptions, OutputStream, ReplayExecutor},
+};
+use common::FakeTools;
+use std::{
+    path::Path,
+    process::Command,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[tokio::test]
+async fn test_output_err_async() {
+    let mut echo = Command::new("echo");
+    echo.arg("hello");
+    let output = output_err_async(echo, &ExecOptions::new()).await.unwrap();
+    assert_eq!(output.stdout, b"hello\n");
+
+    let mut exit = Command::new("sh");
+    exit.args(["-c", "exit 3"]);
+    let Err(Error::CmdFailed(failure)) = output_err_async(exit, &ExecOptions::new()).await else {
+        panic!("sh should fail");
+    };
+    assert_eq!(failure.exit_code, Some(3));
+}
+
+#[tokio::test]
+/// Printed output is still captured to report diagnostics
+async fn test_output_err_async_with_logs() {
+    let mut fail = Command::new("sh");
+    fail.args(["-c", "echo 'error: broken' >&2; exit 1"]);
+    let Err(Error::CmdFailed(failure)) =
+        output_err_async(fail, ExecOptions::new().print_logs(true)).await
+    else {
+        panic!("sh should fail");
+    };
+    assert_eq!(failure.stderr, "error: broken\n");
+    assert_eq!(failure.diagnostics[0].message, "broken");
+}
+
+#[tokio::test]
+/// The child is killed when the timeout elapses instead of blocking until it exits
+async fn test_output_err_async_timeout() {
+    let started = Instant::now();
+    let mut sleep = Command::new("sleep");
+    sleep.arg("10");
+    let options = ExecOptions::new()
+        .timeout(Duration::from_millis(100))
+        .clone();
+    let result = output_err_async(sleep, &options).await;
+    assert!(matches!(result, Err(Error::CmdTimeout(command, _)) if command == "sleep 10"));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+/// Lines are passed to the callback whatever chunks they are read in, including the last
+/// line without a line ending
+async fn test_output_err_async_on_line() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let collected = lines.clone();
+    let mut options = ExecOptions::new();
+    options.on_line(move |stream, line| {
+        collected.lock().unwrap().push((stream, line.to_owned()));
+    });
+    let mut print = Command::new("sh");
+    print.args(["-c", "printf 'first\\nsec'; sleep 0.1; printf 'ond\\nlast'"]);
+    let output = output_err_async(print, &options).await.unwrap();
+    assert_eq!(output.stdout, b"first\nsecond\nlast");
+    let lines = lines.lock().unwrap();
+    let stdout = lines
+        .iter()
+        .filter(|(stream, _)| *stream == OutputStream::Stdout)
+        .map(|(_, line)| line.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(stdout, ["first", "second", "last"]);
+}
+
+#[test]
+/// Async runs use executor of the thread polling them to find and run the tool
+fn test_run_async() {
+    let tools = Arc::new(FakeTools::new());
+    tools.add("aapt2", "#!/bin/sh\necho com.example\n");
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let output = with_executor(tools, || {
+        runtime.block_on(
+            Aapt2
+                .dump(SubCommand::Packagename, Path::new("app.apk"))
+                .run_async_with(ExecOptions::new().timeout(Duration::from_secs(10))),
+        )
+    });
+    assert_eq!(output.unwrap(), "com.example\n");
+}
+
+#[test]
+fn test_run_async_replay() {
+    let mut replay = ReplayExecutor::new();
+    replay
+        .expect("aapt2", &["dump", "packagename", "app.apk"])
+        .stdout("com.example\n");
+    let replay = Arc::new(replay);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let output = with_executor(replay.clone(), || {
+        runtime.block_on(
+            Aapt2
+                .dump(SubCommand::Packagename, Path::new("app.apk"))
+                .run_async(),
+        )
+    });
+    assert_eq!(output.unwrap(), "com.example\n");
+    assert!(replay.remaining().is_empty());
+}
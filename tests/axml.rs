@@ -0,0 +1,270 @@
+use android_tools::axml::{
+    ResValue, StringPool, StringSpan, XmlAttribute, XmlDocument, XmlElement, XmlNamespace, XmlNode,
+    ANDROID_NS, ATTR_DEBUGGABLE, ATTR_NAME, ATTR_VERSION_CODE, ATTR_VERSION_NAME,
+};
+use std::path::Path;
+
+fn manifest() -> XmlDocument {
+    let mut activity = XmlElement::new("activity");
+    activity
+        .set_attribute(XmlAttribute::android(
+            "name",
+            ATTR_NAME,
+            ResValue::String(".MainActivity".to_owned()),
+        ))
+        .line_number = 5;
+    let mut application = XmlElement::new("application");
+    // Attributes are in the order they are written in, sorted by resource ID
+    application.set_attribute(XmlAttribute::android(
+        "label",
+        0x0101_0001,
+        ResValue::Reference(0x7f01_0000),
+    ));
+    application.set_attribute(XmlAttribute::android(
+        "debuggable",
+        ATTR_DEBUGGABLE,
+        ResValue::Boolean(true),
+    ));
+    application.children = vec![
+        XmlNode::Element(activity),
+        XmlNode::Text("Привет & <hello>".to_owned()),
+    ];
+    application.line_number = 4;
+    let mut root = XmlElement::new("manifest");
+    root.namespaces.push(XmlNamespace {
+        prefix: "android".to_owned(),
+        uri: ANDROID_NS.to_owned(),
+    });
+    root.set_attribute(XmlAttribute::android(
+        "versionCode",
+        ATTR_VERSION_CODE,
+        ResValue::Int(1),
+    ));
+    root.set_attribute(XmlAttribute::android(
+        "versionName",
+        ATTR_VERSION_NAME,
+        ResValue::String("1.0".to_owned()),
+    ));
+    // Attribute named like the android one, but without resource ID
+    root.set_attribute(XmlAttribute {
+        namespace: None,
+        name: "name".to_owned(),
+        resource_id: None,
+        raw_value: None,
+        value: ResValue::Hex(0xff),
+    });
+    root.set_attribute(XmlAttribute {
+        namespace: None,
+        name: "package".to_owned(),
+        resource_id: None,
+        raw_value: Some("com.example".to_owned()),
+        value: ResValue::String("com.example".to_owned()),
+    });
+    root.children.push(XmlNode::Element(application));
+    root.line_number = 2;
+    XmlDocument { root }
+}
+
+#[test]
+fn test_binary_xml_round_trip() {
+    let document = manifest();
+    let data = document.to_bytes().unwrap();
+    // RES_XML_TYPE chunk with the size of the data
+    assert_eq!(&data[..4], &[0x03, 0x00, 0x08, 0x00]);
+    assert_eq!(
+        u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize,
+        data.len()
+    );
+    let parsed = XmlDocument::from_bytes(&data).unwrap();
+    assert_eq!(parsed, document);
+    assert_eq!(parsed.to_bytes().unwrap(), data);
+
+    let root = &parsed.root;
+    assert_eq!(
+        root.attribute("package").unwrap().value.to_string(),
+        "com.example"
+    );
+    assert_eq!(root.attribute("name").unwrap().resource_id, None);
+    let activity = root
+        .element("application")
+        .unwrap()
+        .element("activity")
+        .unwrap();
+    assert_eq!(
+        activity.attribute("name").unwrap().resource_id,
+        Some(ATTR_NAME)
+    );
+    assert_eq!(activity.line_number, 5);
+}
+
+#[test]
+/// Manifest laid out like aapt2 writes it, see `tests/resources/axml/generate.py`
+fn test_parse_compiled_manifest() {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/resources/axml/AndroidManifest.xml");
+    let data = std::fs::read(path).unwrap();
+    let document = XmlDocument::from_bytes(&data).unwrap();
+    let root = &document.root;
+    assert_eq!(root.name, "manifest");
+    assert_eq!(root.line_number, 2);
+    assert_eq!(
+        root.namespaces,
+        [XmlNamespace {
+            prefix: "android".to_owned(),
+            uri: ANDROID_NS.to_owned(),
+        }]
+    );
+    let names: Vec<_> = root
+        .attributes
+        .iter()
+        .map(|attribute| attribute.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        ["versionCode", "versionName", "compileSdkVersion", "package"]
+    );
+    let version_code = root.attribute("versionCode").unwrap();
+    assert_eq!(version_code.namespace.as_deref(), Some(ANDROID_NS));
+    assert_eq!(version_code.resource_id, Some(ATTR_VERSION_CODE));
+    assert_eq!(version_code.raw_value, None);
+    assert_eq!(version_code.value, ResValue::Int(1));
+    let package = root.attribute("package").unwrap();
+    assert_eq!(package.namespace, None);
+    assert_eq!(package.resource_id, None);
+    assert_eq!(package.raw_value.as_deref(), Some("com.example.fixture"));
+
+    let uses_sdk = root.element("uses-sdk").unwrap();
+    assert_eq!(
+        uses_sdk.attribute("minSdkVersion").unwrap().value,
+        ResValue::Int(21)
+    );
+    let application = root.element("application").unwrap();
+    assert_eq!(
+        application.attribute("label").unwrap().value,
+        ResValue::Reference(0x7f0b_0000)
+    );
+    assert_eq!(
+        application.attribute("debuggable").unwrap().value,
+        ResValue::Boolean(true)
+    );
+    let activity = application.element("activity").unwrap();
+    assert_eq!(activity.line_number, 9);
+    assert_eq!(
+        activity.attribute("name").unwrap().value,
+        ResValue::String("com.example.fixture.MainActivity".to_owned())
+    );
+    let intent_filter = activity.element("intent-filter").unwrap();
+    assert_eq!(intent_filter.children.len(), 2);
+    assert_eq!(
+        intent_filter
+            .element("category")
+            .unwrap()
+            .attribute("name")
+            .unwrap()
+            .value
+            .to_string(),
+        "android.intent.category.LAUNCHER"
+    );
+
+    // The tree survives writing with the crate's own layout
+    let written = document.to_bytes().unwrap();
+    assert_eq!(XmlDocument::from_bytes(&written).unwrap(), document);
+}
+
+#[test]
+fn test_patch_manifest() {
+    let data = manifest().to_bytes().unwrap();
+    let mut document = XmlDocument::from_bytes(&data).unwrap();
+    document.root.set_attribute(XmlAttribute::android(
+        "versionCode",
+        ATTR_VERSION_CODE,
+        ResValue::Int(42),
+    ));
+    document
+        .root
+        .element_mut("application")
+        .unwrap()
+        .remove_attribute("debuggable")
+        .unwrap();
+
+    let patched = XmlDocument::from_bytes(&document.to_bytes().unwrap()).unwrap();
+    assert_eq!(
+        patched.root.attribute("versionCode").unwrap().value,
+        ResValue::Int(42)
+    );
+    let application = patched.root.element("application").unwrap();
+    assert!(application.attribute("debuggable").is_none());
+    assert_eq!(application.attributes.len(), 1);
+}
+
+#[test]
+fn test_display_binary_xml() {
+    let text = manifest().to_string();
+    assert!(text.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<manifest xmlns:android=\"http://schemas.android.com/apk/res/android\""));
+    assert!(text.contains(" android:versionCode=\"1\""));
+    assert!(text.contains(" android:versionName=\"1.0\""));
+    assert!(text.contains("    <activity android:name=\".MainActivity\"/>\n"));
+    assert!(text.contains("android:debuggable=\"true\""));
+    assert!(text.contains("android:label=\"@0x7f010000\""));
+    assert!(text.contains("Привет &amp; &lt;hello&gt;"));
+    assert!(text.ends_with("</manifest>\n"));
+}
+
+#[test]
+fn test_string_pool() {
+    // UTF-8 pool with "a" and "é", written by hand
+    let data = [
+        0x01, 0x00, 0x1c, 0x00, 0x30, 0x00, 0x00, 0x00, // header
+        0x02, 0x00, 0x00, 0x00, // string count
+        0x00, 0x00, 0x00, 0x00, // style count
+        0x00, 0x01, 0x00, 0x00, // UTF-8 flag
+        0x24, 0x00, 0x00, 0x00, // strings start
+        0x00, 0x00, 0x00, 0x00, // styles start
+        0x00, 0x00, 0x00, 0x00, // offset of "a"
+        0x04, 0x00, 0x00, 0x00, // offset of "é"
+        0x01, 0x01, b'a', 0x00, 0x01, 0x02, 0xc3, 0xa9, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let pool = StringPool::from_bytes(&data).unwrap();
+    assert_eq!(pool.strings, ["a", "é"]);
+    assert!(pool.utf8);
+    assert_eq!(pool.to_bytes().unwrap(), data);
+
+    let long = "x".repeat(300);
+    for utf8 in [true, false] {
+        let pool = StringPool {
+            strings: vec![long.clone(), "b".to_owned(), "😀".to_owned()],
+            styles: vec![vec![StringSpan {
+                name: 1,
+                first_char: 0,
+                last_char: 4,
+            }]],
+            utf8,
+            sorted: false,
+        };
+        assert_eq!(
+            StringPool::from_bytes(&pool.to_bytes().unwrap()).unwrap(),
+            pool
+        );
+    }
+
+    // UTF-8 pools can't encode strings longer than 0x7fff bytes, so it's written in
+    // UTF-16
+    let pool = StringPool {
+        strings: vec!["é".repeat(0x4000), "b".to_owned()],
+        utf8: true,
+        ..Default::default()
+    };
+    let written = StringPool::from_bytes(&pool.to_bytes().unwrap()).unwrap();
+    assert!(!written.utf8);
+    assert_eq!(written.strings, pool.strings);
+}
+
+#[test]
+fn test_res_value_display() {
+    // 16dp and 50%
+    assert_eq!(ResValue::Dimension(0x1001).to_string(), "16dp");
+    assert_eq!(ResValue::Fraction(0x4000_0030).to_string(), "50%");
+    assert_eq!(ResValue::ColorRgb8(0xff11_2233).to_string(), "#112233");
+    assert_eq!(ResValue::Empty.to_string(), "@empty");
+    assert!(XmlDocument::from_bytes(b"not binary xml").is_err());
+}
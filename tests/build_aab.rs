@@ -75,7 +75,7 @@ fn test_build_android_app_bundle() {
 
     // Extracts files from APK to defined output directory and prepares files to generate archive
     let filename = std::path::Path::new(&gen_apk);
-    let file = std::fs::File::open(&filename).unwrap();
+    let file = std::fs::File::open(filename).unwrap();
     let mut apk = zip::ZipArchive::new(file).unwrap();
     apk.extract(&output_dir).unwrap();
     let path = output_dir.join("AndroidManifest.xml");
@@ -85,7 +85,7 @@ fn test_build_android_app_bundle() {
     }
     let mut options = fs_extra::file::CopyOptions::new();
     options.overwrite = true;
-    fs_extra::file::move_file(&path, &manifest_path.join("AndroidManifest.xml"), &options).unwrap();
+    fs_extra::file::move_file(&path, manifest_path.join("AndroidManifest.xml"), &options).unwrap();
 
     // Generates ZIP archive from extracted files
     let zip_path = build_dir.join("extracted_files.zip");
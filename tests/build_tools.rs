@@ -0,0 +1,153 @@
+use android_tools::{
+    build_tools::{Apksigner, ApksignerSigner, ApksignerVerification, Zipalign},
+    executor::{with_executor, ReplayExecutor},
+};
+use std::{path::Path, sync::Arc};
+
+const VERIFY_OUTPUT: &str = "Verifies
+Verified using v1 scheme (JAR signing): false
+Verified using v2 scheme (APK Signature Scheme v2): true
+Verified using v3 scheme (APK Signature Scheme v3): true
+Verified using v3.1 scheme (APK Signature Scheme v3.1): false
+Verified using v4 scheme (APK Signature Scheme v4): false
+Verified for SourceStamp: false
+Number of signers: 1
+Signer #1 certificate DN: CN=Android Debug, O=Android, C=US
+Signer #1 certificate SHA-256 digest: 6a8a9f0ebf0e4b1d3c0e2a2a0f4c4e7c7d0b8a2d1e3f4a5b6c7d8e9f0a1b2c3d
+Signer #1 certificate SHA-1 digest: 8f2c5b3e1a0d9c7b6a5f4e3d2c1b0a9f8e7d6c5b
+Signer #1 certificate MD5 digest: 0a1b2c3d4e5f60718293a4b5c6d7e8f9
+Signer #1 key algorithm: RSA
+Signer #1 key size (bits): 2048
+Signer #1 public key SHA-256 digest: 1f2e3d4c5b6a79880716253443526170fedcba9876543210fedcba9876543210
+WARNING: META-INF/com/android/build/gradle/app-metadata.properties not protected by signature.
+";
+
+#[test]
+fn test_parse_apksigner_verify_output() {
+    let verification = ApksignerVerification::parse_output(VERIFY_OUTPUT);
+    assert_eq!(verification.verified_schemes, ["v2", "v3"]);
+    assert_eq!(verification.signers.len(), 1);
+    let signer = &verification.signers[0];
+    assert_eq!(signer.index, 1);
+    assert_eq!(signer.dn, "CN=Android Debug, O=Android, C=US");
+    assert_eq!(
+        signer.sha256_digest.as_deref(),
+        Some("6a8a9f0ebf0e4b1d3c0e2a2a0f4c4e7c7d0b8a2d1e3f4a5b6c7d8e9f0a1b2c3d")
+    );
+    assert_eq!(signer.key_algorithm.as_deref(), Some("RSA"));
+    assert_eq!(signer.key_size, Some(2048));
+    assert_eq!(verification.warnings.len(), 1);
+}
+
+#[test]
+/// Builders pass signer options in order and parse the tool output
+fn test_apksigner_and_zipalign_commands() {
+    let mut replay = ReplayExecutor::new();
+    replay.expect(
+        "zipalign",
+        &["-p", "-f", "4", "app-unaligned.apk", "app-aligned.apk"],
+    );
+    replay.expect(
+        "apksigner",
+        &[
+            "sign",
+            "--ks",
+            "old.keystore",
+            "--ks-pass",
+            "pass:android",
+            "--next-signer",
+            "--ks",
+            "new.keystore",
+            "--ks-key-alias",
+            "key0",
+            "--key-pass",
+            "file:key.txt",
+            "--out",
+            "app.apk",
+            "--v1-signing-enabled",
+            "false",
+            "--v4-signing-enabled",
+            "false",
+            "--lineage",
+            "lineage",
+            "app-aligned.apk",
+        ],
+    );
+    replay
+        .expect(
+            "apksigner",
+            &[
+                "verify",
+                "--print-certs",
+                "-v",
+                "--min-sdk-version",
+                "24",
+                "app.apk",
+            ],
+        )
+        .stdout(VERIFY_OUTPUT);
+    replay
+        .expect("zipalign", &["-c", "-v", "4", "app.apk"])
+        .stdout(
+            "Verifying alignment of app.apk (4)...
+      50 META-INF/MANIFEST.MF (OK - compressed)
+     523 classes.dex (BAD - 3)
+    9180 resources.arsc (OK)
+Verification FAILED
+",
+        )
+        .exit_code(1);
+    replay
+        .expect("zipalign", &["-c", "-p", "-v", "4", "missing.apk"])
+        .stderr("Unable to open 'missing.apk' as zip archive\n")
+        .exit_code(1);
+    let replay = Arc::new(replay);
+    with_executor(replay.clone(), || {
+        Zipalign::new(
+            4,
+            Path::new("app-unaligned.apk"),
+            Path::new("app-aligned.apk"),
+        )
+        .p(true)
+        .f(true)
+        .run()
+        .unwrap();
+
+        let mut old_signer = ApksignerSigner::new();
+        old_signer
+            .ks(Path::new("old.keystore"))
+            .ks_pass_pass("android".to_owned());
+        let mut new_signer = ApksignerSigner::new();
+        new_signer
+            .ks(Path::new("new.keystore"))
+            .ks_key_alias("key0".to_owned())
+            .key_pass_file(Path::new("key.txt"));
+        let signed = Apksigner
+            .sign(Path::new("app-aligned.apk"), &old_signer)
+            .next_signer(&new_signer)
+            .out(Path::new("app.apk"))
+            .v1_signing_enabled(false)
+            .v4_signing_enabled(false)
+            .lineage(Path::new("lineage"))
+            .run()
+            .unwrap();
+        assert_eq!(signed, Path::new("app.apk"));
+
+        let verification = Apksigner
+            .verify(Path::new("app.apk"))
+            .min_sdk_version(24)
+            .run()
+            .unwrap();
+        assert_eq!(
+            verification.signers[0].dn,
+            "CN=Android Debug, O=Android, C=US"
+        );
+
+        assert!(!Zipalign::check(4, Path::new("app.apk")).run().unwrap());
+        assert!(Zipalign::check(4, Path::new("missing.apk"))
+            .p(true)
+            .run()
+            .is_err());
+    });
+    assert!(replay.remaining().is_empty());
+}
@@ -0,0 +1,128 @@
+use android_tools::error::{parse_diagnostics, redact_args, Diagnostic, Severity};
+use std::path::PathBuf;
+
+#[test]
+fn test_redact_args() {
+    let args = [
+        "jarsigner",
+        "-storepass",
+        "android",
+        "-keypass",
+        "secret",
+        "app.apk",
+        "--ks-pass",
+        "pass:android",
+        "--key-pass=file:key.txt",
+        "--ks-pass=pass:android",
+    ];
+    assert_eq!(
+        redact_args(&args),
+        vec![
+            "jarsigner",
+            "-storepass",
+            "***",
+            "-keypass",
+            "***",
+            "app.apk",
+            "--ks-pass",
+            "pass:***",
+            "--key-pass=file:key.txt",
+            "--ks-pass=pass:***",
+        ]
+    );
+}
+
+#[test]
+/// aapt2 diagnostics keep the location of the resource, other tools only the message
+fn test_parse_diagnostics() {
+    let output = "res/values/strings.xml:3: error: invalid resource.
+res/layout/main.xml: warn: unused attribute.
+error: failed linking file resources.
+";
+    assert_eq!(
+        parse_diagnostics("aapt2", output),
+        vec![
+            Diagnostic {
+                file: Some(PathBuf::from("res/values/strings.xml")),
+                line: Some(3),
+                severity: Severity::Error,
+                message: "invalid resource.".to_owned(),
+            },
+            Diagnostic {
+                file: Some(PathBuf::from("res/layout/main.xml")),
+                line: None,
+                severity: Severity::Warning,
+                message: "unused attribute.".to_owned(),
+            },
+            Diagnostic {
+                file: None,
+                line: None,
+                severity: Severity::Error,
+                message: "failed linking file resources.".to_owned(),
+            },
+        ]
+    );
+    let diagnostics = parse_diagnostics(
+        "keytool",
+        "keytool error: java.lang.Exception: Alias <key> does not exist",
+    );
+    assert_eq!(
+        diagnostics[0].message,
+        "java.lang.Exception: Alias <key> does not exist"
+    );
+}
+
+#[cfg(unix)]
+fn failing_aapt2(dir: &std::path::Path) -> std::process::Command {
+    use std::os::unix::fs::PermissionsExt;
+
+    let aapt2 = dir.join("aapt2");
+    std::fs::write(
+        &aapt2,
+        "#!/bin/sh\necho 'res/values/strings.xml:3: error: invalid resource.' >&2\nexit 1\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&aapt2, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::process::Command::new(&aapt2)
+}
+
+#[cfg(unix)]
+#[test]
+fn test_output_err_command_failure() {
+    use android_tools::error::{CommandExt, Error};
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut command = failing_aapt2(dir.path());
+    command.args(["compile", "-storepass", "android"]);
+    let Err(Error::CmdFailed(failure)) = command.output_err(false) else {
+        panic!("aapt2 should fail");
+    };
+    assert_eq!(failure.tool, "aapt2");
+    assert_eq!(failure.args[1..], ["compile", "-storepass", "***"]);
+    assert_eq!(failure.exit_code, Some(1));
+    assert_eq!(failure.signal, None);
+    assert_eq!(failure.diagnostics[0].line, Some(3));
+}
+
+#[cfg(unix)]
+#[test]
+/// Printed output is captured as well, so the failure still carries diagnostics
+fn test_output_err_command_failure_with_logs() {
+    use android_tools::error::{CommandExt, Error};
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut command = failing_aapt2(dir.path());
+    command.arg("compile");
+    let Err(Error::CmdFailed(failure)) = command.output_err(true) else {
+        panic!("aapt2 should fail");
+    };
+    assert_eq!(
+        failure.stderr,
+        "res/values/strings.xml:3: error: invalid resource.\n"
+    );
+    assert_eq!(failure.diagnostics.len(), 1);
+    assert_eq!(
+        failure.diagnostics[0].file,
+        Some(PathBuf::from("res/values/strings.xml"))
+    );
+}
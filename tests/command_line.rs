@@ -0,0 +1,39 @@
+use android_tools::{
+    bundletool::BuildApks,
+    executor::{with_executor, ReplayExecutor},
+    java_tools::JarSigner,
+};
+use std::{path::Path, sync::Arc};
+
+#[test]
+/// Commands are rendered without running the tools and passwords are masked
+fn test_command_line() {
+    with_executor(Arc::new(ReplayExecutor::new()), || {
+        let command_line = BuildApks::new(Path::new("app.aab"), Path::new("my app.apks"))
+            .ks(Path::new("release.keystore"))
+            .ks_pass_pass("secret".to_owned())
+            .ks_key_alias("key0".to_owned())
+            .key_pass_file(Path::new("key.txt"))
+            .command_line()
+            .unwrap();
+        assert_eq!(command_line.program, Path::new("bundletool"));
+        assert_eq!(command_line.args[4], "my app.apks");
+        assert_eq!(
+            command_line.to_string(),
+            "bundletool build-apks --bundle app.aab --output 'my app.apks' \
+             --ks release.keystore --ks-pass 'pass:***' --ks-key-alias key0 \
+             --key-pass file:key.txt"
+        );
+
+        let command_line = JarSigner::new(Path::new("app.aab"), "key0")
+            .keystore(Path::new("release.keystore"))
+            .storepass("it's secret".to_owned())
+            .keypass("secret".to_owned())
+            .command_line()
+            .unwrap();
+        let rendered = command_line.to_shell_string();
+        assert!(!rendered.contains("secret"), "{}", rendered);
+        assert!(rendered.contains("-storepass '***'"), "{}", rendered);
+        assert!(rendered.contains("-keypass '***'"), "{}", rendered);
+    });
+}
@@ -0,0 +1,112 @@
+//! Helpers shared by integration tests that run fake tools
+#![allow(dead_code)]
+
+use android_tools::{
+    error::Result,
+    executor::{ExecOptions, ProcessExecutor, ToolExecutor, ToolProcess},
+};
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+/// Executor that runs fake tools written to a temporary directory instead of looking
+/// them up in `PATH` or Android SDK, so tests don't change the environment
+pub struct FakeTools {
+    dir: tempfile::TempDir,
+}
+
+impl FakeTools {
+    pub fn new() -> Self {
+        Self {
+            dir: tempfile::tempdir().unwrap(),
+        }
+    }
+
+    /// Directory of the fake tools, also free for other files of the test
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Writes the shell script run for the tool and returns its path
+    #[cfg(unix)]
+    pub fn add(&self, tool: &str, script: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = self.dir.path().join(tool);
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+}
+
+impl ToolExecutor for FakeTools {
+    fn locate(&self, tool: &str, _locate: &dyn Fn() -> Result<Command>) -> Result<Command> {
+        Ok(Command::new(self.dir.path().join(tool)))
+    }
+
+    fn execute(&self, command: &mut Command, options: &ExecOptions) -> Result<Output> {
+        ProcessExecutor.execute(command, options)
+    }
+
+    #[cfg(feature = "async")]
+    fn execute_async<'a>(
+        &'a self,
+        command: &'a mut Command,
+        options: &'a ExecOptions,
+    ) -> android_tools::executor::BoxFuture<'a, Result<Output>> {
+        ProcessExecutor.execute_async(command, options)
+    }
+
+    fn spawn(&self, command: &mut Command) -> Result<Box<dyn ToolProcess>> {
+        ProcessExecutor.spawn(command)
+    }
+}
+
+/// Serializes tests of the same binary that change environment variables
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Lock of the process environment for tests that check how tools are looked up.
+/// Variables changed through it are restored when it's dropped
+pub struct EnvGuard {
+    saved: Vec<(OsString, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl EnvGuard {
+    pub fn lock() -> Self {
+        Self {
+            saved: Vec::new(),
+            _lock: ENV_LOCK.lock().unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+
+    pub fn set_var(&mut self, key: &str, value: impl AsRef<OsStr>) {
+        self.save(key);
+        std::env::set_var(key, value);
+    }
+
+    pub fn remove_var(&mut self, key: &str) {
+        self.save(key);
+        std::env::remove_var(key);
+    }
+
+    fn save(&mut self, key: &str) {
+        if self.saved.iter().all(|(saved, _)| saved != key) {
+            self.saved.push((key.into(), std::env::var_os(key)));
+        }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        for (key, value) in self.saved.drain(..).rev() {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+#![cfg(unix)]
+
+mod common;
+
+use android_tools::{
+    adb::AdbPull,
+    error::{CommandExt, Error},
+    executor::{with_executor, ExecOptions, OutputStream},
+};
+use common::FakeTools;
+use std::{
+    path::Path,
+    process::Command,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[test]
+/// Lines are passed to the callback while the output is still captured
+fn test_exec_options_on_line() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let on_line = lines.clone();
+    let mut sh = Command::new("sh");
+    sh.args(["-c", "echo first; echo error >&2; echo second"]);
+    let output = sh
+        .output_with(
+            ExecOptions::new().on_line(move |stream, line| {
+                on_line.lock().unwrap().push((stream, line.to_owned()))
+            }),
+        )
+        .unwrap();
+    assert_eq!(output.stdout, b"first\nsecond\n");
+    assert_eq!(output.stderr, b"error\n");
+    let lines = lines.lock().unwrap();
+    assert_eq!(lines.len(), 3);
+    assert!(lines.contains(&(OutputStream::Stdout, "first".to_owned())));
+    assert!(lines.contains(&(OutputStream::Stderr, "error".to_owned())));
+}
+
+#[test]
+/// Output written in parts is passed to the callback by lines, the last line may have
+/// no line ending like password prompts
+fn test_exec_options_on_line_partial() {
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    let on_line = lines.clone();
+    let mut sh = Command::new("sh");
+    sh.args([
+        "-c",
+        "printf 'first '; sleep 0.1; printf 'line\\nsecond\\n'; sleep 0.1; printf 'Enter password: '",
+    ]);
+    let output = sh
+        .output_with(
+            ExecOptions::new()
+                .on_line(move |_, line| on_line.lock().unwrap().push(line.to_owned())),
+        )
+        .unwrap();
+    assert_eq!(output.stdout, b"first line\nsecond\nEnter password: ");
+    assert_eq!(
+        *lines.lock().unwrap(),
+        ["first line", "second", "Enter password: "]
+    );
+}
+
+#[test]
+fn test_exec_options_timeout() {
+    let started = Instant::now();
+    let mut sleep = Command::new("sleep");
+    sleep.arg("10");
+    let result = sleep.output_with(ExecOptions::new().timeout(Duration::from_millis(100)));
+    let Err(Error::CmdTimeout(command, timeout)) = result else {
+        panic!("sleep should time out");
+    };
+    assert_eq!(command, "sleep 10");
+    assert_eq!(timeout, Duration::from_millis(100));
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    let mut echo = Command::new("echo");
+    echo.arg("done");
+    let output = echo
+        .output_with(ExecOptions::new().timeout(Duration::from_secs(10)))
+        .unwrap();
+    assert_eq!(output.stdout, b"done\n");
+}
+
+#[test]
+/// Options passed to the typed API apply to the tool command
+fn test_run_with_timeout() {
+    let tools = Arc::new(FakeTools::new());
+    tools.add("adb", "#!/bin/sh\nsleep 10\n");
+
+    let started = Instant::now();
+    let result = with_executor(tools.clone(), || {
+        AdbPull::new(Path::new("/sdcard/report.txt"), tools.path())
+            .run_with(ExecOptions::new().timeout(Duration::from_millis(100)))
+    });
+    assert!(matches!(result, Err(Error::CmdTimeout(_, _))));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
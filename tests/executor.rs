@@ -0,0 +1,97 @@
+use android_tools::{
+    aapt2::{Aapt2, SubCommand},
+    bundletool::BuildBundle,
+    error::{CommandExt, Error},
+    executor::{with_executor, RecordingExecutor, ReplayExecutor},
+};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+};
+
+#[test]
+/// Builders run without Android SDK or bundletool installed
+fn test_replay_executor() {
+    let mut replay = ReplayExecutor::new();
+    replay
+        .expect("aapt2", &["dump", "badging", "app.apk"])
+        .stdout("package: name='com.example' versionCode='2' versionName='1.1'\n");
+    replay.expect(
+        "bundletool",
+        &[
+            "build-bundle",
+            "--modules",
+            "base.zip",
+            "--output",
+            "app.aab",
+        ],
+    );
+    let replay = Arc::new(replay);
+    with_executor(replay.clone(), || {
+        let badging = Aapt2
+            .dump(SubCommand::Badging, Path::new("app.apk"))
+            .badging()
+            .unwrap();
+        assert_eq!(badging.package, "com.example");
+        assert_eq!(badging.version_code, Some(2));
+        BuildBundle::new(&[PathBuf::from("base.zip")], Path::new("app.aab"))
+            .run()
+            .unwrap();
+    });
+    assert!(replay.remaining().is_empty());
+}
+
+#[test]
+fn test_replay_executor_failures() {
+    let mut replay = ReplayExecutor::new();
+    replay
+        .expect("aapt2", &["dump", "packagename", "app.apk"])
+        .stderr("app.apk: error: failed to open APK.\n")
+        .exit_code(1);
+    let replay = Arc::new(replay);
+    with_executor(replay.clone(), || {
+        let Err(Error::UnexpectedCommand(actual, expected)) = Aapt2
+            .dump(SubCommand::Packagename, Path::new("other.apk"))
+            .packagename()
+        else {
+            panic!("command should not match");
+        };
+        assert_eq!(actual, "aapt2 dump packagename other.apk");
+        assert_eq!(expected, "aapt2 dump packagename app.apk");
+        let Err(Error::CmdFailed(failure)) = Aapt2
+            .dump(SubCommand::Packagename, Path::new("app.apk"))
+            .packagename()
+        else {
+            panic!("aapt2 should fail");
+        };
+        assert_eq!(failure.exit_code, Some(1));
+        assert_eq!(failure.diagnostics[0].message, "failed to open APK.");
+    });
+}
+
+#[cfg(unix)]
+#[test]
+/// Recorded invocations can be replayed later
+fn test_recording_executor() {
+    let recording = Arc::new(RecordingExecutor::default());
+    with_executor(recording.clone(), || {
+        let mut echo = Command::new("echo");
+        echo.arg("hello");
+        echo.output_err(false).unwrap();
+    });
+    let invocations = recording.invocations();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(invocations[0].tool, "echo");
+    assert_eq!(invocations[0].args, ["hello"]);
+    assert_eq!(invocations[0].stdout, b"hello\n");
+
+    let mut replay = ReplayExecutor::new();
+    replay.expect_all(invocations);
+    let output = with_executor(Arc::new(replay), || {
+        let mut echo = Command::new("echo");
+        echo.arg("hello");
+        echo.output_err(false)
+    });
+    assert_eq!(output.unwrap().stdout, b"hello\n");
+}
@@ -31,10 +31,10 @@ fn test_sign_application_with_jarsigner() {
 
     // Removes old keystore if it exists
     let android_dir = android_dir().unwrap();
-    let target = vec![android_dir.join("aab.keystore")];
+    let target = [android_dir.join("aab.keystore")];
     target.iter().for_each(|content| {
         if content.is_file() {
-            std::fs::remove_file(&content).unwrap();
+            std::fs::remove_file(content).unwrap();
         }
     });
 
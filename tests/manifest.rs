@@ -0,0 +1,141 @@
+#![cfg(feature = "manifest")]
+
+use android_tools::{
+    aapt2::Aapt2Link,
+    executor::{with_executor, ReplayExecutor},
+    manifest::{
+        Activity, AndroidManifest, IntentFilter, MetaData, Service, UsesFeature, UsesPermission,
+        UsesSdk,
+    },
+};
+use std::{path::PathBuf, sync::Arc};
+
+#[test]
+fn test_parse_manifest() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("resources")
+        .join("manifest")
+        .join("AndroidManifest.xml");
+    let manifest = AndroidManifest::from_file(&path).unwrap();
+    assert_eq!(manifest.package, "com.rust.bevy_2d");
+    assert_eq!(manifest.version_code, Some(1));
+    assert_eq!(manifest.version_name.as_deref(), Some("0.1.0"));
+    assert_eq!(
+        manifest.uses_sdk,
+        Some(UsesSdk {
+            min_sdk_version: Some(9),
+            target_sdk_version: Some(30),
+            max_sdk_version: None,
+        })
+    );
+    assert_eq!(
+        manifest.uses_feature[0].gl_es_version.as_deref(),
+        Some("0x00030002")
+    );
+    assert_eq!(manifest.application.debuggable, Some(true));
+    assert_eq!(manifest.application.has_code, Some(false));
+    let activity = &manifest.application.activities[0];
+    assert_eq!(activity.name, "android.app.NativeActivity");
+    assert_eq!(activity.intent_filters, [IntentFilter::launcher()]);
+    assert_eq!(
+        activity.meta_data,
+        [MetaData::new("android.app.lib_name", "bevy_2d")]
+    );
+    assert_eq!(
+        AndroidManifest::from_xml(&manifest.to_xml().unwrap()).unwrap(),
+        manifest
+    );
+}
+
+#[test]
+/// Unknown elements and attributes are skipped and components may be interleaved
+fn test_parse_unknown_and_interleaved_elements() {
+    let xml = r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    xmlns:tools="http://schemas.android.com/tools" package="com.example">
+    <uses-permission android:name="android.permission.INTERNET" />
+    <supports-screens android:anyDensity="true" />
+    <uses-permission android:name="android.permission.CAMERA" android:maxSdkVersion="28" />
+    <application android:label="@string/app_name" tools:replace="android:label">
+        <service android:name=".First" />
+        <activity android:name=".Main" />
+        <service android:name=".Second" android:exported="false" />
+    </application>
+</manifest>"#;
+    let manifest = AndroidManifest::from_xml(xml).unwrap();
+    assert_eq!(manifest.uses_permission.len(), 2);
+    assert_eq!(manifest.uses_permission[1].max_sdk_version, Some(28));
+    assert_eq!(
+        manifest.application.label.as_deref(),
+        Some("@string/app_name")
+    );
+    assert_eq!(manifest.application.activities, [Activity::new(".Main")]);
+    let services: Vec<_> = manifest
+        .application
+        .services
+        .iter()
+        .map(|service| service.name.as_str())
+        .collect();
+    assert_eq!(services, [".First", ".Second"]);
+    assert!(AndroidManifest::from_xml("<manifest>").is_err());
+}
+
+#[test]
+fn test_generate_manifest() {
+    let mut manifest = AndroidManifest {
+        package: "com.example".to_owned(),
+        version_code: Some(2),
+        uses_permission: vec![UsesPermission::new("android.permission.INTERNET")],
+        uses_feature: vec![UsesFeature {
+            name: Some("android.hardware.vulkan.level".to_owned()),
+            required: Some(false),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    manifest.application.services.push(Service {
+        name: ".Sync".to_owned(),
+        exported: Some(false),
+        ..Default::default()
+    });
+    let xml = manifest.to_xml().unwrap();
+    assert_eq!(
+        xml,
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example" android:versionCode="2">
+    <uses-permission android:name="android.permission.INTERNET"/>
+    <uses-feature android:name="android.hardware.vulkan.level" android:required="false"/>
+    <application>
+        <service android:name=".Sync" android:exported="false"/>
+    </application>
+</manifest>
+"#
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let output_apk = dir.path().join("app.apk");
+    let manifest_path = dir.path().join("generated").join("AndroidManifest.xml");
+    let mut replay = ReplayExecutor::new();
+    replay.expect(
+        "aapt2",
+        &[
+            "link",
+            "-o",
+            output_apk.to_str().unwrap(),
+            "--manifest",
+            manifest_path.to_str().unwrap(),
+        ],
+    );
+    let replay = Arc::new(replay);
+    with_executor(replay.clone(), || {
+        Aapt2Link::new_with_manifest(&[], &output_apk, &manifest, &manifest_path)
+            .unwrap()
+            .run()
+    })
+    .unwrap();
+    assert!(replay.remaining().is_empty());
+    assert_eq!(
+        AndroidManifest::from_file(&manifest_path).unwrap(),
+        manifest
+    );
+}
@@ -0,0 +1,386 @@
+#![cfg(feature = "manifest")]
+
+use android_tools::{
+    aapt2::Aapt2Link,
+    error::{Error, Severity},
+    executor::{with_executor, ReplayExecutor},
+    manifest::{AndroidManifest, ManifestMerger},
+};
+use std::{path::PathBuf, sync::Arc};
+
+const MAIN: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    xmlns:tools="http://schemas.android.com/tools"
+    package="com.example.app" android:versionCode="1">
+    <uses-sdk android:minSdkVersion="21" android:targetSdkVersion="33" />
+    <uses-permission android:name="android.permission.INTERNET" />
+    <uses-permission android:name="android.permission.READ_CONTACTS" tools:node="remove" />
+    <application android:label="App" tools:replace="android:label">
+        <activity android:name=".Main" android:exported="true">
+            <intent-filter>
+                <action android:name="android.intent.action.MAIN" />
+                <category android:name="android.intent.category.LAUNCHER" />
+            </intent-filter>
+        </activity>
+    </application>
+</manifest>
+"#;
+
+const LIBRARY: &str = r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.billing" android:versionCode="7">
+    <uses-sdk android:minSdkVersion="19" />
+    <uses-permission android:name="android.permission.INTERNET" />
+    <uses-permission android:name="android.permission.READ_CONTACTS" />
+    <uses-permission android:name="com.android.vending.BILLING" />
+    <application android:label="Billing" android:allowBackup="false">
+        <activity android:name=".ProxyActivity" android:exported="false" />
+        <provider android:name=".BillingProvider"
+            android:authorities="${applicationId}.billing" />
+    </application>
+</manifest>
+"#;
+
+fn write(dir: &std::path::Path, name: &str, xml: &str) -> PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, xml).unwrap();
+    path
+}
+
+#[test]
+/// Library elements are added to the app manifest unless removed with `tools:node`, and
+/// conflicting attributes are resolved with `tools:replace`
+fn test_merge_library_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = write(dir.path(), "main.xml", MAIN);
+    let library = write(dir.path(), "library.xml", LIBRARY);
+    let merged = ManifestMerger::new(&main).library(&library).run().unwrap();
+    assert!(!merged.xml.contains("tools"));
+
+    let manifest = AndroidManifest::from_xml(&merged.xml).unwrap();
+    assert_eq!(manifest.package, "com.example.app");
+    assert_eq!(manifest.version_code, Some(1));
+    assert_eq!(manifest.uses_sdk.unwrap().min_sdk_version, Some(21));
+    let permissions: Vec<_> = manifest
+        .uses_permission
+        .iter()
+        .map(|permission| permission.name.as_str())
+        .collect();
+    assert_eq!(
+        permissions,
+        ["android.permission.INTERNET", "com.android.vending.BILLING"]
+    );
+    let application = manifest.application;
+    assert_eq!(application.label.as_deref(), Some("App"));
+    assert_eq!(application.allow_backup, Some(false));
+    assert_eq!(application.activities.len(), 2);
+    assert_eq!(application.activities[0].name, "com.example.app.Main");
+    assert_eq!(
+        application.activities[1].name,
+        "com.example.billing.ProxyActivity"
+    );
+    assert_eq!(
+        application.providers[0].authorities,
+        "com.example.app.billing"
+    );
+
+    let notes: Vec<_> = merged
+        .log
+        .iter()
+        .map(|record| {
+            assert_eq!(record.severity, Severity::Note);
+            assert_eq!(record.source, library);
+            format!("{}: {}", record.element, record.message)
+        })
+        .collect();
+    assert!(notes.contains(
+        &"manifest/uses-permission#android.permission.READ_CONTACTS: removed by tools:node=\"remove\""
+            .to_owned()
+    ));
+    assert!(notes.contains(
+        &"manifest/application: attribute android:label value=(Billing) replaced by tools:replace"
+            .to_owned()
+    ));
+
+    let manifest_path = dir.path().join("AndroidManifest.xml");
+    merged.to_file(&manifest_path).unwrap();
+    let output_apk = dir.path().join("app.apk");
+    let mut replay = ReplayExecutor::new();
+    replay.expect(
+        "aapt2",
+        &[
+            "link",
+            "-o",
+            output_apk.to_str().unwrap(),
+            "--manifest",
+            manifest_path.to_str().unwrap(),
+        ],
+    );
+    let replay = Arc::new(replay);
+    with_executor(replay.clone(), || {
+        Aapt2Link::new(&[], &output_apk, &manifest_path).run()
+    })
+    .unwrap();
+    assert!(replay.remaining().is_empty());
+}
+
+#[test]
+/// Overlays have higher priority than the main manifest
+fn test_merge_overlay_and_placeholders() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = write(dir.path(), "main.xml", MAIN);
+    let overlay = write(
+        dir.path(),
+        "debug.xml",
+        r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    xmlns:tools="http://schemas.android.com/tools">
+    <application android:debuggable="true">
+        <activity android:name=".Main" tools:node="merge-only-attributes"
+            android:label="${appName}" />
+        <activity android:name=".Debug" />
+    </application>
+</manifest>"#,
+    );
+    let merged = ManifestMerger::new(&main)
+        .overlay(&overlay)
+        .placeholder("appName", "Debug App")
+        .run()
+        .unwrap();
+    let manifest = AndroidManifest::from_xml(&merged.xml).unwrap();
+    assert_eq!(manifest.package, "com.example.app");
+    let application = manifest.application;
+    assert_eq!(application.debuggable, Some(true));
+    assert_eq!(application.label.as_deref(), Some("App"));
+    let main_activity = &application.activities[0];
+    assert_eq!(main_activity.label.as_deref(), Some("Debug App"));
+    assert_eq!(main_activity.exported, Some(true));
+    // Children of the main manifest are dropped with `merge-only-attributes`
+    assert!(main_activity.intent_filters.is_empty());
+    assert_eq!(application.activities[1].name, "com.example.app.Debug");
+}
+
+#[test]
+/// Relative class names of overlays match absolute names of the main manifest
+fn test_merge_overlay_class_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = write(
+        dir.path(),
+        "main.xml",
+        r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.app">
+    <application>
+        <activity android:name="com.example.app.MainActivity" android:exported="true" />
+    </application>
+</manifest>"#,
+    );
+    let overlay = write(
+        dir.path(),
+        "debug.xml",
+        r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android">
+    <application>
+        <activity android:name=".MainActivity" android:label="Debug" />
+    </application>
+</manifest>"#,
+    );
+    let merged = ManifestMerger::new(&main).overlay(&overlay).run().unwrap();
+    let activities = AndroidManifest::from_xml(&merged.xml)
+        .unwrap()
+        .application
+        .activities;
+    assert_eq!(activities.len(), 1);
+    assert_eq!(activities[0].name, "com.example.app.MainActivity");
+    assert_eq!(activities[0].label.as_deref(), Some("Debug"));
+    assert_eq!(activities[0].exported, Some(true));
+}
+
+#[test]
+/// `tools:` annotations of the main manifest apply to libraries when an overlay is merged
+/// over it
+fn test_merge_overlay_keeps_main_annotations() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = write(
+        dir.path(),
+        "main.xml",
+        r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    xmlns:tools="http://schemas.android.com/tools"
+    package="com.example.app">
+    <uses-sdk android:minSdkVersion="21" />
+    <uses-permission android:name="com.android.vending.BILLING" tools:node="remove" />
+    <application android:label="App" tools:replace="android:label" />
+</manifest>"#,
+    );
+    let overlay = write(
+        dir.path(),
+        "debug.xml",
+        r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    xmlns:tools="http://schemas.android.com/tools">
+    <application android:debuggable="true" tools:replace="android:allowBackup"
+        android:allowBackup="true" />
+</manifest>"#,
+    );
+    let library = write(dir.path(), "library.xml", LIBRARY);
+    let merged = ManifestMerger::new(&main)
+        .overlay(&overlay)
+        .library(&library)
+        .run()
+        .unwrap();
+    assert!(!merged.xml.contains("tools"));
+    let manifest = AndroidManifest::from_xml(&merged.xml).unwrap();
+    let application = manifest.application;
+    assert_eq!(application.label.as_deref(), Some("App"));
+    assert_eq!(application.allow_backup, Some(true));
+    assert_eq!(application.debuggable, Some(true));
+    assert!(manifest
+        .uses_permission
+        .iter()
+        .all(|permission| permission.name != "com.android.vending.BILLING"));
+}
+
+#[test]
+/// Placeholder values aren't substituted again, even if they contain the placeholder
+fn test_merge_recursive_placeholder() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = write(
+        dir.path(),
+        "main.xml",
+        r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.app">
+    <application android:label="${label} (${label})" />
+</manifest>"#,
+    );
+    let merged = ManifestMerger::new(&main)
+        .placeholder("label", "My ${label}")
+        .run()
+        .unwrap();
+    let manifest = AndroidManifest::from_xml(&merged.xml).unwrap();
+    assert_eq!(
+        manifest.application.label.as_deref(),
+        Some("My ${label} (My ${label})")
+    );
+}
+
+#[test]
+fn test_merge_conflicts() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = write(
+        dir.path(),
+        "main.xml",
+        r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.app">
+    <uses-sdk android:minSdkVersion="16" />
+    <application android:label="App" android:icon="${icon}" />
+</manifest>"#,
+    );
+    let library = write(dir.path(), "library.xml", LIBRARY);
+    let err = ManifestMerger::new(&main)
+        .library(&library)
+        .run()
+        .unwrap_err();
+    let Error::ManifestMergeFailed(log) = err else {
+        panic!("unexpected error: {}", err);
+    };
+    let source = library.display().to_string();
+    let lines: Vec<_> = log.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            format!(
+                "{}: error: manifest/uses-sdk: minSdkVersion 16 cannot be smaller than version \
+                 19 declared in library",
+                source
+            ),
+            format!(
+                "{}: error: manifest/application: attribute android:label value=(App) is also \
+                 present with value=(Billing). Add tools:replace=\"android:label\" to \
+                 <application> element to override it",
+                source
+            ),
+            format!(
+                "{}: error: manifest/application: attribute android:icon requires a value for \
+                 placeholder ${{icon}}",
+                main.display()
+            ),
+        ]
+    );
+}
+
+#[test]
+/// `uses-sdk` of libraries is never copied into the app, which has `minSdkVersion` 1
+/// without it
+fn test_merge_library_uses_sdk() {
+    let dir = tempfile::tempdir().unwrap();
+    let main = write(
+        dir.path(),
+        "main.xml",
+        r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.app" />"#,
+    );
+    let library = write(
+        dir.path(),
+        "library.xml",
+        r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.lib">
+    <uses-sdk android:targetSdkVersion="30" />
+</manifest>"#,
+    );
+    let merged = ManifestMerger::new(&main).library(&library).run().unwrap();
+    assert!(AndroidManifest::from_xml(&merged.xml)
+        .unwrap()
+        .uses_sdk
+        .is_none());
+
+    let library = write(dir.path(), "billing.xml", LIBRARY);
+    let err = ManifestMerger::new(&main)
+        .library(&library)
+        .run()
+        .unwrap_err();
+    let Error::ManifestMergeFailed(log) = err else {
+        panic!("unexpected error: {}", err);
+    };
+    assert_eq!(
+        log,
+        format!(
+            "{}: error: manifest/uses-sdk: minSdkVersion 1 cannot be smaller than version 19 \
+             declared in library",
+            library.display()
+        )
+    );
+}
+
+#[test]
+/// Preview codenames are newer than API levels, placeholders can't be checked
+fn test_merge_min_sdk_codename() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = |min_sdk: &str| {
+        format!(
+            r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android"
+    package="com.example.{}">
+    <uses-sdk android:minSdkVersion="{}" />
+</manifest>"#,
+            min_sdk.to_lowercase().replace(['$', '{', '}'], ""),
+            min_sdk
+        )
+    };
+    let merge = |app: &str, library: &str| {
+        let main = write(dir.path(), "main.xml", &manifest(app));
+        let library = write(dir.path(), "library.xml", &manifest(library));
+        ManifestMerger::new(&main)
+            .library(&library)
+            .placeholder("minSdk", "21")
+            .run()
+    };
+    merge("UpsideDownCake", "19").unwrap();
+    merge("UpsideDownCake", "UpsideDownCake").unwrap();
+    let merged = merge("${minSdk}", "19").unwrap();
+    assert_eq!(merged.log[0].severity, Severity::Warning);
+    assert_eq!(
+        merged.log[0].message,
+        "minSdkVersion ${minSdk} of the app can't be checked against version 19 declared in \
+         library"
+    );
+    let Err(Error::ManifestMergeFailed(log)) = merge("33", "UpsideDownCake") else {
+        panic!("codename of the library should require the same codename");
+    };
+    assert!(log.ends_with(
+        "minSdkVersion 33 cannot be different than version UpsideDownCake declared in library"
+    ));
+}
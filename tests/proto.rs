@@ -0,0 +1,157 @@
+#![cfg(feature = "proto")]
+
+use android_tools::{
+    axml::{ANDROID_NS, ATTR_VERSION_CODE},
+    proto::{
+        item, primitive::OneofValue, read_message, value, write_message, ConfigValue,
+        Configuration, Entry, Item, Message, Package, PackageId, ResourceTable, Type, Value,
+        XmlAttribute, XmlElement, XmlNamespace, XmlNode,
+    },
+};
+
+fn element(name: &str, attribute: Vec<XmlAttribute>, child: Vec<XmlNode>) -> XmlNode {
+    XmlNode {
+        node: Some(android_tools::proto::xml_node::Node::Element(XmlElement {
+            name: name.to_owned(),
+            attribute,
+            child,
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+}
+
+fn string_value(value: &str) -> ConfigValue {
+    ConfigValue {
+        value: Some(Value {
+            value: Some(value::Value::Item(Item::string(value))),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_wire_format() {
+    let attribute = XmlAttribute {
+        name: "a".to_owned(),
+        ..Default::default()
+    };
+    assert_eq!(attribute.encode_to_vec(), [0x12, 0x01, b'a']);
+    let item = Item::primitive(OneofValue::IntDecimalValue(2));
+    // Item.prim { Primitive.int_decimal_value = 2 }
+    assert_eq!(item.encode_to_vec(), [0x3a, 0x02, 0x30, 0x02]);
+    assert_eq!(
+        Item::decode([0x3a, 0x02, 0x30, 0x02].as_slice()).unwrap(),
+        item
+    );
+    assert!(XmlNode::decode([0x0a, 0x05, 0x00].as_slice()).is_err());
+}
+
+#[test]
+fn test_patch_manifest() {
+    let mut manifest = element(
+        "manifest",
+        vec![XmlAttribute {
+            name: "package".to_owned(),
+            value: "com.example".to_owned(),
+            ..Default::default()
+        }],
+        vec![element("application", Vec::new(), Vec::new())],
+    );
+    let root = manifest.element_mut().unwrap();
+    root.namespace_declaration.push(XmlNamespace {
+        prefix: "android".to_owned(),
+        uri: ANDROID_NS.to_owned(),
+        ..Default::default()
+    });
+    root.set_attribute(XmlAttribute::android(
+        "versionCode",
+        ATTR_VERSION_CODE,
+        "1",
+        Item::primitive(OneofValue::IntDecimalValue(1)),
+    ));
+    root.set_attribute(XmlAttribute::android(
+        "versionCode",
+        ATTR_VERSION_CODE,
+        "2",
+        Item::primitive(OneofValue::IntDecimalValue(2)),
+    ));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("AndroidManifest.xml");
+    write_message(&path, &manifest).unwrap();
+    let decoded: XmlNode = read_message(&path).unwrap();
+    assert_eq!(decoded, manifest);
+    let root = decoded.element().unwrap();
+    assert_eq!(root.attribute.len(), 2);
+    let version_code = root.attribute("versionCode").unwrap();
+    assert_eq!(version_code.value, "2");
+    assert_eq!(version_code.namespace_uri, ANDROID_NS);
+    assert!(root.child_element("application").is_some());
+    assert_eq!(root.elements().count(), 1);
+}
+
+#[test]
+fn test_resource_table() {
+    let mut table = ResourceTable {
+        package: vec![Package {
+            package_id: Some(PackageId { id: 0x7f }),
+            package_name: "com.example".to_owned(),
+            r#type: vec![Type {
+                name: "string".to_owned(),
+                entry: vec![Entry {
+                    name: "app_name".to_owned(),
+                    config_value: vec![string_value("My App")],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }],
+        ..Default::default()
+    };
+    let mut localized = string_value("Mon App");
+    localized.config = Some(Configuration {
+        locale: "fr".to_owned(),
+        ..Default::default()
+    });
+    table
+        .package_mut("com.example")
+        .unwrap()
+        .entry_mut("string", "app_name")
+        .unwrap()
+        .config_value
+        .push(localized);
+
+    let decoded = ResourceTable::decode(table.encode_to_vec().as_slice()).unwrap();
+    assert_eq!(decoded, table);
+    let entry = decoded
+        .package("com.example")
+        .unwrap()
+        .entry("string", "app_name")
+        .unwrap();
+    let values: Vec<_> = entry
+        .config_value
+        .iter()
+        .map(|config_value| {
+            let locale = config_value
+                .config
+                .as_ref()
+                .map(|config| config.locale.clone())
+                .unwrap_or_default();
+            match &config_value.value.as_ref().unwrap().value {
+                Some(value::Value::Item(Item {
+                    value: Some(item::Value::Str(string)),
+                })) => (locale, string.value.clone()),
+                _ => panic!("unexpected value"),
+            }
+        })
+        .collect();
+    assert_eq!(
+        values,
+        [
+            (String::new(), "My App".to_owned()),
+            ("fr".to_owned(), "Mon App".to_owned())
+        ]
+    );
+}
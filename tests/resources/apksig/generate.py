@@ -0,0 +1,175 @@
+#!/usr/bin/env python3
+"""Generates APKs signed with APK Signature Scheme v2 and v3 for tests/apksig.rs.
+
+The blocks are laid out like apksigner lays them out: v2 signers carry the stripping
+protection attribute, v3 signers apply to SDK 28 and later and the APK Signing Block
+is padded to 4096 bytes. Requires the `cryptography` package.
+
+    python3 tests/resources/apksig/generate.py
+"""
+
+import datetime
+import hashlib
+import io
+import os
+import struct
+import zipfile
+
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec, padding, rsa
+from cryptography.x509.oid import NameOID
+
+V2_BLOCK_ID = 0x7109871A
+V3_BLOCK_ID = 0xF05368C0
+PADDING_BLOCK_ID = 0x42726577
+STRIPPING_PROTECTION_ATTR_ID = 0xBEEFF00D
+CHUNK_SIZE = 1024 * 1024
+
+RSA_PSS_WITH_SHA512 = 0x0102
+RSA_PKCS1_V1_5_WITH_SHA512 = 0x0104
+ECDSA_WITH_SHA256 = 0x0201
+
+
+def u32(value):
+    return struct.pack("<I", value)
+
+
+def prefixed(value):
+    return u32(len(value)) + value
+
+
+def sequence(items):
+    return prefixed(b"".join(prefixed(item) for item in items))
+
+
+def unsigned_apk():
+    apk = io.BytesIO()
+    with zipfile.ZipFile(apk, "w") as archive:
+        for name, data, compression in [
+            ("AndroidManifest.xml", b'<manifest package="com.example.fixture"/>', zipfile.ZIP_STORED),
+            ("classes.dex", bytes(range(256)) * 64, zipfile.ZIP_DEFLATED),
+        ]:
+            info = zipfile.ZipInfo(name, date_time=(2024, 1, 1, 0, 0, 0))
+            info.compress_type = compression
+            archive.writestr(info, data)
+    return apk.getvalue()
+
+
+def zip_sections(apk):
+    eocd = apk.rindex(b"PK\x05\x06")
+    cd_size, cd_offset = struct.unpack("<II", apk[eocd + 12 : eocd + 20])
+    return cd_offset, apk[cd_offset : cd_offset + cd_size], apk[eocd:]
+
+
+def content_digest(apk, hash_name):
+    cd_offset, central_directory, eocd = zip_sections(apk)
+    chunks = []
+    for section in [apk[:cd_offset], central_directory, eocd]:
+        chunks += [section[i : i + CHUNK_SIZE] for i in range(0, len(section), CHUNK_SIZE)]
+    digest = hashlib.new(hash_name, b"\x5a" + u32(len(chunks)))
+    for chunk in chunks:
+        digest.update(hashlib.new(hash_name, b"\xa5" + u32(len(chunk)) + chunk).digest())
+    return digest.digest()
+
+
+def certificate(key, subject):
+    name = x509.Name([x509.NameAttribute(NameOID.COMMON_NAME, subject)])
+    now = datetime.datetime(2024, 1, 1, tzinfo=datetime.timezone.utc)
+    return (
+        x509.CertificateBuilder()
+        .subject_name(name)
+        .issuer_name(name)
+        .public_key(key.public_key())
+        .serial_number(1)
+        .not_valid_before(now)
+        .not_valid_after(now + datetime.timedelta(days=10000))
+        .sign(key, hashes.SHA256())
+        .public_bytes(serialization.Encoding.DER)
+    )
+
+
+def sign(key, algorithm, data):
+    if algorithm == RSA_PSS_WITH_SHA512:
+        pss = padding.PSS(mgf=padding.MGF1(hashes.SHA512()), salt_length=64)
+        return key.sign(data, pss, hashes.SHA512())
+    if algorithm == RSA_PKCS1_V1_5_WITH_SHA512:
+        return key.sign(data, padding.PKCS1v15(), hashes.SHA512())
+    if algorithm == ECDSA_WITH_SHA256:
+        return key.sign(data, ec.ECDSA(hashes.SHA256()))
+    raise ValueError(algorithm)
+
+
+def signer(key, cert, algorithm, digest, v3, stripping_protection=False):
+    digests = sequence([u32(algorithm) + prefixed(digest)])
+    certificates = sequence([cert])
+    sdk_versions = u32(28) + u32(0x7FFFFFFF) if v3 else b""
+    # v2 signer requires v3 signature when the APK has one
+    attributes = sequence([u32(STRIPPING_PROTECTION_ATTR_ID) + u32(3)] if stripping_protection else [])
+    signed_data = digests + certificates + sdk_versions + attributes
+    signatures = sequence([u32(algorithm) + prefixed(sign(key, algorithm, signed_data))])
+    public_key = key.public_key().public_bytes(
+        serialization.Encoding.DER, serialization.PublicFormat.SubjectPublicKeyInfo
+    )
+    return prefixed(signed_data) + sdk_versions + signatures + prefixed(public_key)
+
+
+def signing_block(pairs):
+    encoded = b"".join(struct.pack("<Q", len(value) + 4) + u32(id) + value for id, value in pairs)
+    size = 8 + len(encoded) + 8 + 16
+    if size % 4096 != 0:
+        padding_len = 4096 - size % 4096
+        if padding_len < 12:
+            padding_len += 4096
+        encoded += struct.pack("<Q", padding_len - 8) + u32(PADDING_BLOCK_ID)
+        encoded += bytes(padding_len - 12)
+    size_field = struct.pack("<Q", len(encoded) + 8 + 16)
+    return size_field + encoded + size_field + b"APK Sig Block 42"
+
+
+def signed_apk(key, subject, algorithm, schemes):
+    apk = unsigned_apk()
+    hash_name = "sha512" if algorithm in (RSA_PSS_WITH_SHA512, RSA_PKCS1_V1_5_WITH_SHA512) else "sha256"
+    digest = content_digest(apk, hash_name)
+    cert = certificate(key, subject)
+    pairs = []
+    if 2 in schemes:
+        v2 = signer(key, cert, algorithm, digest, False, 3 in schemes)
+        pairs.append((V2_BLOCK_ID, sequence([v2])))
+    if 3 in schemes:
+        pairs.append((V3_BLOCK_ID, sequence([signer(key, cert, algorithm, digest, True)])))
+    block = signing_block(pairs)
+    cd_offset, central_directory, eocd = zip_sections(apk)
+    eocd = eocd[:16] + u32(cd_offset + len(block)) + eocd[20:]
+    return apk[:cd_offset] + block + central_directory + eocd
+
+
+def main():
+    directory = os.path.dirname(os.path.abspath(__file__))
+    fixtures = {
+        "rsa4096_v2_v3.apk": (
+            rsa.generate_private_key(public_exponent=65537, key_size=4096),
+            "RSA 4096",
+            RSA_PKCS1_V1_5_WITH_SHA512,
+            (2, 3),
+        ),
+        "rsa_pss_v2.apk": (
+            rsa.generate_private_key(public_exponent=65537, key_size=2048),
+            "RSA PSS",
+            RSA_PSS_WITH_SHA512,
+            (2,),
+        ),
+        "ec_p256_v2_v3.apk": (
+            ec.generate_private_key(ec.SECP256R1()),
+            "EC P-256",
+            ECDSA_WITH_SHA256,
+            (2, 3),
+        ),
+    }
+    for name, (key, subject, algorithm, schemes) in fixtures.items():
+        with open(os.path.join(directory, name), "wb") as apk:
+            apk.write(signed_apk(key, subject, algorithm, schemes))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,178 @@
+#!/usr/bin/env python3
+"""Generates resources.arsc for tests/arsc.rs.
+
+Chunks are laid out like aapt2 flattens the resource table of `com.example`: UTF-8
+value and key pools, UTF-16 type pool, 288 bytes package header, type spec followed
+by the types of every configuration, densities qualified with `-v4` and the `fr`
+strings written sparse like with `--enable-sparse-encoding`. The resources are:
+
+    color/accent         @android:color/black
+    drawable/icon        res/drawable-{hdpi,xhdpi,xxhdpi}-v4/icon.png
+    string/app_name      My App, Mon App in fr
+    string/brand         @string/app_name
+    style/AppTheme       parent @android:style/Theme.Material, textColor #ff0000ff
+
+    python3 tests/resources/arsc/generate.py
+"""
+
+import os
+import struct
+
+RES_STRING_POOL_TYPE = 0x0001
+RES_TABLE_TYPE = 0x0002
+RES_TABLE_PACKAGE_TYPE = 0x0200
+RES_TABLE_TYPE_TYPE = 0x0201
+RES_TABLE_TYPE_SPEC_TYPE = 0x0202
+UTF8_FLAG = 1 << 8
+NO_ENTRY = 0xFFFFFFFF
+TYPE_FLAG_SPARSE = 0x01
+ENTRY_FLAG_COMPLEX = 0x0001
+SPEC_PUBLIC = 0x40000000
+CONFIG_LOCALE = 0x0004
+CONFIG_DENSITY = 0x0100
+
+TYPE_REFERENCE = 0x01
+TYPE_STRING = 0x03
+TYPE_INT_COLOR_ARGB8 = 0x1C
+
+ANDROID_COLOR_BLACK = 0x0106000C
+ANDROID_STYLE_THEME_MATERIAL = 0x01030224
+ANDROID_ATTR_TEXT_COLOR = 0x01010098
+PACKAGE_ID = 0x7F
+
+
+def chunk(chunk_type, header, body):
+    header_size = 8 + len(header)
+    return struct.pack("<HHI", chunk_type, header_size, header_size + len(body)) + header + body
+
+
+def utf8_length(value):
+    if value < 0x80:
+        return bytes([value])
+    return bytes([0x80 | value >> 8, value & 0xFF])
+
+
+def string_pool(strings, utf8):
+    offsets = b""
+    data = b""
+    for value in strings:
+        offsets += struct.pack("<I", len(data))
+        if utf8:
+            encoded = value.encode("utf-8")
+            data += utf8_length(len(value.encode("utf-16-le")) // 2) + utf8_length(len(encoded))
+            data += encoded + b"\0"
+        else:
+            encoded = value.encode("utf-16-le")
+            data += struct.pack("<H", len(encoded) // 2) + encoded + b"\0\0"
+    data += bytes(-len(data) % 4)
+    flags = UTF8_FLAG if utf8 else 0
+    header = struct.pack("<IIIII", len(strings), 0, flags, 28 + len(offsets), 0)
+    return chunk(RES_STRING_POOL_TYPE, header, offsets + data)
+
+
+def config(language="", density=0, sdk_version=0):
+    data = bytearray(64)
+    struct.pack_into("<I", data, 0, 64)
+    data[8 : 8 + len(language)] = language.encode()
+    struct.pack_into("<H", data, 14, density)
+    struct.pack_into("<H", data, 24, sdk_version)
+    return bytes(data)
+
+
+def simple(key, data_type, data):
+    return struct.pack("<HHI", 8, 0, key) + struct.pack("<HBBI", 8, 0, data_type, data)
+
+
+def complex_entry(key, parent, values):
+    entry = struct.pack("<HHIII", 16, ENTRY_FLAG_COMPLEX, key, parent, len(values))
+    for name, data_type, data in values:
+        entry += struct.pack("<I", name) + struct.pack("<HBBI", 8, 0, data_type, data)
+    return entry
+
+
+def type_chunk(type_id, type_config, entries, sparse=False):
+    offsets = b""
+    data = b""
+    for index, entry in enumerate(entries):
+        if entry is None:
+            if not sparse:
+                offsets += struct.pack("<I", NO_ENTRY)
+            continue
+        if sparse:
+            offsets += struct.pack("<HH", index, len(data) // 4)
+        else:
+            offsets += struct.pack("<I", len(data))
+        data += entry
+    count = len(offsets) // 4 if sparse else len(entries)
+    entries_start = 8 + 12 + len(type_config) + len(offsets)
+    flags = TYPE_FLAG_SPARSE if sparse else 0
+    header = struct.pack("<BBHII", type_id, flags, 0, count, entries_start) + type_config
+    return chunk(RES_TABLE_TYPE_TYPE, header, offsets + data)
+
+
+def type_spec(type_id, flags):
+    header = struct.pack("<BBHI", type_id, 0, 0, len(flags))
+    return chunk(RES_TABLE_TYPE_SPEC_TYPE, header, b"".join(struct.pack("<I", flag) for flag in flags))
+
+
+def main():
+    values = [
+        "My App",
+        "Mon App",
+        "res/drawable-hdpi-v4/icon.png",
+        "res/drawable-xhdpi-v4/icon.png",
+        "res/drawable-xxhdpi-v4/icon.png",
+    ]
+    types = ["color", "drawable", "string", "style"]
+    keys = ["accent", "icon", "app_name", "brand", "AppTheme"]
+    key = keys.index
+    value = values.index
+    string_id = lambda entry: PACKAGE_ID << 24 | (types.index("string") + 1) << 16 | entry
+
+    package_body = [
+        string_pool(types, utf8=False),
+        string_pool(keys, utf8=True),
+        type_spec(1, [SPEC_PUBLIC]),
+        type_chunk(1, config(), [simple(key("accent"), TYPE_REFERENCE, ANDROID_COLOR_BLACK)]),
+        type_spec(2, [CONFIG_DENSITY]),
+    ]
+    for density, path in [(240, values[2]), (320, values[3]), (480, values[4])]:
+        entry = simple(key("icon"), TYPE_STRING, value(path))
+        package_body.append(type_chunk(2, config(density=density, sdk_version=4), [entry]))
+    package_body += [
+        type_spec(3, [CONFIG_LOCALE, 0]),
+        type_chunk(
+            3,
+            config(),
+            [
+                simple(key("app_name"), TYPE_STRING, value("My App")),
+                simple(key("brand"), TYPE_REFERENCE, string_id(0)),
+            ],
+        ),
+        type_chunk(3, config("fr"), [simple(key("app_name"), TYPE_STRING, value("Mon App")), None], True),
+        type_spec(4, [0]),
+        type_chunk(
+            4,
+            config(),
+            [
+                complex_entry(
+                    key("AppTheme"),
+                    ANDROID_STYLE_THEME_MATERIAL,
+                    [(ANDROID_ATTR_TEXT_COLOR, TYPE_INT_COLOR_ARGB8, 0xFF0000FF)],
+                )
+            ],
+        ),
+    ]
+    type_strings = package_body[0]
+    name = "com.example".encode("utf-16-le").ljust(256, b"\0")
+    package_header = struct.pack("<I", PACKAGE_ID) + name
+    package_header += struct.pack("<IIIII", 288, len(types), 288 + len(type_strings), len(keys), 0)
+    package = chunk(RES_TABLE_PACKAGE_TYPE, package_header, b"".join(package_body))
+    table = chunk(RES_TABLE_TYPE, struct.pack("<I", 1), string_pool(values, utf8=True) + package)
+    directory = os.path.dirname(os.path.abspath(__file__))
+    with open(os.path.join(directory, "resources.arsc"), "wb") as arsc:
+        arsc.write(table)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,211 @@
+#!/usr/bin/env python3
+"""Generates binary AndroidManifest.xml for tests/axml.rs.
+
+Chunks are laid out like aapt2 flattens XML: UTF-8 string pool starting with the
+names of attributes with resource IDs in the order of the resource map, attributes
+sorted by resource ID, compiled values without raw values except strings and
+namespace nodes around the root element. The source is:
+
+    <manifest xmlns:android="http://schemas.android.com/apk/res/android"
+        android:versionCode="1" android:versionName="1.0"
+        android:compileSdkVersion="34" package="com.example.fixture">
+        <uses-sdk android:minSdkVersion="21" android:targetSdkVersion="34" />
+        <uses-permission android:name="android.permission.INTERNET" />
+        <application android:label="@string/app_name" android:icon="@mipmap/ic_launcher"
+            android:debuggable="true">
+            <activity android:name="com.example.fixture.MainActivity"
+                android:exported="true">
+                <intent-filter>
+                    <action android:name="android.intent.action.MAIN" />
+                    <category android:name="android.intent.category.LAUNCHER" />
+                </intent-filter>
+            </activity>
+        </application>
+    </manifest>
+
+    python3 tests/resources/axml/generate.py
+"""
+
+import os
+import struct
+
+ANDROID_NS = "http://schemas.android.com/apk/res/android"
+NO_INDEX = 0xFFFFFFFF
+
+RES_STRING_POOL_TYPE = 0x0001
+RES_XML_TYPE = 0x0003
+RES_XML_START_NAMESPACE_TYPE = 0x0100
+RES_XML_END_NAMESPACE_TYPE = 0x0101
+RES_XML_START_ELEMENT_TYPE = 0x0102
+RES_XML_END_ELEMENT_TYPE = 0x0103
+RES_XML_RESOURCE_MAP_TYPE = 0x0180
+UTF8_FLAG = 1 << 8
+
+TYPE_REFERENCE = 0x01
+TYPE_STRING = 0x03
+TYPE_INT_DEC = 0x10
+TYPE_INT_BOOLEAN = 0x12
+
+ATTRIBUTE_IDS = {
+    "label": 0x01010001,
+    "icon": 0x01010002,
+    "name": 0x01010003,
+    "debuggable": 0x0101000F,
+    "exported": 0x01010010,
+    "minSdkVersion": 0x0101020C,
+    "versionCode": 0x0101021B,
+    "versionName": 0x0101021C,
+    "targetSdkVersion": 0x01010270,
+    "compileSdkVersion": 0x01010572,
+}
+
+
+def string(value):
+    return (TYPE_STRING, value)
+
+
+def integer(value):
+    return (TYPE_INT_DEC, value)
+
+
+def boolean(value):
+    return (TYPE_INT_BOOLEAN, 0xFFFFFFFF if value else 0)
+
+
+def reference(value):
+    return (TYPE_REFERENCE, value)
+
+
+# Elements as (line, name, attributes, children), attributes as (android, name, value)
+MANIFEST = (
+    2,
+    "manifest",
+    [
+        (True, "versionCode", integer(1)),
+        (True, "versionName", string("1.0")),
+        (True, "compileSdkVersion", integer(34)),
+        (False, "package", string("com.example.fixture")),
+    ],
+    [
+        (5, "uses-sdk", [(True, "minSdkVersion", integer(21)), (True, "targetSdkVersion", integer(34))], []),
+        (6, "uses-permission", [(True, "name", string("android.permission.INTERNET"))], []),
+        (
+            7,
+            "application",
+            [
+                (True, "label", reference(0x7F0B0000)),
+                (True, "icon", reference(0x7F0A0000)),
+                (True, "debuggable", boolean(True)),
+            ],
+            [
+                (
+                    9,
+                    "activity",
+                    [
+                        (True, "name", string("com.example.fixture.MainActivity")),
+                        (True, "exported", boolean(True)),
+                    ],
+                    [
+                        (
+                            11,
+                            "intent-filter",
+                            [],
+                            [
+                                (12, "action", [(True, "name", string("android.intent.action.MAIN"))], []),
+                                (13, "category", [(True, "name", string("android.intent.category.LAUNCHER"))], []),
+                            ],
+                        )
+                    ],
+                )
+            ],
+        ),
+    ],
+)
+
+
+def chunk(chunk_type, header, body):
+    header_size = 8 + len(header)
+    return struct.pack("<HHI", chunk_type, header_size, header_size + len(body)) + header + body
+
+
+class Pool:
+    def __init__(self, attribute_names):
+        self.strings = list(attribute_names)
+
+    def index(self, value):
+        if value not in self.strings:
+            self.strings.append(value)
+        return self.strings.index(value)
+
+    def flatten(self):
+        offsets = b""
+        data = b""
+        for value in self.strings:
+            offsets += struct.pack("<I", len(data))
+            encoded = value.encode("utf-8")
+            data += length(len(value.encode("utf-16-le")) // 2) + length(len(encoded)) + encoded + b"\0"
+        data += bytes(-len(data) % 4)
+        header = struct.pack("<IIIII", len(self.strings), 0, UTF8_FLAG, 28 + len(offsets), 0)
+        return chunk(RES_STRING_POOL_TYPE, header, offsets + data)
+
+
+def length(value):
+    if value < 0x80:
+        return bytes([value])
+    return bytes([0x80 | value >> 8, value & 0xFF])
+
+
+def collect_attribute_names(element, names):
+    _, _, attributes, children = element
+    for android, name, _ in attributes:
+        if android:
+            names.add(name)
+    for child in children:
+        collect_attribute_names(child, names)
+
+
+def flatten_element(element, pool, android, out):
+    line, name, attributes, children = element
+    # Attributes with resource IDs first, sorted by ID
+    attributes = sorted(
+        attributes,
+        key=lambda attribute: (not attribute[0], ATTRIBUTE_IDS.get(attribute[1], 0), attribute[1]),
+    )
+    encoded = b""
+    for is_android, attribute, (data_type, value) in attributes:
+        raw = NO_INDEX
+        if data_type == TYPE_STRING:
+            raw = value = pool.index(value)
+        namespace = android if is_android else NO_INDEX
+        encoded += struct.pack("<III", namespace, pool.index(attribute), raw)
+        encoded += struct.pack("<HBBI", 8, 0, data_type, value)
+    name_index = pool.index(name)
+    body = struct.pack("<IIHHHHHH", NO_INDEX, name_index, 20, 20, len(attributes), 0, 0, 0)
+    out.append(chunk(RES_XML_START_ELEMENT_TYPE, struct.pack("<II", line, NO_INDEX), body + encoded))
+    for child in children:
+        flatten_element(child, pool, android, out)
+    end = struct.pack("<II", NO_INDEX, name_index)
+    out.append(chunk(RES_XML_END_ELEMENT_TYPE, struct.pack("<II", line, NO_INDEX), end))
+
+
+def main():
+    names = set()
+    collect_attribute_names(MANIFEST, names)
+    names = sorted(names, key=ATTRIBUTE_IDS.get)
+    pool = Pool(names)
+    resource_map = chunk(
+        RES_XML_RESOURCE_MAP_TYPE, b"", b"".join(struct.pack("<I", ATTRIBUTE_IDS[name]) for name in names)
+    )
+    prefix, uri = pool.index("android"), pool.index(ANDROID_NS)
+    namespace = struct.pack("<II", prefix, uri)
+    nodes = [chunk(RES_XML_START_NAMESPACE_TYPE, struct.pack("<II", 2, NO_INDEX), namespace)]
+    flatten_element(MANIFEST, pool, uri, nodes)
+    nodes.append(chunk(RES_XML_END_NAMESPACE_TYPE, struct.pack("<II", 2, NO_INDEX), namespace))
+    xml = chunk(RES_XML_TYPE, b"", pool.flatten() + resource_map + b"".join(nodes))
+    directory = os.path.dirname(os.path.abspath(__file__))
+    with open(os.path.join(directory, "AndroidManifest.xml"), "wb") as manifest:
+        manifest.write(xml)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,118 @@
+use android_tools::{
+    error::Error,
+    zip_align::{ZipAlign, PAGE_SIZE_16K, PAGE_SIZE_4K},
+};
+use std::io::{Cursor, Read, Write};
+use zip::{write::FileOptions, CompressionMethod};
+
+/// Creates APK with misaligned uncompressed entries
+fn unaligned_apk() -> Vec<u8> {
+    let mut apk = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    apk.start_file("AndroidManifest.xml", FileOptions::default())
+        .unwrap();
+    apk.write_all(b"<manifest package=\"com.example\"/>")
+        .unwrap();
+    apk.start_file("resources.arsc", stored).unwrap();
+    apk.write_all(&[1; 1001]).unwrap();
+    apk.start_file("lib/arm64-v8a/libmain.so", stored).unwrap();
+    apk.write_all(&[2; 5000]).unwrap();
+    apk.start_file("res/raw/a.bin", stored).unwrap();
+    apk.write_all(&[3; 3]).unwrap();
+    apk.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_align_apk() {
+    let apk = unaligned_apk();
+    let misaligned = ZipAlign::new().check_alignment_bytes(&apk).unwrap();
+    let names: Vec<_> = misaligned.iter().map(|entry| entry.name.as_str()).collect();
+    assert!(names.contains(&"lib/arm64-v8a/libmain.so"));
+    let so = misaligned
+        .iter()
+        .find(|entry| entry.name == "lib/arm64-v8a/libmain.so")
+        .unwrap();
+    assert_eq!(so.alignment, PAGE_SIZE_16K);
+
+    let aligned = ZipAlign::new().align_bytes(&apk).unwrap();
+    assert_eq!(ZipAlign::new().check_alignment_bytes(&aligned).unwrap(), []);
+    // 16 KB aligned libraries are 4 KB aligned too
+    assert_eq!(
+        ZipAlign::new()
+            .page_size(Some(PAGE_SIZE_4K))
+            .check_alignment_bytes(&aligned)
+            .unwrap(),
+        []
+    );
+
+    // Contents are unchanged
+    let mut archive = zip::ZipArchive::new(Cursor::new(&aligned)).unwrap();
+    let mut so = Vec::new();
+    archive
+        .by_name("lib/arm64-v8a/libmain.so")
+        .unwrap()
+        .read_to_end(&mut so)
+        .unwrap();
+    assert_eq!(so, [2; 5000]);
+    let mut manifest = String::new();
+    archive
+        .by_name("AndroidManifest.xml")
+        .unwrap()
+        .read_to_string(&mut manifest)
+        .unwrap();
+    assert_eq!(manifest, "<manifest package=\"com.example\"/>");
+
+    // Aligning again replaces the padding instead of adding more
+    let realigned = ZipAlign::new().align_bytes(&aligned).unwrap();
+    assert_eq!(realigned, aligned);
+
+    // Switching to 4 KB pages shrinks the padding
+    let aligned_4k = ZipAlign::new()
+        .page_size(Some(PAGE_SIZE_4K))
+        .align_bytes(&aligned)
+        .unwrap();
+    assert!(aligned_4k.len() < aligned.len());
+    assert_eq!(
+        ZipAlign::new()
+            .page_size(Some(PAGE_SIZE_4K))
+            .check_alignment_bytes(&aligned_4k)
+            .unwrap(),
+        []
+    );
+}
+
+#[test]
+fn test_invalid_alignment() {
+    assert!(ZipAlign::new()
+        .alignment(3)
+        .align_bytes(&unaligned_apk())
+        .is_err());
+    assert!(ZipAlign::new().align_bytes(b"not a zip").is_err());
+}
+
+#[test]
+/// Name length of the local header that differs from the central directory is reported
+/// instead of misreading the header
+fn test_align_mismatched_local_header() {
+    let mut apk = unaligned_apk();
+    let name = b"res/raw/a.bin";
+    let local_name = apk
+        .windows(name.len())
+        .position(|window| window == name)
+        .unwrap();
+    // Moves the last 4 bytes of the name into the extra field
+    let local_header = local_name - 30;
+    apk[local_header + 26..local_header + 28].copy_from_slice(&9u16.to_le_bytes());
+    apk[local_header + 28..local_header + 30].copy_from_slice(&4u16.to_le_bytes());
+    assert!(matches!(
+        ZipAlign::new().align_bytes(&apk),
+        Err(Error::InvalidApk(_))
+    ));
+
+    // Local header without the name
+    apk[local_header + 26..local_header + 30].copy_from_slice(&[0; 4]);
+    assert!(matches!(
+        ZipAlign::new().align_bytes(&apk),
+        Err(Error::InvalidApk(_))
+    ));
+}